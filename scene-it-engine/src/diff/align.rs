@@ -0,0 +1,78 @@
+/// One step in the alignment of two sequences.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Step {
+    /// The item at this index in the old sequence pairs with the one at this
+    /// index in the new sequence.
+    Both(usize, usize),
+    /// The item at this index is only in the old sequence.
+    Old(usize),
+    /// The item at this index is only in the new sequence.
+    New(usize),
+}
+
+/// Aligns two sequences in order, pairing items so that the total score of
+/// the pairs is as high as possible.
+///
+/// Pairs scoring `0` are never made. With a score of `1` for equal items and
+/// `0` otherwise this is the longest common subsequence. Where an item could
+/// be dropped from either side, the old item comes first, so removals are
+/// listed before the insertions that replace them.
+pub(crate) fn align<T>(old: &[T], new: &[T], score: impl Fn(&T, &T) -> u32) -> Vec<Step> {
+    let (rows, columns) = (old.len(), new.len());
+    let mut best = vec![vec![0u32; columns + 1]; rows + 1];
+    for i in (0..rows).rev() {
+        for j in (0..columns).rev() {
+            let paired = match score(&old[i], &new[j]) {
+                0 => 0,
+                points => points + best[i + 1][j + 1],
+            };
+            best[i][j] = paired.max(best[i + 1][j]).max(best[i][j + 1]);
+        }
+    }
+
+    let mut steps = Vec::with_capacity(rows.max(columns));
+    let (mut i, mut j) = (0, 0);
+    while i < rows && j < columns {
+        let points = score(&old[i], &new[j]);
+        if points > 0 && best[i][j] == points + best[i + 1][j + 1] {
+            steps.push(Step::Both(i, j));
+            i += 1;
+            j += 1;
+        } else if best[i][j] == best[i + 1][j] {
+            steps.push(Step::Old(i));
+            i += 1;
+        } else {
+            steps.push(Step::New(j));
+            j += 1;
+        }
+    }
+    steps.extend((i..rows).map(Step::Old));
+    steps.extend((j..columns).map(Step::New));
+
+    steps
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::diff::align::{Step, align};
+
+    #[test]
+    fn test_alignment_keeps_the_longest_common_subsequence() {
+        // ARRANGE
+        let old = ["a", "b", "c", "d"];
+        let new = ["a", "c", "e", "d"];
+        // ACT
+        let steps = align(&old, &new, |a, b| u32::from(a == b));
+        // ASSERT
+        assert_eq!(
+            steps,
+            vec![
+                Step::Both(0, 0),
+                Step::Old(1),
+                Step::Both(2, 1),
+                Step::New(2),
+                Step::Both(3, 3),
+            ]
+        );
+    }
+}
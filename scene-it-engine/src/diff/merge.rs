@@ -0,0 +1,1251 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::models::{
+    Author, Character, Cut, Dialogue, HasMetadata, Id, Narrative, NarrativeError, Relationship,
+    Scene, SceneElement, SceneGraphError, SceneVariant, Storyboard,
+};
+
+/// One of the two edited copies in a merge.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Side {
+    Ours,
+    Theirs,
+}
+
+impl Side {
+    fn other(self) -> Side {
+        match self {
+            Side::Ours => Side::Theirs,
+            Side::Theirs => Side::Ours,
+        }
+    }
+}
+
+/// Where in the storyboard a conflict lies.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ConflictLocation {
+    Title,
+    Summary,
+    Template,
+    Author(Id<Author>),
+    Character(Id<Character>),
+    Relationship(Id<Relationship>),
+    Cut(Id<Cut>),
+    /// A whole scene, which one side removed.
+    Scene(Id<Scene>),
+    ActiveVariant(Id<Scene>),
+    /// A whole variant, which one side removed.
+    Variant(Id<SceneVariant>),
+    Heading(Id<SceneVariant>),
+    VariantSummary(Id<SceneVariant>),
+    Element {
+        variant: Id<SceneVariant>,
+        element: Id<SceneElement>,
+    },
+    /// The order of a variant's elements.
+    ElementOrder(Id<SceneVariant>),
+    Edge {
+        src: Id<SceneVariant>,
+        dest: Id<SceneVariant>,
+    },
+    /// The scene numbers given out in the narrative.
+    SceneNumbers,
+}
+
+impl ConflictLocation {
+    /// Returns a key that orders conflicts by storyboard section, then by ID.
+    fn sort_key(&self) -> (u8, Vec<Uuid>) {
+        match self {
+            ConflictLocation::Title => (0, vec![]),
+            ConflictLocation::Summary => (1, vec![]),
+            ConflictLocation::Template => (2, vec![]),
+            ConflictLocation::Author(id) => (3, vec![id.uuid()]),
+            ConflictLocation::Character(id) => (4, vec![id.uuid()]),
+            ConflictLocation::Relationship(id) => (5, vec![id.uuid()]),
+            ConflictLocation::Cut(id) => (6, vec![id.uuid()]),
+            ConflictLocation::Scene(id) => (7, vec![id.uuid()]),
+            ConflictLocation::ActiveVariant(id) => (8, vec![id.uuid()]),
+            ConflictLocation::Variant(id) => (9, vec![id.uuid()]),
+            ConflictLocation::Heading(id) => (10, vec![id.uuid()]),
+            ConflictLocation::VariantSummary(id) => (11, vec![id.uuid()]),
+            ConflictLocation::Element { variant, element } => {
+                (12, vec![variant.uuid(), element.uuid()])
+            }
+            ConflictLocation::ElementOrder(id) => (13, vec![id.uuid()]),
+            ConflictLocation::Edge { src, dest } => (14, vec![src.uuid(), dest.uuid()]),
+            ConflictLocation::SceneNumbers => (15, vec![]),
+        }
+    }
+}
+
+/// Why the two sides could not be merged at a location.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ConflictKind {
+    /// Both sides changed it, differently.
+    BothEdited,
+    /// Both sides added it under the same ID, differently.
+    BothAdded,
+    /// One side removed it and the other side changed it.
+    RemovedAndEdited { removed_by: Side },
+    /// One side removed something the other side newly refers to: a scene
+    /// or variant linked into the graph, by an edge or as a root; a
+    /// character given lines or relationships; or a scene a relationship
+    /// starts or ends at.
+    RemovedAndLinked { removed_by: Side },
+    /// The edge, added by `added_by`, would close a cycle with edges the
+    /// other side added.
+    Cycle { added_by: Side },
+}
+
+/// A change the two sides disagree on.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Conflict {
+    pub location: ConflictLocation,
+    pub kind: ConflictKind,
+}
+
+/// Errors that can occur while resolving the conflicts of a [`Merge`].
+#[derive(Debug, Serialize, PartialEq)]
+pub enum MergeError {
+    /// No open conflict lies at this location.
+    UnknownConflict(ConflictLocation),
+}
+
+/// A three-way merge of two storyboards edited apart from a common base.
+///
+/// Authors, characters, relationships, cuts, scenes, variants, and elements
+/// are matched by ID, and each is merged on its own: a change made on one side only is
+/// taken, and a change made differently on both sides is a [`Conflict`].
+/// Within a variant, the heading, summary, each element, and the order of
+/// the elements are merged separately, so both sides can edit different
+/// elements of the same variant. The graph's edges and roots are merged as
+/// sets. Metadata is not compared, so touching something is not an edit.
+///
+/// Until a conflict is resolved, the merged storyboard takes our side of it,
+/// except that an edge that would close a cycle is left out. Resolving a
+/// conflict merges again with the choice applied, which may bring up new
+/// conflicts; for example, keeping an edge that closed a cycle leaves out
+/// the other side's edge instead.
+///
+/// Lines and relationships that name a character the merged storyboard no
+/// longer has are left out with the character, so no line is left without
+/// a speaker.
+#[derive(Debug)]
+pub struct Merge {
+    base: Storyboard,
+    ours: Storyboard,
+    theirs: Storyboard,
+    resolutions: HashMap<ConflictLocation, Side>,
+    merged: Storyboard,
+    conflicts: Vec<Conflict>,
+}
+
+impl Merge {
+    /// Merges `ours` and `theirs`, two edited copies of `base`.
+    pub fn new(base: Storyboard, ours: Storyboard, theirs: Storyboard) -> Self {
+        let resolutions = HashMap::new();
+        let (merged, conflicts) = Merger::new(&base, &ours, &theirs, &resolutions).merge();
+
+        Self {
+            base,
+            ours,
+            theirs,
+            resolutions,
+            merged,
+            conflicts,
+        }
+    }
+
+    /// Returns the merged storyboard, with our side taken for each open conflict.
+    pub fn merged(&self) -> &Storyboard {
+        &self.merged
+    }
+
+    /// Consumes the merge, returning the merged storyboard.
+    pub fn into_merged(self) -> Storyboard {
+        self.merged
+    }
+
+    /// Returns the conflicts still open, ordered by storyboard section.
+    pub fn conflicts(&self) -> &[Conflict] {
+        &self.conflicts
+    }
+
+    /// Returns `true` if no conflicts are open.
+    pub fn is_resolved(&self) -> bool {
+        self.conflicts.is_empty()
+    }
+
+    /// Settles the conflict at `location` in favour of `side`, and merges
+    /// again.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MergeError::UnknownConflict`] if no open conflict lies at
+    /// `location`.
+    pub fn resolve(&mut self, location: ConflictLocation, side: Side) -> Result<(), MergeError> {
+        if !self.conflicts.iter().any(|c| c.location == location) {
+            return Err(MergeError::UnknownConflict(location));
+        }
+
+        self.resolutions.insert(location, side);
+        (self.merged, self.conflicts) =
+            Merger::new(&self.base, &self.ours, &self.theirs, &self.resolutions).merge();
+
+        Ok(())
+    }
+}
+
+/// One pass of a merge, collecting conflicts as it goes.
+struct Merger<'a> {
+    base: &'a Storyboard,
+    ours: &'a Storyboard,
+    theirs: &'a Storyboard,
+    resolutions: &'a HashMap<ConflictLocation, Side>,
+    conflicts: Vec<Conflict>,
+    /// The edges of each storyboard's graph.
+    edges: [HashSet<(Id<SceneVariant>, Id<SceneVariant>)>; 3],
+    /// The roots of each storyboard's graph.
+    roots: [HashSet<Id<SceneVariant>>; 3],
+    /// The characters each side newly refers to; see [`new_references`].
+    /// Empty for the base.
+    characters_used: [HashSet<Id<Character>>; 3],
+    /// The scenes each side's relationships newly start or end at. Empty
+    /// for the base.
+    scenes_used: [HashSet<Id<Scene>>; 3],
+}
+
+/// Indexes into [`Merger::edges`] and [`Merger::roots`].
+const BASE: usize = 0;
+const OURS: usize = 1;
+const THEIRS: usize = 2;
+
+impl<'a> Merger<'a> {
+    fn new(
+        base: &'a Storyboard,
+        ours: &'a Storyboard,
+        theirs: &'a Storyboard,
+        resolutions: &'a HashMap<ConflictLocation, Side>,
+    ) -> Self {
+        let edges = |storyboard: &Storyboard| {
+            let graph = storyboard.narrative().graph();
+            graph
+                .variants()
+                .flat_map(|src| graph.next_variants(src).map(move |dest| (src, dest)))
+                .collect()
+        };
+        let roots = |storyboard: &Storyboard| storyboard.narrative().graph().roots().clone();
+        let (ours_characters, ours_scenes) = new_references(base, ours);
+        let (theirs_characters, theirs_scenes) = new_references(base, theirs);
+
+        Self {
+            base,
+            ours,
+            theirs,
+            resolutions,
+            conflicts: Vec::new(),
+            edges: [edges(base), edges(ours), edges(theirs)],
+            roots: [roots(base), roots(ours), roots(theirs)],
+            characters_used: [HashSet::new(), ours_characters, theirs_characters],
+            scenes_used: [HashSet::new(), ours_scenes, theirs_scenes],
+        }
+    }
+
+    fn merge(mut self) -> (Storyboard, Vec<Conflict>) {
+        let (base, ours, theirs) = (self.base, self.ours, self.theirs);
+        let mut merged = ours.clone();
+        // Locks guard against edits, not merges; they are put back below.
+        let locked = merged.suspend_locks();
+        let unlocked = "Locks are suspended while merging";
+
+        match self.pick(
+            ConflictLocation::Title,
+            base.title().as_ref(),
+            ours.title().as_ref(),
+            theirs.title().as_ref(),
+        ) {
+            Some(title) => merged.update_title(title),
+            None => merged.clear_title(),
+        }
+        .expect(unlocked);
+        if let Some(summary) = self.pick(
+            ConflictLocation::Summary,
+            Some(base.summary()),
+            Some(ours.summary()),
+            Some(theirs.summary()),
+        ) {
+            merged.update_summary(summary).expect(unlocked);
+        }
+        match self.pick(
+            ConflictLocation::Template,
+            base.template().as_ref(),
+            ours.template().as_ref(),
+            theirs.template().as_ref(),
+        ) {
+            Some(template) => merged.update_template(template),
+            None => merged.clear_template(),
+        }
+        .expect(unlocked);
+
+        let authors = |s: &'a Storyboard| -> HashMap<_, _> {
+            s.authors().into_iter().map(|a| (a.id(), a)).collect()
+        };
+        for (id, author) in self.pick_all(
+            ConflictLocation::Author,
+            [authors(base), authors(ours), authors(theirs)],
+        ) {
+            match author {
+                Some(author) => merged.authors_mut().insert(id, author),
+                None => merged.authors_mut().remove(&id),
+            };
+        }
+
+        let characters = |s: &'a Storyboard| -> HashMap<_, _> {
+            s.characters().into_iter().map(|c| (c.id(), c)).collect()
+        };
+        let [b, o, t] = [characters(base), characters(ours), characters(theirs)];
+        let mut removed = HashSet::new();
+        for id in sorted_keys([&b, &o, &t]) {
+            let linked_by = [OURS, THEIRS].map(|side| self.characters_used[side].contains(&id));
+            match self.pick_linked(
+                ConflictLocation::Character(id),
+                b.get(&id).copied(),
+                o.get(&id).copied(),
+                t.get(&id).copied(),
+                linked_by,
+            ) {
+                Some(character) => merged.characters_mut().insert(id, character),
+                None => {
+                    removed.insert(id);
+                    merged.characters_mut().remove(&id)
+                }
+            };
+        }
+
+        let relationships = |s: &'a Storyboard| -> HashMap<_, _> {
+            s.relationships().into_iter().map(|r| (r.id(), r)).collect()
+        };
+        for (id, relationship) in self.pick_all(
+            ConflictLocation::Relationship,
+            [
+                relationships(base),
+                relationships(ours),
+                relationships(theirs),
+            ],
+        ) {
+            match relationship {
+                Some(relationship) => merged.relationships_mut().insert(id, relationship),
+                None => merged.relationships_mut().remove(&id),
+            };
+        }
+
+        let cuts = |s: &'a Storyboard| -> HashMap<_, _> {
+            s.cuts().into_iter().map(|c| (c.id(), c)).collect()
+        };
+        for (id, cut) in self.pick_all(
+            ConflictLocation::Cut,
+            [cuts(base), cuts(ours), cuts(theirs)],
+        ) {
+            match cut {
+                Some(cut) => merged.cuts_mut().insert(id, cut),
+                None => merged.cuts_mut().remove(&id),
+            };
+        }
+
+        *merged.narrative_mut() = self.merge_narrative();
+
+        merged
+            .relationships_mut()
+            .retain(|_, r| !removed.contains(&r.from()) && !removed.contains(&r.to()));
+        merged
+            .narrative_mut()
+            .remove_lines_unless(|speaker| !removed.contains(&speaker));
+        merged.restore_locks(&locked);
+        self.conflicts
+            .sort_by_key(|conflict| conflict.location.sort_key());
+
+        (merged, self.conflicts)
+    }
+
+    fn merge_narrative(&mut self) -> Narrative {
+        let scenes = |s: &'a Storyboard| {
+            s.narrative()
+                .scenes()
+                .map(|scene| (scene.id(), scene))
+                .collect::<HashMap<_, _>>()
+        };
+        let [base, ours, theirs] = [scenes(self.base), scenes(self.ours), scenes(self.theirs)];
+
+        let mut narrative = Narrative::default();
+        for id in sorted_keys([&base, &ours, &theirs]) {
+            let (b, o, t) = (base.get(&id), ours.get(&id), theirs.get(&id));
+            let scene = match (b, o, t) {
+                (Some(b), Some(o), Some(t)) => Some(self.merge_scene(b, o, t)),
+                _ => {
+                    let linked_by = [(o, OURS), (t, THEIRS)].map(|(scene, side)| {
+                        scene.is_some_and(|scene| {
+                            scene.variant_ids().any(|v| self.linked(v))
+                                || self.scenes_used[side].contains(&scene.id())
+                        })
+                    });
+                    self.pick_linked(
+                        ConflictLocation::Scene(id),
+                        b.copied(),
+                        o.copied(),
+                        t.copied(),
+                        linked_by,
+                    )
+                }
+            };
+            if let Some(scene) = scene {
+                narrative
+                    .add_scene(scene)
+                    .expect("Merged scenes have distinct IDs");
+            }
+        }
+        let locked = narrative.suspend_locks();
+
+        let mut roots: Vec<_> = self
+            .merge_set(&self.roots)
+            .into_iter()
+            .filter(|root| narrative.graph().contains(*root))
+            .collect();
+        roots.sort_by_key(|root| root.uuid());
+        for root in roots {
+            narrative
+                .set_variant_as_root(root)
+                .expect("Each merged root is set once");
+        }
+
+        // Edges both sides kept from the base go first, as they cannot form a
+        // cycle, then edges kept by a resolution, then the rest.
+        let mut edges: Vec<_> = self
+            .merge_set(&self.edges)
+            .into_iter()
+            .filter(|(src, dest)| {
+                narrative.graph().contains(*src) && narrative.graph().contains(*dest)
+            })
+            .collect();
+        edges.sort_by_key(|(src, dest)| {
+            let location = ConflictLocation::Edge {
+                src: *src,
+                dest: *dest,
+            };
+            (
+                !self.edges[BASE].contains(&(*src, *dest)),
+                self.resolutions.get(&location).is_none(),
+                src.uuid(),
+                dest.uuid(),
+            )
+        });
+        for (src, dest) in edges {
+            let location = ConflictLocation::Edge { src, dest };
+            let added_by = match self.edges[OURS].contains(&(src, dest)) {
+                true => Side::Ours,
+                false => Side::Theirs,
+            };
+            if self.resolutions.get(&location) == Some(&added_by.other()) {
+                continue;
+            }
+            if let Err(NarrativeError::Graph(SceneGraphError::CycleDetected { .. })) =
+                narrative.link_variants(src, dest)
+                && !self.resolutions.contains_key(&location)
+            {
+                self.conflicts.push(Conflict {
+                    location,
+                    kind: ConflictKind::Cycle { added_by },
+                });
+            }
+        }
+
+        narrative.restore_locks(&locked);
+
+        // Numbers stay with their scenes, so the two sides' numberings are
+        // kept or set aside whole; the numbers of scenes the merge removed
+        // show as omitted.
+        let numbers = [self.base, self.ours, self.theirs].map(|s| s.narrative().scene_numbers());
+        if let Some(numbers) = self.pick(
+            ConflictLocation::SceneNumbers,
+            Some(&numbers[BASE]),
+            Some(&numbers[OURS]),
+            Some(&numbers[THEIRS]),
+        ) {
+            narrative.set_scene_numbers(numbers.to_vec());
+        }
+
+        narrative
+    }
+
+    fn merge_scene(&mut self, base: &Scene, ours: &Scene, theirs: &Scene) -> Scene {
+        let mut variants = HashMap::new();
+        for id in sorted_keys([base.variants(), ours.variants(), theirs.variants()]) {
+            let (b, o, t) = (
+                base.variants().get(&id),
+                ours.variants().get(&id),
+                theirs.variants().get(&id),
+            );
+            let variant = match (b, o, t) {
+                (Some(b), Some(o), Some(t)) => Some(self.merge_variant(b, o, t)),
+                _ => {
+                    let linked_by = [o, t].map(|v| v.is_some() && self.linked(&id));
+                    self.pick_linked(ConflictLocation::Variant(id), b, o, t, linked_by)
+                }
+            };
+            if let Some(variant) = variant {
+                variants.insert(id, variant);
+            }
+        }
+        // Each side removed a different variant, leaving none.
+        if variants.is_empty() {
+            variants = ours.variants().clone();
+        }
+
+        let picked = self.pick(
+            ConflictLocation::ActiveVariant(ours.id()),
+            Some(base.active_variant()),
+            Some(ours.active_variant()),
+            Some(theirs.active_variant()),
+        );
+        let active = [
+            picked.as_ref(),
+            Some(ours.active_variant()),
+            Some(theirs.active_variant()),
+        ]
+        .into_iter()
+        .flatten()
+        .find(|id| variants.contains_key(id))
+        .copied()
+        .unwrap_or_else(|| {
+            *variants
+                .keys()
+                .min_by_key(|id| id.uuid())
+                .expect("The scene has variants")
+        });
+
+        let mut scene = ours.clone();
+        *scene.variants_mut() = variants;
+        let locked = std::mem::take(&mut scene.metadata_mut().locked);
+        scene
+            .set_active_variant(active)
+            .expect("The active variant is among the merged variants");
+        scene.metadata_mut().locked = locked;
+
+        scene
+    }
+
+    fn merge_variant(
+        &mut self,
+        base: &SceneVariant,
+        ours: &SceneVariant,
+        theirs: &SceneVariant,
+    ) -> SceneVariant {
+        let id = ours.id();
+        let heading = self
+            .pick(
+                ConflictLocation::Heading(id),
+                Some(&base.heading().cloned()),
+                Some(&ours.heading().cloned()),
+                Some(&theirs.heading().cloned()),
+            )
+            .flatten();
+        let summary = self
+            .pick(
+                ConflictLocation::VariantSummary(id),
+                Some(base.summary()),
+                Some(ours.summary()),
+                Some(theirs.summary()),
+            )
+            .unwrap_or_else(|| ours.summary().clone());
+
+        let [b, o, t] = [base, ours, theirs].map(|v| {
+            v.elements()
+                .iter()
+                .map(|e| (e.id(), e))
+                .collect::<HashMap<_, _>>()
+        });
+        let mut merged = HashMap::new();
+        for element in sorted_keys([&b, &o, &t]) {
+            let location = ConflictLocation::Element {
+                variant: id,
+                element,
+            };
+            let picked = self.pick(
+                location,
+                b.get(&element).copied(),
+                o.get(&element).copied(),
+                t.get(&element).copied(),
+            );
+            if let Some(picked) = picked {
+                merged.insert(element, picked);
+            }
+        }
+
+        let order = self.merge_order(id, [base, ours, theirs], &merged);
+        let elements = order
+            .into_iter()
+            .filter_map(|element| merged.remove(&element))
+            .collect();
+
+        ours.with_content(heading, summary, elements)
+    }
+
+    /// Merges the order of a variant's elements, keeping the ones in `kept`.
+    ///
+    /// If only one side reordered the elements they share with the base, its
+    /// order is kept; if both did, differently, the order is a conflict.
+    /// Elements only the other side has are then placed after the element
+    /// that comes before them on that side.
+    fn merge_order(
+        &mut self,
+        variant: Id<SceneVariant>,
+        [base, ours, theirs]: [&SceneVariant; 3],
+        kept: &HashMap<Id<SceneElement>, SceneElement>,
+    ) -> Vec<Id<SceneElement>> {
+        let order = |v: &SceneVariant| v.elements().iter().map(|e| e.id()).collect::<Vec<_>>();
+        let (base, ours, theirs) = (order(base), order(ours), order(theirs));
+        let among = |order: &[Id<SceneElement>], other: &[Id<SceneElement>]| {
+            order
+                .iter()
+                .filter(|id| other.contains(id))
+                .copied()
+                .collect::<Vec<_>>()
+        };
+        let reordered = |side: &[Id<SceneElement>]| among(&base, side) != among(side, &base);
+
+        let skeleton = match (reordered(&ours), reordered(&theirs)) {
+            (true, true) if among(&ours, &theirs) != among(&theirs, &ours) => self.side_for(
+                ConflictLocation::ElementOrder(variant),
+                ConflictKind::BothEdited,
+            ),
+            (false, true) => Side::Theirs,
+            _ => Side::Ours,
+        };
+        let (skeleton, other) = match skeleton {
+            Side::Ours => (ours, theirs),
+            Side::Theirs => (theirs, ours),
+        };
+
+        let mut merged: Vec<_> = skeleton
+            .into_iter()
+            .filter(|id| kept.contains_key(id))
+            .collect();
+        let mut previous = None;
+        for id in other.into_iter().filter(|id| kept.contains_key(id)) {
+            let at = match merged.iter().position(|placed| *placed == id) {
+                Some(at) => at,
+                None => {
+                    let at = previous.map_or(0, |p| p + 1);
+                    merged.insert(at, id);
+                    at
+                }
+            };
+            previous = Some(at);
+        }
+
+        merged
+    }
+
+    /// Returns `true` if either side newly linked the variant into the graph.
+    fn linked(&self, variant: &Id<SceneVariant>) -> bool {
+        let added = |side: usize| {
+            self.roots[side].contains(variant) && !self.roots[BASE].contains(variant)
+                || self.edges[side].iter().any(|edge| {
+                    (edge.0 == *variant || edge.1 == *variant) && !self.edges[BASE].contains(edge)
+                })
+        };
+
+        added(OURS) || added(THEIRS)
+    }
+
+    /// Merges a set three ways: a member stays if both sides kept it, and is
+    /// added if either side added it.
+    fn merge_set<T: Copy + Eq + std::hash::Hash>(&self, sets: &[HashSet<T>; 3]) -> HashSet<T> {
+        let [base, ours, theirs] = sets;
+        ours.iter()
+            .chain(theirs)
+            .filter(|item| (ours.contains(item) && theirs.contains(item)) || !base.contains(item))
+            .copied()
+            .collect()
+    }
+
+    /// Merges each entry of three maps keyed by ID, returning the merged
+    /// value, or `None` if it was removed, for every ID in any of them.
+    fn pick_all<K, T>(
+        &mut self,
+        location: fn(Id<K>) -> ConflictLocation,
+        [base, ours, theirs]: [HashMap<Id<K>, &T>; 3],
+    ) -> Vec<(Id<K>, Option<T>)>
+    where
+        T: Clone + Serialize,
+    {
+        sorted_keys([&base, &ours, &theirs])
+            .into_iter()
+            .map(|id| {
+                let picked = self.pick(
+                    location(id),
+                    base.get(&id).copied(),
+                    ours.get(&id).copied(),
+                    theirs.get(&id).copied(),
+                );
+                (id, picked)
+            })
+            .collect()
+    }
+
+    /// Merges one value three ways, where `None` means the value is absent.
+    fn pick<T: Clone + Serialize>(
+        &mut self,
+        location: ConflictLocation,
+        base: Option<&T>,
+        ours: Option<&T>,
+        theirs: Option<&T>,
+    ) -> Option<T> {
+        let same = |a: Option<&T>, b: Option<&T>| match (a, b) {
+            (Some(a), Some(b)) => same_content(a, b),
+            (a, b) => a.is_none() && b.is_none(),
+        };
+
+        if same(ours, theirs) || same(base, theirs) {
+            return ours.cloned();
+        }
+        if same(base, ours) {
+            return theirs.cloned();
+        }
+
+        let kind = match (base, ours, theirs) {
+            (None, _, _) => ConflictKind::BothAdded,
+            (_, None, _) => ConflictKind::RemovedAndEdited {
+                removed_by: Side::Ours,
+            },
+            (_, _, None) => ConflictKind::RemovedAndEdited {
+                removed_by: Side::Theirs,
+            },
+            _ => ConflictKind::BothEdited,
+        };
+        match self.side_for(location, kind) {
+            Side::Ours => ours.cloned(),
+            Side::Theirs => theirs.cloned(),
+        }
+    }
+
+    /// As [`Merger::pick`], except that a scene or variant one side removed
+    /// is a conflict if the other side linked it, given by `linked_by` for
+    /// our side and theirs, even if it is otherwise unchanged.
+    fn pick_linked<T: Clone + Serialize>(
+        &mut self,
+        location: ConflictLocation,
+        base: Option<&T>,
+        ours: Option<&T>,
+        theirs: Option<&T>,
+        linked_by: [bool; 2],
+    ) -> Option<T> {
+        let removed_by = match (base, ours, theirs) {
+            (Some(_), None, Some(_)) if linked_by[1] => Side::Ours,
+            (Some(_), Some(_), None) if linked_by[0] => Side::Theirs,
+            _ => return self.pick(location, base, ours, theirs),
+        };
+
+        match self.side_for(location, ConflictKind::RemovedAndLinked { removed_by }) {
+            Side::Ours => ours.cloned(),
+            Side::Theirs => theirs.cloned(),
+        }
+    }
+
+    /// Returns the side a conflict is settled in favour of: the resolution
+    /// if there is one, or our side while the conflict is recorded as open.
+    fn side_for(&mut self, location: ConflictLocation, kind: ConflictKind) -> Side {
+        match self.resolutions.get(&location) {
+            Some(side) => *side,
+            None => {
+                self.conflicts.push(Conflict { location, kind });
+                Side::Ours
+            }
+        }
+    }
+}
+
+/// Returns the characters and scenes `side` refers to where `base` does
+/// not: the speakers of lines it added or gave to someone else, and the
+/// characters and bounding scenes of relationships it added or changed.
+fn new_references(
+    base: &Storyboard,
+    side: &Storyboard,
+) -> (HashSet<Id<Character>>, HashSet<Id<Scene>>) {
+    let lines = |storyboard: &Storyboard| -> HashSet<(Id<Dialogue>, Id<Character>)> {
+        storyboard
+            .narrative()
+            .scenes()
+            .flat_map(|scene| scene.variants().values())
+            .flat_map(|variant| variant.elements())
+            .filter_map(|element| match element {
+                SceneElement::Dialogue(dialogue) => Some((dialogue.id(), dialogue.speaker())),
+                SceneElement::Action(_) => None,
+            })
+            .collect()
+    };
+    let base_lines = lines(base);
+    let mut characters: HashSet<_> = lines(side)
+        .into_iter()
+        .filter(|line| !base_lines.contains(line))
+        .map(|(_, speaker)| speaker)
+        .collect();
+
+    let mut scenes = HashSet::new();
+    for relationship in side.relationships() {
+        let unchanged = base
+            .relationship(&relationship.id())
+            .is_some_and(|old| same_content(old, relationship));
+        if !unchanged {
+            characters.extend([relationship.from(), relationship.to()]);
+            scenes.extend(
+                [relationship.starts(), relationship.ends()]
+                    .into_iter()
+                    .flatten(),
+            );
+        }
+    }
+
+    (characters, scenes)
+}
+
+/// Returns every key of the maps, once each, ordered by ID.
+fn sorted_keys<K, V>(maps: [&HashMap<Id<K>, V>; 3]) -> Vec<Id<K>> {
+    let mut keys: Vec<_> = maps
+        .iter()
+        .flat_map(|map| map.keys().copied())
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    keys.sort_by_key(|id| id.uuid());
+    keys
+}
+
+/// Returns `true` if two values are equal apart from their metadata.
+fn same_content<T: Serialize>(a: &T, b: &T) -> bool {
+    fn content<T: Serialize>(value: &T) -> Value {
+        let mut value = serde_json::to_value(value).expect("Models serialize to JSON");
+        strip_metadata(&mut value);
+        value
+    }
+
+    content(a) == content(b)
+}
+
+fn strip_metadata(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            map.remove("metadata");
+            map.values_mut().for_each(strip_metadata);
+        }
+        Value::Array(items) => items.iter_mut().for_each(strip_metadata),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        diff::merge::{ConflictKind, ConflictLocation, Merge, MergeError, Side},
+        models::{
+            BranchPolicy, Character, CharacterName, Dialogue, DialogueBlock, DialogueText,
+            HasMetadata, Id, NumberedScene, Relationship, RelationshipKind, RevisionNote, Scene,
+            SceneAction, SceneElement, SceneNumber, SceneVariant, Storyboard,
+        },
+    };
+
+    /// A storyboard with a root scene leading to a second scene, whose
+    /// variant holds an action and a line of dialogue.
+    fn generate_test_components() -> (Storyboard, Vec<Id<SceneVariant>>) {
+        let mut storyboard = Storyboard::default();
+        let narrative = storyboard.narrative_mut();
+        let mut variants = Vec::new();
+        for _ in 0..2 {
+            let mut scene = Scene::new();
+            let (scene_id, variant_id) = (scene.id(), *scene.active_variant());
+            let variant = scene.variants_mut().get_mut(&variant_id).unwrap();
+            variant
+                .add_element(SceneElement::Action(
+                    SceneAction::new("The door creaks open.").unwrap(),
+                ))
+                .unwrap();
+            let mut dialogue = Dialogue::new(scene_id, Id::new());
+            dialogue.add_dialogue_block(text("Who's there?")).unwrap();
+            variant
+                .add_element(SceneElement::Dialogue(dialogue))
+                .unwrap();
+            variants.push(variant_id);
+            narrative.add_scene(scene).unwrap();
+        }
+        narrative.set_variant_as_root(variants[0]).unwrap();
+        narrative.link_variants(variants[0], variants[1]).unwrap();
+
+        (storyboard, variants)
+    }
+
+    fn text(line: &str) -> DialogueBlock {
+        DialogueBlock::Text(DialogueText::new(line).unwrap())
+    }
+
+    fn edit_line(storyboard: &mut Storyboard, variant: Id<SceneVariant>, line: &str) {
+        storyboard
+            .narrative_mut()
+            .variant_mut(&variant)
+            .unwrap()
+            .replace_dialogue_block(1, 0, text(line))
+            .unwrap();
+    }
+
+    /// Adds two characters to the storyboard, returning their IDs.
+    fn add_characters(storyboard: &mut Storyboard) -> [Id<Character>; 2] {
+        ["Mara", "Theo"].map(|name| {
+            let character = Character::new(CharacterName::new(name).unwrap());
+            let id = character.id();
+            storyboard.add_character(character).unwrap();
+            id
+        })
+    }
+
+    fn line(storyboard: &Storyboard, variant: Id<SceneVariant>) -> DialogueBlock {
+        let scene = storyboard.narrative().scene_for_variant(&variant).unwrap();
+        let SceneElement::Dialogue(dialogue) = &scene.variants()[&variant].elements()[1] else {
+            panic!("Expected dialogue");
+        };
+        dialogue.content()[0].clone()
+    }
+
+    #[test]
+    fn test_edits_to_different_parts_merge_cleanly() {
+        // ARRANGE
+        let (base, v) = generate_test_components();
+        let (mut ours, mut theirs) = (base.clone(), base.clone());
+        let character = Character::new(CharacterName::new("Mara").unwrap());
+        let character_id = character.id();
+        ours.add_character(character).unwrap();
+        ours.narrative_mut()
+            .variant_mut(&v[1])
+            .unwrap()
+            .edit_action(0, "The door swings open.")
+            .unwrap();
+        edit_line(&mut theirs, v[1], "Hello?");
+        // ACT
+        let merge = Merge::new(base, ours, theirs);
+        // ASSERT
+        assert!(merge.is_resolved());
+        let merged = merge.into_merged();
+        assert!(merged.character(&character_id).is_some());
+        let scene = merged.narrative().scene_for_variant(&v[1]).unwrap();
+        let SceneElement::Action(action) = &scene.variants()[&v[1]].elements()[0] else {
+            panic!("Expected an action");
+        };
+        assert_eq!(action.as_str(), "The door swings open.");
+        assert_eq!(line(&merged, v[1]), text("Hello?"));
+        assert!(
+            merged
+                .narrative()
+                .graph()
+                .next_variants(v[0])
+                .any(|n| n == v[1])
+        );
+    }
+
+    #[test]
+    fn test_both_sides_editing_a_line_conflict_until_resolved() {
+        // ARRANGE
+        let (base, v) = generate_test_components();
+        let (mut ours, mut theirs) = (base.clone(), base.clone());
+        edit_line(&mut ours, v[1], "Hello?");
+        edit_line(&mut theirs, v[1], "Who goes there?");
+        let element = base
+            .narrative()
+            .scene_for_variant(&v[1])
+            .unwrap()
+            .variants()[&v[1]]
+            .elements()[1]
+            .id();
+        let location = ConflictLocation::Element {
+            variant: v[1],
+            element,
+        };
+        let mut merge = Merge::new(base, ours, theirs);
+        assert_eq!(merge.conflicts().len(), 1);
+        assert_eq!(merge.conflicts()[0].location, location);
+        assert_eq!(merge.conflicts()[0].kind, ConflictKind::BothEdited);
+        assert_eq!(line(merge.merged(), v[1]), text("Hello?"));
+        // ACT
+        merge.resolve(location, Side::Theirs).unwrap();
+        // ASSERT
+        assert!(merge.is_resolved());
+        assert_eq!(line(merge.merged(), v[1]), text("Who goes there?"));
+        assert_eq!(
+            merge.resolve(location, Side::Ours),
+            Err(MergeError::UnknownConflict(location))
+        );
+    }
+
+    #[test]
+    fn test_removing_a_variant_the_other_side_linked_conflicts() {
+        // ARRANGE
+        let (mut base, v) = generate_test_components();
+        let scene_id = base.narrative().scene_for_variant(&v[1]).unwrap().id();
+        let (fork, _) = base.narrative_mut().fork_variant(scene_id, v[1]).unwrap();
+        let (mut ours, mut theirs) = (base.clone(), base.clone());
+        ours.narrative_mut().remove_variant(scene_id, fork).unwrap();
+        theirs.narrative_mut().link_variants(v[0], fork).unwrap();
+        let mut merge = Merge::new(base, ours, theirs);
+        let location = ConflictLocation::Variant(fork);
+        assert_eq!(merge.conflicts().len(), 1);
+        assert_eq!(merge.conflicts()[0].location, location);
+        assert_eq!(
+            merge.conflicts()[0].kind,
+            ConflictKind::RemovedAndLinked {
+                removed_by: Side::Ours
+            }
+        );
+        assert!(!merge.merged().narrative().graph().contains(fork));
+        // ACT
+        merge.resolve(location, Side::Theirs).unwrap();
+        // ASSERT
+        assert!(merge.is_resolved());
+        let graph = merge.merged().narrative().graph();
+        assert!(graph.next_variants(v[0]).any(|n| n == fork));
+        assert!(graph.next_variants(v[0]).any(|n| n == v[1]));
+    }
+
+    #[test]
+    fn test_removing_a_character_the_other_side_gave_lines_conflicts() {
+        // ARRANGE
+        let (mut base, v) = generate_test_components();
+        let [mara, theo] = add_characters(&mut base);
+        let (mut ours, mut theirs) = (base.clone(), base.clone());
+        ours.remove_character(mara).unwrap();
+        let scene_id = theirs.narrative().scene_for_variant(&v[1]).unwrap().id();
+        let mut dialogue = Dialogue::new(scene_id, mara);
+        dialogue.add_dialogue_block(text("It's me.")).unwrap();
+        theirs
+            .narrative_mut()
+            .variant_mut(&v[1])
+            .unwrap()
+            .add_element(SceneElement::Dialogue(dialogue))
+            .unwrap();
+        theirs
+            .add_relationship(Relationship::new(mara, theo, RelationshipKind::Sibling))
+            .unwrap();
+        let mut merge = Merge::new(base, ours, theirs);
+        let location = ConflictLocation::Character(mara);
+        assert_eq!(merge.conflicts().len(), 1);
+        assert_eq!(merge.conflicts()[0].location, location);
+        assert_eq!(
+            merge.conflicts()[0].kind,
+            ConflictKind::RemovedAndLinked {
+                removed_by: Side::Ours
+            }
+        );
+        let elements = |merge: &Merge| {
+            let scene = merge.merged().narrative().scene_for_variant(&v[1]).unwrap();
+            scene.variants()[&v[1]].elements().len()
+        };
+        assert_eq!(elements(&merge), 2);
+        assert!(merge.merged().relationships().is_empty());
+        // ACT
+        merge.resolve(location, Side::Theirs).unwrap();
+        // ASSERT
+        assert!(merge.is_resolved());
+        assert!(merge.merged().character(&mara).is_some());
+        assert_eq!(elements(&merge), 3);
+        assert_eq!(merge.merged().relationships().len(), 1);
+    }
+
+    #[test]
+    fn test_removing_a_scene_a_new_relationship_starts_at_conflicts() {
+        // ARRANGE
+        let (mut base, v) = generate_test_components();
+        let [mara, theo] = add_characters(&mut base);
+        let scene_id = base.narrative().scene_for_variant(&v[1]).unwrap().id();
+        let (mut ours, mut theirs) = (base.clone(), base.clone());
+        ours.narrative_mut().remove_scene(scene_id).unwrap();
+        let mut rivals = Relationship::new(mara, theo, RelationshipKind::Rival);
+        rivals.set_span(Some(scene_id), None);
+        theirs.add_relationship(rivals).unwrap();
+        // ACT
+        let merge = Merge::new(base, ours, theirs);
+        // ASSERT
+        assert_eq!(merge.conflicts().len(), 1);
+        assert_eq!(
+            merge.conflicts()[0].location,
+            ConflictLocation::Scene(scene_id)
+        );
+        assert_eq!(
+            merge.conflicts()[0].kind,
+            ConflictKind::RemovedAndLinked {
+                removed_by: Side::Ours
+            }
+        );
+    }
+
+    #[test]
+    fn test_both_sides_adding_a_character_differently_conflicts() {
+        // ARRANGE
+        let (base, _) = generate_test_components();
+        let (mut ours, mut theirs) = (base.clone(), base.clone());
+        let character = Character::new(CharacterName::new("Mara").unwrap());
+        let mut renamed = character.clone();
+        renamed.set_name(CharacterName::new("Marla").unwrap());
+        ours.add_character(character.clone()).unwrap();
+        theirs.add_character(renamed).unwrap();
+        // ACT
+        let merge = Merge::new(base, ours, theirs);
+        // ASSERT
+        assert_eq!(merge.conflicts().len(), 1);
+        assert_eq!(
+            merge.conflicts()[0].location,
+            ConflictLocation::Character(character.id())
+        );
+        assert_eq!(merge.conflicts()[0].kind, ConflictKind::BothAdded);
+        assert_eq!(
+            merge.merged().character(&character.id()).unwrap().name(),
+            "Mara"
+        );
+    }
+
+    #[test]
+    fn test_reordering_elements_differently_conflicts() {
+        // ARRANGE
+        let (mut base, v) = generate_test_components();
+        base.narrative_mut()
+            .variant_mut(&v[1])
+            .unwrap()
+            .add_element(SceneElement::Action(
+                SceneAction::new("Nobody answers.").unwrap(),
+            ))
+            .unwrap();
+        let (mut ours, mut theirs) = (base.clone(), base.clone());
+        let reorder = |storyboard: &mut Storyboard, from: usize, to: usize| {
+            storyboard
+                .narrative_mut()
+                .variant_mut(&v[1])
+                .unwrap()
+                .move_element(from, to)
+                .unwrap();
+        };
+        reorder(&mut ours, 0, 2);
+        reorder(&mut theirs, 2, 0);
+        // ACT
+        let merge = Merge::new(base, ours.clone(), theirs);
+        // ASSERT
+        assert_eq!(merge.conflicts().len(), 1);
+        assert_eq!(
+            merge.conflicts()[0].location,
+            ConflictLocation::ElementOrder(v[1])
+        );
+        assert_eq!(merge.conflicts()[0].kind, ConflictKind::BothEdited);
+        let order = |storyboard: &Storyboard| -> Vec<_> {
+            let scene = storyboard.narrative().scene_for_variant(&v[1]).unwrap();
+            scene.variants()[&v[1]]
+                .elements()
+                .iter()
+                .map(|element| element.id())
+                .collect()
+        };
+        assert_eq!(order(merge.merged()), order(&ours));
+    }
+
+    #[test]
+    fn test_edges_closing_a_cycle_conflict_and_resolving_one_brings_up_the_other() {
+        // ARRANGE
+        let (mut base, v) = generate_test_components();
+        let parked = Scene::new();
+        let v2 = *parked.active_variant();
+        base.narrative_mut().add_scene(parked).unwrap();
+        let (mut ours, mut theirs) = (base.clone(), base.clone());
+        ours.narrative_mut().link_variants(v[1], v2).unwrap();
+        theirs.narrative_mut().link_variants(v2, v[1]).unwrap();
+        let mut merge = Merge::new(base, ours, theirs);
+        assert_eq!(merge.conflicts().len(), 1);
+        let ConflictKind::Cycle { added_by } = merge.conflicts()[0].kind else {
+            panic!("Expected a cycle");
+        };
+        let left_out = merge.conflicts()[0].location;
+        // ACT
+        merge.resolve(left_out, added_by).unwrap();
+        // ASSERT
+        assert_eq!(merge.conflicts().len(), 1);
+        assert_ne!(merge.conflicts()[0].location, left_out);
+        assert_eq!(
+            merge.conflicts()[0].kind,
+            ConflictKind::Cycle {
+                added_by: added_by.other()
+            }
+        );
+        let ConflictLocation::Edge { src, dest } = left_out else {
+            panic!("Expected an edge");
+        };
+        let graph = merge.merged().narrative().graph();
+        assert!(graph.next_variants(src).any(|n| n == dest));
+    }
+
+    #[test]
+    fn test_locked_inputs_merge_and_stay_locked() {
+        // ARRANGE
+        let (mut base, v) = generate_test_components();
+        let scene_id = base.narrative().scene_for_variant(&v[1]).unwrap().id();
+        let (mut ours, mut theirs) = (base.clone(), base.clone());
+        base.narrative_mut()
+            .lock_scene(scene_id, RevisionNote::new("Picture lock").unwrap())
+            .unwrap();
+        ours.narrative_mut()
+            .lock_scene(scene_id, RevisionNote::new("Picture lock").unwrap())
+            .unwrap();
+        ours.lock(RevisionNote::new("Shooting draft").unwrap());
+        edit_line(&mut theirs, v[1], "Hello?");
+        // ACT
+        let merge = Merge::new(base, ours, theirs);
+        // ASSERT
+        assert!(merge.is_resolved());
+        let merged = merge.into_merged();
+        assert_eq!(line(&merged, v[1]), text("Hello?"));
+        assert!(merged.is_locked());
+        assert!(
+            merged
+                .narrative()
+                .scene_for_variant(&v[1])
+                .unwrap()
+                .is_locked()
+        );
+    }
+
+    #[test]
+    fn test_numbering_on_both_sides_differently_conflicts() {
+        // ARRANGE
+        let (base, v) = generate_test_components();
+        let (mut ours, mut theirs) = (base.clone(), base.clone());
+        ours.narrative_mut()
+            .number_scenes(v[0], &BranchPolicy::default())
+            .unwrap();
+        let first = base.narrative().scene_for_variant(&v[0]).unwrap().id();
+        theirs
+            .narrative_mut()
+            .set_scene_numbers(vec![NumberedScene::new(
+                SceneNumber::new("12").unwrap(),
+                Some(first),
+            )]);
+        let numbered_by_ours = Merge::new(base.clone(), ours.clone(), base.clone());
+        // ACT
+        let merge = Merge::new(base, ours.clone(), theirs);
+        // ASSERT
+        assert!(numbered_by_ours.is_resolved());
+        assert_eq!(
+            numbered_by_ours.merged().narrative().scene_numbers(),
+            ours.narrative().scene_numbers()
+        );
+        assert_eq!(merge.conflicts().len(), 1);
+        assert_eq!(
+            merge.conflicts()[0].location,
+            ConflictLocation::SceneNumbers
+        );
+        assert_eq!(merge.conflicts()[0].kind, ConflictKind::BothEdited);
+    }
+}
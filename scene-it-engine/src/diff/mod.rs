@@ -0,0 +1,14 @@
+//! Comparing drafts: what changed between two scene variants, down to the
+//! word, and views of the changes for a browser or a terminal; and merging
+//! two storyboards edited apart from a common base.
+
+mod align;
+mod merge;
+mod render;
+mod variant;
+
+pub use merge::{Conflict, ConflictKind, ConflictLocation, Merge, MergeError, Side};
+pub use render::{render_ansi, render_html};
+pub use variant::{
+    BlockDiff, ElementDiff, SpeakerChanges, VariantDiff, WordDiff, diff_variants, diff_words,
+};
@@ -0,0 +1,340 @@
+use std::fmt::Write;
+
+use quick_xml::escape::escape;
+
+use crate::{
+    diff::variant::{BlockDiff, ElementDiff, VariantDiff, WordDiff},
+    formats::parenthetical_text,
+    models::{Character, DialogueBlock, Id, SceneElement, Storyboard},
+};
+
+const ANSI_RESET: &str = "\x1b[0m";
+const ANSI_INSERTED: &str = "\x1b[32m";
+const ANSI_REMOVED: &str = "\x1b[31;9m";
+
+/// How an element or block changed, which sets its class or line marker.
+#[derive(Clone, Copy)]
+enum Change {
+    Unchanged,
+    Inserted,
+    Removed,
+    Changed,
+}
+
+impl Change {
+    fn class(self) -> &'static str {
+        match self {
+            Change::Unchanged => "unchanged",
+            Change::Inserted => "inserted",
+            Change::Removed => "removed",
+            Change::Changed => "changed",
+        }
+    }
+
+    fn marker(self) -> char {
+        match self {
+            Change::Unchanged => ' ',
+            Change::Inserted => '+',
+            Change::Removed => '-',
+            Change::Changed => '~',
+        }
+    }
+}
+
+/// One line of the diff in script order, before it is marked up.
+struct Row {
+    kind: &'static str,
+    change: Change,
+    words: Vec<WordDiff>,
+}
+
+/// Renders a diff as an HTML fragment.
+///
+/// The fragment is a `div` of class `variant-diff` with one element per
+/// script line. Each line has a class for its kind (`heading`, `summary`,
+/// `action`, `character`, `parenthetical`, or `dialogue`) and one for its
+/// change (`unchanged`, `inserted`, `removed`, or `changed`). Within changed
+/// lines, removed words are wrapped in `<del>` and inserted words in `<ins>`.
+/// Speakers are named from the storyboard's characters.
+pub fn render_html(diff: &VariantDiff, storyboard: &Storyboard) -> String {
+    let mut output = String::from("<div class=\"variant-diff\">\n");
+    for row in rows(diff, storyboard) {
+        let mut line = String::new();
+        for words in &row.words {
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            match words {
+                WordDiff::Equal(text) => line.push_str(&escape(text)),
+                WordDiff::Inserted(text) => {
+                    let _ = write!(line, "<ins>{}</ins>", escape(text));
+                }
+                WordDiff::Removed(text) => {
+                    let _ = write!(line, "<del>{}</del>", escape(text));
+                }
+            }
+        }
+        let _ = writeln!(
+            output,
+            "  <p class=\"{} {}\">{line}</p>",
+            row.kind,
+            row.change.class()
+        );
+    }
+    output.push_str("</div>\n");
+
+    output
+}
+
+/// Renders a diff as text for a terminal, coloured with ANSI escape codes.
+///
+/// Each script line starts with a marker: a space if unchanged, `+` if
+/// inserted, `-` if removed, and `~` if its words changed. Inserted text is
+/// green and removed text red and struck through. Dialogue is indented under
+/// its speaker, named from the storyboard's characters.
+pub fn render_ansi(diff: &VariantDiff, storyboard: &Storyboard) -> String {
+    let mut output = String::new();
+    for row in rows(diff, storyboard) {
+        let indent = match row.kind {
+            "character" => "  ",
+            "parenthetical" | "dialogue" => "    ",
+            _ => "",
+        };
+        let colour = match row.change {
+            Change::Inserted => ANSI_INSERTED,
+            Change::Removed => ANSI_REMOVED,
+            Change::Unchanged | Change::Changed => "",
+        };
+
+        let _ = write!(output, "{} {indent}{colour}", row.change.marker());
+        for (index, words) in row.words.iter().enumerate() {
+            if index > 0 {
+                output.push(' ');
+            }
+            match words {
+                WordDiff::Equal(text) => output.push_str(text),
+                WordDiff::Inserted(text) if colour.is_empty() => {
+                    let _ = write!(output, "{ANSI_INSERTED}{text}{ANSI_RESET}");
+                }
+                WordDiff::Removed(text) if colour.is_empty() => {
+                    let _ = write!(output, "{ANSI_REMOVED}{text}{ANSI_RESET}");
+                }
+                WordDiff::Inserted(text) | WordDiff::Removed(text) => output.push_str(text),
+            }
+        }
+        if !colour.is_empty() {
+            output.push_str(ANSI_RESET);
+        }
+        output.push('\n');
+    }
+
+    output
+}
+
+/// Lays the diff out as script lines.
+fn rows(diff: &VariantDiff, storyboard: &Storyboard) -> Vec<Row> {
+    let mut rows = Vec::new();
+    for (kind, words) in [("heading", &diff.heading), ("summary", &diff.summary)] {
+        if !words.is_empty() {
+            rows.push(Row {
+                kind,
+                change: words_change(words),
+                words: words.clone(),
+            });
+        }
+    }
+
+    for element in &diff.elements {
+        match element {
+            ElementDiff::Unchanged(element) => {
+                element_rows(&mut rows, element, Change::Unchanged, storyboard)
+            }
+            ElementDiff::Inserted(element) => {
+                element_rows(&mut rows, element, Change::Inserted, storyboard)
+            }
+            ElementDiff::Removed(element) => {
+                element_rows(&mut rows, element, Change::Removed, storyboard)
+            }
+            ElementDiff::ActionChanged { words, .. } => rows.push(Row {
+                kind: "action",
+                change: Change::Changed,
+                words: words.clone(),
+            }),
+            ElementDiff::DialogueChanged { new, blocks, .. } => {
+                rows.push(speaker_row(new.speaker(), Change::Changed, storyboard));
+                for block in blocks {
+                    rows.push(match block {
+                        BlockDiff::Unchanged(block) => block_row(block, Change::Unchanged),
+                        BlockDiff::Inserted(block) => block_row(block, Change::Inserted),
+                        BlockDiff::Removed(block) => block_row(block, Change::Removed),
+                        BlockDiff::Changed { new, words, .. } => Row {
+                            kind: block_kind(new),
+                            change: Change::Changed,
+                            words: match new {
+                                DialogueBlock::Parenthetical(p)
+                                    if parenthetical_text(p) != p.as_str() =>
+                                {
+                                    wrap_in_parentheses(words)
+                                }
+                                _ => words.clone(),
+                            },
+                        },
+                    });
+                }
+            }
+        }
+    }
+
+    rows
+}
+
+fn element_rows(
+    rows: &mut Vec<Row>,
+    element: &SceneElement,
+    change: Change,
+    storyboard: &Storyboard,
+) {
+    match element {
+        SceneElement::Action(action) => rows.push(Row {
+            kind: "action",
+            change,
+            words: vec![WordDiff::Equal(action.as_str().to_string())],
+        }),
+        SceneElement::Dialogue(dialogue) => {
+            rows.push(speaker_row(dialogue.speaker(), change, storyboard));
+            rows.extend(dialogue.content().iter().map(|b| block_row(b, change)));
+        }
+    }
+}
+
+fn speaker_row(speaker: Id<Character>, change: Change, storyboard: &Storyboard) -> Row {
+    let name = storyboard
+        .character(&speaker)
+        .map_or_else(|| "UNKNOWN".to_string(), |c| c.name().to_uppercase());
+
+    Row {
+        kind: "character",
+        change,
+        words: vec![WordDiff::Equal(name)],
+    }
+}
+
+fn block_row(block: &DialogueBlock, change: Change) -> Row {
+    let text = match block {
+        DialogueBlock::Text(text) => text.as_str().to_string(),
+        DialogueBlock::Parenthetical(p) => parenthetical_text(p),
+    };
+
+    Row {
+        kind: block_kind(block),
+        change,
+        words: vec![WordDiff::Equal(text)],
+    }
+}
+
+fn block_kind(block: &DialogueBlock) -> &'static str {
+    match block {
+        DialogueBlock::Text(_) => "dialogue",
+        DialogueBlock::Parenthetical(_) => "parenthetical",
+    }
+}
+
+/// Returns how a heading or summary changed, given its words.
+fn words_change(words: &[WordDiff]) -> Change {
+    let has = |f: fn(&WordDiff) -> bool| words.iter().any(f);
+    match (
+        has(|w| matches!(w, WordDiff::Equal(_))),
+        has(|w| matches!(w, WordDiff::Inserted(_))),
+        has(|w| matches!(w, WordDiff::Removed(_))),
+    ) {
+        (_, false, false) => Change::Unchanged,
+        (false, true, false) => Change::Inserted,
+        (false, false, true) => Change::Removed,
+        _ => Change::Changed,
+    }
+}
+
+/// Adds the parentheses a parenthetical is shown with around its words.
+fn wrap_in_parentheses(words: &[WordDiff]) -> Vec<WordDiff> {
+    let mut words = words.to_vec();
+    if let Some(WordDiff::Equal(first) | WordDiff::Inserted(first) | WordDiff::Removed(first)) =
+        words.first_mut()
+    {
+        first.insert(0, '(');
+    }
+    if let Some(WordDiff::Equal(last) | WordDiff::Inserted(last) | WordDiff::Removed(last)) =
+        words.last_mut()
+    {
+        last.push(')');
+    }
+
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        diff::{diff_variants, render_ansi, render_html},
+        formats::fountain,
+        models::{SceneElement, SceneVariant, Storyboard},
+    };
+
+    /// A storyboard with one scene, and an edit of its variant that changes
+    /// the action and drops the line of dialogue.
+    fn generate_test_components() -> (Storyboard, SceneVariant, SceneVariant) {
+        let storyboard =
+            fountain::parse("INT. HOUSE - DAY\n\nRain & wind.\n\nANNA\nClose it.\n").unwrap();
+        let old = storyboard
+            .narrative()
+            .scenes()
+            .next()
+            .unwrap()
+            .variants()
+            .values()
+            .next()
+            .unwrap()
+            .clone();
+        let mut new = old.clone();
+        let action = new
+            .elements()
+            .iter()
+            .position(|e| matches!(e, SceneElement::Action(_)))
+            .unwrap();
+        new.edit_action(action, "Rain & hail.").unwrap();
+        new.remove_element(action + 1).unwrap();
+
+        (storyboard, old, new)
+    }
+
+    #[test]
+    fn test_html_marks_words_and_lines() {
+        // ARRANGE
+        let (storyboard, old, new) = generate_test_components();
+        // ACT
+        let html = render_html(&diff_variants(&old, &new), &storyboard);
+        // ASSERT
+        assert!(html.starts_with("<div class=\"variant-diff\">"));
+        assert!(html.contains("<p class=\"heading unchanged\">INT. HOUSE - DAY</p>"));
+        assert!(html.contains(
+            "<p class=\"action changed\">Rain &amp; <del>wind.</del> <ins>hail.</ins></p>"
+        ));
+        assert!(html.contains("<p class=\"character removed\">ANNA</p>"));
+    }
+
+    #[test]
+    fn test_ansi_marks_each_line() {
+        // ARRANGE
+        let (storyboard, old, new) = generate_test_components();
+        // ACT
+        let text = render_ansi(&diff_variants(&old, &new), &storyboard);
+        // ASSERT
+        let lines: Vec<_> = text.lines().collect();
+        assert_eq!(lines[0], "  INT. HOUSE - DAY");
+        assert_eq!(
+            lines[1],
+            "~ Rain & \u{1b}[31;9mwind.\u{1b}[0m \u{1b}[32mhail.\u{1b}[0m"
+        );
+        assert_eq!(lines[2], "-   \u{1b}[31;9mANNA\u{1b}[0m");
+        assert_eq!(lines[3], "-     \u{1b}[31;9mClose it.\u{1b}[0m");
+    }
+}
@@ -0,0 +1,426 @@
+use serde::Serialize;
+
+use crate::{
+    diff::align::{Step, align},
+    models::{
+        Character, Dialogue, DialogueBlock, HasMetadata, Id, SceneAction, SceneElement,
+        SceneVariant,
+    },
+};
+
+/// Elements whose words overlap at least this much are treated as one
+/// element that changed, rather than one removed and another inserted.
+const SIMILARITY_THRESHOLD: f32 = 0.5;
+
+/// A run of words that two texts share, or that only one of them has.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub enum WordDiff {
+    /// Words in both texts.
+    Equal(String),
+    /// Words only in the new text.
+    Inserted(String),
+    /// Words only in the old text.
+    Removed(String),
+}
+
+/// How one block of a changed dialogue differs.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub enum BlockDiff {
+    Unchanged(DialogueBlock),
+    Inserted(DialogueBlock),
+    Removed(DialogueBlock),
+    /// A block whose words changed. Both blocks are of the same kind.
+    Changed {
+        old: DialogueBlock,
+        new: DialogueBlock,
+        words: Vec<WordDiff>,
+    },
+}
+
+/// How one element differs between two variants.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub enum ElementDiff {
+    Unchanged(SceneElement),
+    Inserted(SceneElement),
+    Removed(SceneElement),
+    /// An action whose words changed.
+    ActionChanged {
+        old: SceneAction,
+        new: SceneAction,
+        words: Vec<WordDiff>,
+    },
+    /// A dialogue by the same speaker whose blocks changed.
+    DialogueChanged {
+        old: Box<Dialogue>,
+        new: Box<Dialogue>,
+        blocks: Vec<BlockDiff>,
+    },
+}
+
+/// The number of dialogues one speaker gained, lost, or had changed.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct SpeakerChanges {
+    pub speaker: Id<Character>,
+    pub inserted: usize,
+    pub removed: usize,
+    pub changed: usize,
+}
+
+/// The differences between two scene variants, in script order.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct VariantDiff {
+    pub old: Id<SceneVariant>,
+    pub new: Id<SceneVariant>,
+    /// The [`Metadata::version`](crate::models::Metadata) of each side.
+    pub old_version: u32,
+    pub new_version: u32,
+    /// The heading, word by word. It is all [`WordDiff::Equal`] if unchanged,
+    /// and empty if neither variant has a heading.
+    pub heading: Vec<WordDiff>,
+    /// The summary, word by word, as for the heading.
+    pub summary: Vec<WordDiff>,
+    /// Every element of either variant, aligned in script order.
+    pub elements: Vec<ElementDiff>,
+}
+
+impl VariantDiff {
+    /// Returns `true` if the variants have the same heading, summary, and
+    /// elements.
+    pub fn is_empty(&self) -> bool {
+        is_unchanged(&self.heading)
+            && is_unchanged(&self.summary)
+            && self
+                .elements
+                .iter()
+                .all(|e| matches!(e, ElementDiff::Unchanged(_)))
+    }
+
+    /// Returns the dialogue changes of each speaker who has any, ordered by
+    /// speaker ID.
+    pub fn speakers(&self) -> Vec<SpeakerChanges> {
+        let mut speakers: Vec<SpeakerChanges> = Vec::new();
+        for element in &self.elements {
+            let (speaker, count): (_, fn(&mut SpeakerChanges)) = match element {
+                ElementDiff::Inserted(SceneElement::Dialogue(d)) => {
+                    (d.speaker(), |s| s.inserted += 1)
+                }
+                ElementDiff::Removed(SceneElement::Dialogue(d)) => {
+                    (d.speaker(), |s| s.removed += 1)
+                }
+                ElementDiff::DialogueChanged { new, .. } => (new.speaker(), |s| s.changed += 1),
+                _ => continue,
+            };
+
+            let index = speakers
+                .iter()
+                .position(|s| s.speaker == speaker)
+                .unwrap_or_else(|| {
+                    speakers.push(SpeakerChanges {
+                        speaker,
+                        inserted: 0,
+                        removed: 0,
+                        changed: 0,
+                    });
+                    speakers.len() - 1
+                });
+            count(&mut speakers[index]);
+        }
+        speakers.sort_by_key(|s| s.speaker.uuid());
+
+        speakers
+    }
+}
+
+/// Compares two scene variants.
+///
+/// Elements are aligned in script order. An element in both variants is one
+/// with the same ID, or the same kind and speaker and mostly the same words;
+/// it is reported as changed, with a word-level diff, unless its text is
+/// identical. Dialogue is compared block by block the same way. Everything
+/// else is reported as inserted or removed.
+///
+/// To compare a variant with itself at an earlier
+/// [`Metadata::version`](crate::models::Metadata), keep a clone of it from
+/// that version and pass it as `old`. Its elements keep their IDs across
+/// edits, so they line up exactly.
+pub fn diff_variants(old: &SceneVariant, new: &SceneVariant) -> VariantDiff {
+    let heading_text =
+        |variant: &SceneVariant| variant.heading().map(|h| h.to_string()).unwrap_or_default();
+
+    let elements = align(old.elements(), new.elements(), element_score)
+        .into_iter()
+        .map(|step| match step {
+            Step::Old(i) => ElementDiff::Removed(old.elements()[i].clone()),
+            Step::New(j) => ElementDiff::Inserted(new.elements()[j].clone()),
+            Step::Both(i, j) => diff_element(&old.elements()[i], &new.elements()[j]),
+        })
+        .collect();
+
+    VariantDiff {
+        old: old.id(),
+        new: new.id(),
+        old_version: old.metadata().version,
+        new_version: new.metadata().version,
+        heading: diff_words(&heading_text(old), &heading_text(new)),
+        summary: diff_words(old.summary().as_str(), new.summary().as_str()),
+        elements,
+    }
+}
+
+/// Compares two texts word by word, grouping consecutive words of the same
+/// kind into one run.
+pub fn diff_words(old: &str, new: &str) -> Vec<WordDiff> {
+    let (old, new): (Vec<_>, Vec<_>) = (
+        old.split_whitespace().collect(),
+        new.split_whitespace().collect(),
+    );
+
+    let mut runs: Vec<WordDiff> = Vec::new();
+    for step in align(&old, &new, |a, b| u32::from(a == b)) {
+        let (word, run): (_, fn(String) -> WordDiff) = match step {
+            Step::Both(i, _) => (old[i], WordDiff::Equal),
+            Step::Old(i) => (old[i], WordDiff::Removed),
+            Step::New(j) => (new[j], WordDiff::Inserted),
+        };
+
+        match runs.last_mut() {
+            Some(WordDiff::Equal(text)) if matches!(step, Step::Both(..)) => push_word(text, word),
+            Some(WordDiff::Removed(text)) if matches!(step, Step::Old(_)) => push_word(text, word),
+            Some(WordDiff::Inserted(text)) if matches!(step, Step::New(_)) => push_word(text, word),
+            _ => runs.push(run(word.to_string())),
+        }
+    }
+
+    runs
+}
+
+fn push_word(text: &mut String, word: &str) {
+    text.push(' ');
+    text.push_str(word);
+}
+
+fn is_unchanged(words: &[WordDiff]) -> bool {
+    words.iter().all(|w| matches!(w, WordDiff::Equal(_)))
+}
+
+/// Returns how much two texts' words overlap, from `0.0` to `1.0`.
+fn similarity(old: &str, new: &str) -> f32 {
+    let (old, new): (Vec<_>, Vec<_>) = (
+        old.split_whitespace().collect(),
+        new.split_whitespace().collect(),
+    );
+    if old.is_empty() && new.is_empty() {
+        return 1.0;
+    }
+
+    let shared = align(&old, &new, |a, b| u32::from(a == b))
+        .into_iter()
+        .filter(|step| matches!(step, Step::Both(..)))
+        .count();
+
+    (2 * shared) as f32 / (old.len() + new.len()) as f32
+}
+
+/// Scores a pairing of two elements: `2` for the same element, `1` for a
+/// likely edit of it, and `0` for unrelated elements.
+///
+/// Only actions, or dialogue by the same speaker, are ever paired.
+fn element_score(old: &SceneElement, new: &SceneElement) -> u32 {
+    let score = match (old, new) {
+        (SceneElement::Action(old), SceneElement::Action(new)) => {
+            text_score(old.as_str(), new.as_str())
+        }
+        (SceneElement::Dialogue(old), SceneElement::Dialogue(new))
+            if old.speaker() == new.speaker() =>
+        {
+            text_score(&dialogue_text(old), &dialogue_text(new))
+        }
+        _ => return 0,
+    };
+
+    match old.id() == new.id() {
+        true => 2,
+        false => score,
+    }
+}
+
+/// Scores a pairing of two dialogue blocks, as [`element_score`] does.
+fn block_score(old: &DialogueBlock, new: &DialogueBlock) -> u32 {
+    match (old, new) {
+        (DialogueBlock::Text(_), DialogueBlock::Text(_))
+        | (DialogueBlock::Parenthetical(_), DialogueBlock::Parenthetical(_)) => {
+            text_score(block_text(old), block_text(new))
+        }
+        _ => 0,
+    }
+}
+
+fn text_score(old: &str, new: &str) -> u32 {
+    if old == new {
+        2
+    } else if similarity(old, new) >= SIMILARITY_THRESHOLD {
+        1
+    } else {
+        0
+    }
+}
+
+fn diff_element(old: &SceneElement, new: &SceneElement) -> ElementDiff {
+    match (old, new) {
+        (SceneElement::Action(old_action), SceneElement::Action(new_action)) => {
+            match old_action.as_str() == new_action.as_str() {
+                true => ElementDiff::Unchanged(new.clone()),
+                false => ElementDiff::ActionChanged {
+                    old: old_action.clone(),
+                    new: new_action.clone(),
+                    words: diff_words(old_action.as_str(), new_action.as_str()),
+                },
+            }
+        }
+        (SceneElement::Dialogue(old_dialogue), SceneElement::Dialogue(new_dialogue))
+            if old_dialogue.speaker() == new_dialogue.speaker() =>
+        {
+            if old_dialogue.content() == new_dialogue.content() {
+                return ElementDiff::Unchanged(new.clone());
+            }
+
+            let (old_blocks, new_blocks) = (old_dialogue.content(), new_dialogue.content());
+            let blocks = align(old_blocks, new_blocks, block_score)
+                .into_iter()
+                .map(|step| match step {
+                    Step::Old(i) => BlockDiff::Removed(old_blocks[i].clone()),
+                    Step::New(j) => BlockDiff::Inserted(new_blocks[j].clone()),
+                    Step::Both(i, j) if old_blocks[i] == new_blocks[j] => {
+                        BlockDiff::Unchanged(new_blocks[j].clone())
+                    }
+                    Step::Both(i, j) => BlockDiff::Changed {
+                        old: old_blocks[i].clone(),
+                        new: new_blocks[j].clone(),
+                        words: diff_words(block_text(&old_blocks[i]), block_text(&new_blocks[j])),
+                    },
+                })
+                .collect();
+
+            ElementDiff::DialogueChanged {
+                old: Box::new(old_dialogue.clone()),
+                new: Box::new(new_dialogue.clone()),
+                blocks,
+            }
+        }
+        _ => unreachable!("Only elements of the same kind and speaker are paired"),
+    }
+}
+
+fn block_text(block: &DialogueBlock) -> &str {
+    match block {
+        DialogueBlock::Text(text) => text.as_str(),
+        DialogueBlock::Parenthetical(parenthetical) => parenthetical.as_str(),
+    }
+}
+
+fn dialogue_text(dialogue: &Dialogue) -> String {
+    dialogue
+        .content()
+        .iter()
+        .map(block_text)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        diff::variant::{BlockDiff, ElementDiff, WordDiff, diff_variants, diff_words},
+        models::{
+            Character, Dialogue, DialogueBlock, DialogueText, HasMetadata, Id, Parenthetical,
+            Scene, SceneAction, SceneElement, SceneVariant,
+        },
+    };
+
+    /// A variant with an action and a line for each of two speakers.
+    fn generate_test_components() -> (SceneVariant, Vec<Id<Character>>) {
+        let scene = Scene::new();
+        let speakers = vec![Id::new(), Id::new()];
+        let mut variant = SceneVariant::new();
+        variant
+            .add_element(SceneElement::Action(
+                SceneAction::new("Rain falls on the roof.").unwrap(),
+            ))
+            .unwrap();
+        for (speaker, line) in speakers.iter().zip(["Close the window.", "It is stuck."]) {
+            let mut dialogue = Dialogue::new(scene.id(), *speaker);
+            dialogue
+                .add_dialogue_block(DialogueBlock::Text(DialogueText::new(line).unwrap()))
+                .unwrap();
+            variant
+                .add_element(SceneElement::Dialogue(dialogue))
+                .unwrap();
+        }
+
+        (variant, speakers)
+    }
+
+    #[test]
+    fn test_words_are_grouped_into_runs() {
+        // ARRANGE & ACT
+        let words = diff_words("Rain falls on the roof.", "Rain pours down on the roof.");
+        // ASSERT
+        assert_eq!(
+            words,
+            vec![
+                WordDiff::Equal("Rain".to_string()),
+                WordDiff::Removed("falls".to_string()),
+                WordDiff::Inserted("pours down".to_string()),
+                WordDiff::Equal("on the roof.".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_a_variant_against_an_earlier_version_of_itself() {
+        // ARRANGE
+        let (mut variant, _) = generate_test_components();
+        let before = variant.clone();
+        variant.edit_action(0, "Rain pours on the roof.").unwrap();
+        variant.remove_element(2).unwrap();
+        // ACT
+        let diff = diff_variants(&before, &variant);
+        // ASSERT
+        assert_eq!(diff.new_version, before.metadata().version + 2);
+        assert!(matches!(
+            diff.elements[0],
+            ElementDiff::ActionChanged { .. }
+        ));
+        assert!(matches!(diff.elements[1], ElementDiff::Unchanged(_)));
+        assert!(matches!(diff.elements[2], ElementDiff::Removed(_)));
+        assert!(!diff.is_empty());
+    }
+
+    #[test]
+    fn test_dialogue_changes_are_counted_per_speaker() {
+        // ARRANGE
+        let (old, speakers) = generate_test_components();
+        let mut new = old.duplicate();
+        new.insert_dialogue_block(
+            1,
+            0,
+            DialogueBlock::Parenthetical(Parenthetical::new("sharply").unwrap()),
+        )
+        .unwrap();
+        new.remove_element(2).unwrap();
+        // ACT
+        let diff = diff_variants(&old, &new);
+        // ASSERT
+        let ElementDiff::DialogueChanged { blocks, .. } = &diff.elements[1] else {
+            panic!("Expected changed dialogue");
+        };
+        assert!(matches!(blocks[0], BlockDiff::Inserted(_)));
+        let counts: Vec<_> = diff
+            .speakers()
+            .into_iter()
+            .map(|s| (s.speaker, s.inserted, s.removed, s.changed))
+            .collect();
+        assert!(counts.contains(&(speakers[0], 0, 0, 1)));
+        assert!(counts.contains(&(speakers[1], 0, 1, 0)));
+    }
+}
@@ -0,0 +1,185 @@
+use crate::{
+    models::{
+        BranchPolicy, Character, CharacterName, Id, NarrativeError, NumberedScene, Scene,
+        SceneElement, SceneHeading, SceneNumber, SceneVariant, Storyboard,
+    },
+    utils::InputError,
+};
+
+/// Why setting imported content cannot fail: nothing read from a file has
+/// been locked yet.
+pub(crate) const UNLOCKED: &str = "imported content is never locked";
+
+/// Accumulates content read from a script file, then assembles it into a
+/// [`Storyboard`].
+///
+/// Each scene is tagged with a format-specific source position (a line number
+/// for Fountain, a paragraph index for Final Draft) so importers can report
+/// where a failure came from.
+#[derive(Default)]
+pub(crate) struct ScriptBuilder {
+    storyboard: Storyboard,
+    /// Parsed scenes in document order, with the position each one started at.
+    scenes: Vec<(usize, Scene)>,
+    /// Scene numbers read from the script, omitted ones included, in document order.
+    numbers: Vec<NumberedScene>,
+}
+
+impl ScriptBuilder {
+    /// Returns the storyboard under construction, for title-page details.
+    pub(crate) fn storyboard_mut(&mut self) -> &mut Storyboard {
+        &mut self.storyboard
+    }
+
+    /// Returns `true` once the first scene has been started.
+    pub(crate) fn has_scenes(&self) -> bool {
+        !self.scenes.is_empty()
+    }
+
+    /// Starts a new scene, optionally headed and numbered, and returns it.
+    pub(crate) fn start_scene(
+        &mut self,
+        position: usize,
+        heading: Option<SceneHeading>,
+        number: Option<SceneNumber>,
+    ) -> &mut Scene {
+        let mut scene = Scene::new();
+        if let Some(number) = number {
+            self.numbers
+                .push(NumberedScene::new(number, Some(scene.id())));
+        }
+        let variant_id = *scene.active_variant();
+
+        if let (Some(heading), Some(variant)) = (heading, scene.variants_mut().get_mut(&variant_id))
+        {
+            variant.set_heading(heading).expect(UNLOCKED);
+        }
+
+        self.scenes.push((position, scene));
+        self.current_scene(position)
+    }
+
+    /// Records the number of a scene the script shows as `OMITTED`.
+    pub(crate) fn omit_scene(&mut self, number: SceneNumber) {
+        self.numbers.push(NumberedScene::new(number, None));
+    }
+
+    /// Returns the character named by a cue, creating it on first sight.
+    ///
+    /// Cues are matched as [`Storyboard::character_by_name`] matches them,
+    /// so a cue under an alias is the aliased character's.
+    pub(crate) fn character(&mut self, name: &str) -> Result<Id<Character>, InputError> {
+        if let Some(character) = self.storyboard.character_by_name(name) {
+            return Ok(character.id());
+        }
+
+        let character = Character::new(CharacterName::new(name)?);
+        let id = character.id();
+        self.storyboard.add_character(character).expect(UNLOCKED);
+
+        Ok(id)
+    }
+
+    /// Returns the scene currently being written to.
+    ///
+    /// Content that appears before the first heading opens a scene without one.
+    pub(crate) fn current_scene(&mut self, position: usize) -> &mut Scene {
+        if self.scenes.is_empty() {
+            self.scenes.push((position, Scene::new()));
+        }
+
+        let (_, scene) = self.scenes.last_mut().expect("a scene was just ensured");
+        scene
+    }
+
+    /// Returns the active variant of the scene currently being written to.
+    pub(crate) fn current_variant(&mut self, position: usize) -> &mut SceneVariant {
+        let scene = self.current_scene(position);
+        let variant_id = *scene.active_variant();
+
+        scene
+            .variants_mut()
+            .get_mut(&variant_id)
+            .expect("a scene always holds its active variant")
+    }
+
+    /// Appends an element to the active variant of the current scene.
+    pub(crate) fn add_element(&mut self, position: usize, element: SceneElement) {
+        self.current_variant(position)
+            .add_element(element)
+            .expect(UNLOCKED);
+    }
+
+    /// Adds the parsed scenes to the narrative as one path rooted at the first scene.
+    ///
+    /// If the script carried scene numbers they are kept, frozen, and any
+    /// scene that had none gets an insert number as
+    /// [`Narrative::number_scenes`](crate::models::Narrative::number_scenes)
+    /// gives them.
+    ///
+    /// # Errors
+    ///
+    /// Returns the position of the scene that could not be placed, along with
+    /// the [`NarrativeError`] that stopped it.
+    pub(crate) fn finish(mut self) -> Result<Storyboard, (usize, NarrativeError)> {
+        let narrative = self.storyboard.narrative_mut();
+        let mut previous: Option<Id<SceneVariant>> = None;
+        let mut root = None;
+
+        for (position, scene) in self.scenes {
+            let variant_id = *scene.active_variant();
+            let with_position = |e: NarrativeError| (position, e);
+
+            narrative.add_scene(scene).map_err(with_position)?;
+            match previous {
+                None => narrative
+                    .set_variant_as_root(variant_id)
+                    .map_err(with_position)?,
+                Some(src) => narrative
+                    .link_variants(src, variant_id)
+                    .map_err(with_position)?,
+            };
+
+            previous = Some(variant_id);
+            root = root.or(Some((position, variant_id)));
+        }
+
+        if !self.numbers.is_empty() {
+            narrative.set_scene_numbers(self.numbers);
+            if let Some((position, root)) = root {
+                narrative
+                    .number_scenes(root, &BranchPolicy::default())
+                    .map_err(|e| (position, e))?;
+            }
+        }
+
+        Ok(self.storyboard)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        formats::builder::ScriptBuilder,
+        models::{Character, CharacterName},
+    };
+
+    #[test]
+    fn test_cues_under_an_alias_are_the_aliased_character() {
+        // ARRANGE
+        let mut script = ScriptBuilder::default();
+        let mut kit = Character::new(CharacterName::new("Katherine").unwrap());
+        kit.add_alias(CharacterName::new("Kit").unwrap());
+        let kit_id = kit.id();
+        script.storyboard_mut().add_character(kit).unwrap();
+        // ACT
+        let by_alias = script.character("KIT").unwrap();
+        let by_name = script.character("katherine").unwrap();
+        let newcomer = script.character("RAY").unwrap();
+        // ASSERT
+        assert_eq!(by_alias, kit_id);
+        assert_eq!(by_name, kit_id);
+        assert_ne!(newcomer, kit_id);
+        assert_eq!(script.storyboard_mut().characters().len(), 2);
+    }
+}
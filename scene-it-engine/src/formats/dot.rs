@@ -0,0 +1,223 @@
+//! Writing a narrative's scene graph, and the relationships between a
+//! storyboard's characters, in the [Graphviz](https://graphviz.org) DOT language.
+
+use std::fmt::Write;
+
+use crate::{
+    formats::graph::{
+        GraphView, Node, character_id, cluster_id, node_id, relationship_label, sorted_characters,
+        sorted_relationships,
+    },
+    models::{Narrative, Relationship, Storyboard},
+};
+
+/// Writes the narrative's scene graph as a DOT digraph.
+///
+/// Each scene becomes a `cluster_` subgraph holding its variants, labelled
+/// with the active variant's heading. Variants are labelled with their
+/// heading and summary; the active variant of each scene is drawn solid and
+/// the others dashed. Roots are drawn bold with a double border, and
+/// variants no root can reach are filled red. Edges are drawn grey, and
+/// edges into unreachable variants dashed.
+///
+/// Render it with, for example, `dot -Tsvg story.dot -o story.svg`.
+pub fn write(narrative: &Narrative) -> String {
+    let view = GraphView::new(narrative);
+    let mut output = String::from(
+        "digraph narrative {\n  \
+         rankdir=TB;\n  \
+         node [shape=box, style=\"rounded\", fontname=\"Courier\"];\n  \
+         edge [color=\"#555555\"];\n",
+    );
+
+    for cluster in &view.clusters {
+        let _ = write!(
+            output,
+            "\n  subgraph cluster_{} {{\n    label=\"{}\";\n    style=\"rounded,dashed\";\n    color=\"#999999\";\n",
+            cluster_id(cluster.scene),
+            escape(&cluster.label)
+        );
+        for node in &cluster.nodes {
+            write_node(&mut output, "    ", node);
+        }
+        output.push_str("  }\n");
+    }
+
+    if !view.unowned.is_empty() {
+        output.push('\n');
+        for node in &view.unowned {
+            write_node(&mut output, "  ", node);
+        }
+    }
+
+    if !view.edges.is_empty() {
+        output.push('\n');
+    }
+    let unreachable: Vec<_> = view
+        .nodes()
+        .filter(|n| n.unreachable)
+        .map(|n| n.id)
+        .collect();
+    for (src, dest) in &view.edges {
+        let style = if unreachable.contains(dest) {
+            " [style=dashed, color=\"#cc0000\"]"
+        } else {
+            ""
+        };
+        let _ = writeln!(output, "  {} -> {}{style};", node_id(*src), node_id(*dest));
+    }
+
+    output.push_str("}\n");
+    output
+}
+
+/// Writes the given relationships between the storyboard's characters as a
+/// DOT digraph.
+///
+/// Every character becomes a node labelled with their name, related or not.
+/// Each relationship is an edge from the first character to the second,
+/// labelled with its kind and note; relationships scoped to a span of
+/// scenes are drawn dashed. Pass [`Storyboard::relationships`] for the whole
+/// story, or [`Storyboard::relationships_as_of`] for one moment in it.
+pub fn write_relationships(storyboard: &Storyboard, relationships: &[&Relationship]) -> String {
+    let mut output = String::from(
+        "digraph relationships {\n  \
+         rankdir=LR;\n  \
+         node [shape=ellipse, fontname=\"Courier\"];\n  \
+         edge [color=\"#555555\", fontsize=10];\n",
+    );
+
+    for character in sorted_characters(storyboard) {
+        let _ = writeln!(
+            output,
+            "  {} [label=\"{}\"];",
+            character_id(character.id()),
+            escape(character.name())
+        );
+    }
+
+    for relationship in sorted_relationships(relationships) {
+        let scoped = relationship.starts().is_some() || relationship.ends().is_some();
+        let style = if scoped { ", style=dashed" } else { "" };
+        let _ = writeln!(
+            output,
+            "  {} -> {} [label=\"{}\"{style}];",
+            character_id(relationship.from()),
+            character_id(relationship.to()),
+            escape(&relationship_label(relationship))
+        );
+    }
+
+    output.push_str("}\n");
+    output
+}
+
+/// Writes a node statement with the styling for its state.
+fn write_node(output: &mut String, indent: &str, node: &Node) {
+    let mut label = escape(&node.heading);
+    if !node.summary.is_empty() {
+        label.push_str("\\n");
+        label.push_str(&escape(&node.summary));
+    }
+
+    let mut style = vec!["rounded"];
+    let mut attributes = Vec::new();
+    if !node.active {
+        style.push("dashed");
+    }
+    if node.root {
+        style.push("bold");
+        attributes.push("peripheries=2".to_string());
+    }
+    if node.unreachable {
+        style.push("filled");
+        attributes.push("color=\"#cc0000\"".to_string());
+        attributes.push("fillcolor=\"#f4cccc\"".to_string());
+    }
+    attributes.insert(0, format!("style=\"{}\"", style.join(",")));
+
+    let _ = writeln!(
+        output,
+        "{indent}{} [label=\"{label}\", {}];",
+        node_id(node.id),
+        attributes.join(", ")
+    );
+}
+
+/// Escapes text for a double-quoted DOT string.
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        formats::{
+            dot::{write, write_relationships},
+            fountain,
+        },
+        models::{
+            Character, CharacterName, Narrative, Relationship, RelationshipKind, Scene, Storyboard,
+            Summary,
+        },
+    };
+
+    /// Two scenes in a chain from a root, and one scene no root reaches.
+    fn generate_test_components() -> Narrative {
+        let storyboard =
+            fountain::parse("INT. HOUSE - DAY\n\nRain.\n\nEXT. \"YARD\" - NIGHT\n\nWind.\n")
+                .unwrap();
+        let mut narrative = storyboard.narrative().clone();
+        narrative.add_scene(Scene::new()).unwrap();
+        narrative
+    }
+
+    #[test]
+    fn test_scenes_become_clusters_of_labelled_variants() {
+        // ARRANGE
+        let narrative = generate_test_components();
+        // ACT
+        let dot = write(&narrative);
+        // ASSERT
+        assert!(dot.starts_with("digraph narrative {"));
+        assert_eq!(dot.matches("subgraph cluster_").count(), 3);
+        assert!(dot.contains("label=\"INT. HOUSE - DAY"));
+        assert!(dot.contains("EXT. \\\"YARD\\\" - NIGHT"));
+        assert_eq!(dot.matches(" -> ").count(), 1);
+    }
+
+    #[test]
+    fn test_roots_and_unreachable_variants_are_styled() {
+        // ARRANGE
+        let narrative = generate_test_components();
+        // ACT
+        let dot = write(&narrative);
+        // ASSERT
+        assert_eq!(dot.matches("peripheries=2").count(), 1);
+        assert_eq!(dot.matches("fillcolor=\"#f4cccc\"").count(), 1);
+        assert!(dot.contains("(no heading)"));
+    }
+
+    #[test]
+    fn test_relationships_become_labelled_edges_between_characters() {
+        // ARRANGE
+        let mut storyboard = Storyboard::default();
+        let kit = Character::new(CharacterName::new("Kit").unwrap());
+        let ray = Character::new(CharacterName::new("Ray \"Red\"").unwrap());
+        let mut boss = Relationship::new(kit.id(), ray.id(), RelationshipKind::Employer);
+        boss.set_note(Summary::new("Since the mill closed").unwrap());
+        storyboard.add_character(kit).unwrap();
+        storyboard.add_character(ray).unwrap();
+        storyboard.add_relationship(boss).unwrap();
+        // ACT
+        let dot = write_relationships(&storyboard, &storyboard.relationships());
+        // ASSERT
+        assert!(dot.starts_with("digraph relationships {"));
+        assert!(dot.contains("[label=\"Ray \\\"Red\\\"\"]"));
+        assert!(dot.contains(" -> "));
+        assert!(dot.contains("label=\"employer of: Since the mill closed\""));
+        assert!(!dot.contains("style=dashed"));
+    }
+}
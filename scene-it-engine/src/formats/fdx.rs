@@ -0,0 +1,653 @@
+//! Reading and writing Final Draft (`.fdx`) screenplays.
+
+use std::{collections::HashMap, fmt::Write};
+
+use quick_xml::{
+    Reader,
+    escape::{escape, partial_escape},
+    events::Event,
+};
+use serde::Serialize;
+
+use crate::{
+    formats::{
+        HeadingError, OMITTED, WriteError,
+        builder::{ScriptBuilder, UNLOCKED},
+        parenthetical_text, parse_scene_heading,
+    },
+    models::{
+        Author, AuthorName, BranchPolicy, Dialogue, DialogueBlock, DialogueText, HasMetadata, Id,
+        NarrativeError, Parenthetical, SceneAction, SceneElement, SceneNumber, SceneVariant,
+        ScriptScene, Storyboard, Title,
+    },
+    utils::InputError,
+};
+
+/// Storyboard metadata property holding the title page paragraphs other than
+/// the title and author credit, one per line.
+const TITLE_PAGE_PROPERTY: &str = "fdx.title_page.extra";
+
+/// Storyboard metadata property holding the type of each paragraph imported
+/// as action that was not an `Action` paragraph, one `<action ID> <type>`
+/// pair per line.
+const PARAGRAPH_TYPES_PROPERTY: &str = "fdx.paragraph_types";
+
+/// An error raised while parsing a Final Draft document.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct FdxError {
+    /// The byte offset in the document where the error was detected.
+    pub position: u64,
+    /// What went wrong at that position.
+    pub kind: FdxErrorKind,
+}
+
+/// The reasons a Final Draft document can fail to parse.
+#[derive(Debug, Serialize, PartialEq)]
+pub enum FdxErrorKind {
+    /// The document is not well-formed XML.
+    Xml(String),
+    /// A paragraph's text failed input validation.
+    InvalidInput(InputError),
+    /// A `Scene Heading` paragraph could not be read as a heading.
+    InvalidSceneHeading(HeadingError),
+    /// A `Parenthetical` or `Dialogue` paragraph appeared without a `Character` before it.
+    DialogueWithoutCharacter,
+    /// A lower-level [`NarrativeError`] occurred while chaining the parsed scenes.
+    Narrative(NarrativeError),
+}
+
+impl FdxError {
+    fn new(position: u64, kind: FdxErrorKind) -> Self {
+        Self { position, kind }
+    }
+
+    fn input(position: u64) -> impl FnOnce(InputError) -> Self {
+        move |e| Self::new(position, FdxErrorKind::InvalidInput(e))
+    }
+}
+
+/// Where in the document the paragraph being read lives.
+#[derive(Clone, Copy, PartialEq)]
+enum Section {
+    Body,
+    TitlePage,
+    Other,
+}
+
+/// Parses a Final Draft document into a new [`Storyboard`].
+///
+/// Body paragraphs map onto the engine's types by their `Type` attribute:
+///
+/// - `Scene Heading` starts a new [`Scene`](crate::models::Scene); its
+///   `Number` attribute becomes the scene's number. A heading reading
+///   `OMITTED` starts no scene, but keeps its number as an omitted one
+/// - `Character` starts a [`Dialogue`], followed by `Parenthetical` and
+///   `Dialogue` paragraphs as its [`DialogueBlock`]s
+/// - `Action` and every other type become a [`SceneAction`]. The types of
+///   `Transition`, `Shot`, `General`, and other paragraphs are kept in the
+///   storyboard's metadata properties so they can be written back
+///
+/// The first line of the title page becomes the storyboard title and the line
+/// after a `Written by` credit becomes the author. The rest of the title page
+/// is kept in the storyboard's metadata properties.
+///
+/// As with Fountain, the scenes are chained in document order into a single
+/// path through the scene graph, rooted at the first scene. If the document
+/// carries scene numbers, they are frozen as the narrative's numbering.
+///
+/// # Errors
+///
+/// Returns an [`FdxError`] with the byte offset of the paragraph that could
+/// not be read.
+pub fn parse(input: &str) -> Result<Storyboard, FdxError> {
+    let mut reader = Reader::from_str(input);
+    let mut script = ScriptBuilder::default();
+    let mut title_page: Vec<String> = Vec::new();
+    let mut dialogue: Option<Dialogue> = None;
+
+    let mut section = Section::Other;
+    let mut paragraph: Option<(String, Option<String>)> = None;
+    let mut in_text = false;
+    let mut text = String::new();
+
+    loop {
+        let position = reader.buffer_position();
+        let event = reader.read_event().map_err(|e| {
+            FdxError::new(reader.error_position(), FdxErrorKind::Xml(e.to_string()))
+        })?;
+        let xml_error =
+            |e: &dyn std::fmt::Display| FdxError::new(position, FdxErrorKind::Xml(e.to_string()));
+
+        match event {
+            Event::Start(tag) => match tag.name().as_ref() {
+                b"Content" if section == Section::Other => section = Section::Body,
+                b"TitlePage" => section = Section::TitlePage,
+                b"Paragraph" => {
+                    let mut kind = String::new();
+                    let mut number = None;
+                    for attribute in tag.attributes() {
+                        let attribute = attribute.map_err(|e| xml_error(&e))?;
+                        let value = attribute.unescape_value().map_err(|e| xml_error(&e))?;
+                        match attribute.key.as_ref() {
+                            b"Type" => kind = value.into_owned(),
+                            b"Number" => number = Some(value.into_owned()),
+                            _ => {}
+                        }
+                    }
+                    paragraph = Some((kind, number));
+                    text.clear();
+                }
+                b"Text" => in_text = true,
+                _ => {}
+            },
+            Event::Text(content) if in_text => {
+                text.push_str(&content.unescape().map_err(|e| xml_error(&e))?);
+            }
+            Event::End(tag) => match tag.name().as_ref() {
+                b"Text" => in_text = false,
+                b"TitlePage" => section = Section::Other,
+                b"Content" if section == Section::Body => section = Section::Other,
+                b"Paragraph" => {
+                    let Some((kind, number)) = paragraph.take() else {
+                        continue;
+                    };
+
+                    match section {
+                        Section::TitlePage => title_page.push(text.trim().to_string()),
+                        Section::Body => read_paragraph(
+                            &mut script,
+                            &mut dialogue,
+                            (&kind, number, text.trim()),
+                            position,
+                        )?,
+                        Section::Other => {}
+                    }
+                }
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    if let Some(dialogue) = dialogue.take() {
+        let position = reader.buffer_position();
+        script.add_element(position as usize, SceneElement::Dialogue(dialogue));
+    }
+
+    read_title_page(&mut script, &title_page)?;
+
+    script
+        .finish()
+        .map_err(|(position, e)| FdxError::new(position as u64, FdxErrorKind::Narrative(e)))
+}
+
+/// Adds one body paragraph to the script.
+///
+/// `dialogue` holds the speech opened by the most recent `Character`
+/// paragraph; any other paragraph type closes it.
+fn read_paragraph(
+    script: &mut ScriptBuilder,
+    dialogue: &mut Option<Dialogue>,
+    (kind, number, text): (&str, Option<String>, &str),
+    position: u64,
+) -> Result<(), FdxError> {
+    let scene_position = position as usize;
+
+    if !matches!(kind, "Parenthetical" | "Dialogue")
+        && let Some(dialogue) = dialogue.take()
+    {
+        script.add_element(scene_position, SceneElement::Dialogue(dialogue));
+    }
+
+    match kind {
+        "Scene Heading" => {
+            let number = number
+                .filter(|n| !n.trim().is_empty())
+                .map(|n| SceneNumber::new(&n))
+                .transpose()
+                .map_err(FdxError::input(position))?;
+
+            if text.eq_ignore_ascii_case(OMITTED) {
+                if let Some(number) = number {
+                    script.omit_scene(number);
+                }
+                return Ok(());
+            }
+
+            let heading = parse_scene_heading(text)
+                .map_err(|e| FdxError::new(position, FdxErrorKind::InvalidSceneHeading(e)))?;
+            script.start_scene(scene_position, Some(heading), number);
+        }
+        "Character" => {
+            let name = text.split('(').next().unwrap_or(text).trim();
+            let speaker = script.character(name).map_err(FdxError::input(position))?;
+            let scene_id = script.current_scene(scene_position).id();
+            *dialogue = Some(Dialogue::new(scene_id, speaker));
+        }
+        "Parenthetical" | "Dialogue" => {
+            let dialogue = dialogue.as_mut().ok_or(FdxError::new(
+                position,
+                FdxErrorKind::DialogueWithoutCharacter,
+            ))?;
+            let block = if kind == "Parenthetical" {
+                let inner = text.strip_prefix('(').unwrap_or(text);
+                let inner = inner.strip_suffix(')').unwrap_or(inner);
+                DialogueBlock::Parenthetical(
+                    Parenthetical::new(inner).map_err(FdxError::input(position))?,
+                )
+            } else {
+                DialogueBlock::Text(DialogueText::new(text).map_err(FdxError::input(position))?)
+            };
+            dialogue.add_dialogue_block(block).expect(UNLOCKED);
+        }
+        _ if text.is_empty() => {}
+        _ => {
+            let action = SceneAction::new(text).map_err(FdxError::input(position))?;
+            if kind != "Action" {
+                record_paragraph_type(script, action.id(), kind);
+            }
+            script.add_element(scene_position, SceneElement::Action(action));
+        }
+    }
+
+    Ok(())
+}
+
+/// Records the type of a paragraph read as action, so it is written back as
+/// that type.
+fn record_paragraph_type(script: &mut ScriptBuilder, action: Id<SceneAction>, kind: &str) {
+    let types = script
+        .storyboard_mut()
+        .metadata_mut()
+        .properties
+        .entry(PARAGRAPH_TYPES_PROPERTY.to_string())
+        .or_default();
+    let _ = writeln!(types, "{action} {kind}");
+}
+
+/// Derives the storyboard title and author from the title page, and keeps the
+/// rest of it.
+fn read_title_page(script: &mut ScriptBuilder, lines: &[String]) -> Result<(), FdxError> {
+    let storyboard = script.storyboard_mut();
+    let mut non_empty = lines.iter().enumerate().filter(|(_, l)| !l.is_empty());
+    let mut owned = Vec::new();
+
+    if let Some((index, title)) = non_empty.next() {
+        storyboard
+            .update_title(Title::new(title).map_err(FdxError::input(0))?)
+            .expect(UNLOCKED);
+        owned.push(index);
+    }
+
+    let is_credit = |(_, l): &(usize, &String)| l.to_lowercase().ends_with("by");
+    let mut credit = non_empty.skip_while(|l| !is_credit(l));
+    if let (Some((credit, _)), Some((index, author))) = (credit.next(), credit.next()) {
+        let name = AuthorName::new(author).map_err(FdxError::input(0))?;
+        storyboard.add_author(Author::new(name)).expect(UNLOCKED);
+        owned.extend([credit, index]);
+    }
+
+    let rest: Vec<_> = lines
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| !owned.contains(i))
+        .map(|(_, l)| l.as_str())
+        .collect();
+    let start = rest.iter().position(|l| !l.is_empty());
+    let end = rest.iter().rposition(|l| !l.is_empty());
+    if let (Some(start), Some(end)) = (start, end) {
+        storyboard.metadata_mut().properties.insert(
+            TITLE_PAGE_PROPERTY.to_string(),
+            rest[start..=end].join("\n"),
+        );
+    }
+
+    Ok(())
+}
+
+/// Writes the path through the storyboard starting at `root` as a Final Draft document.
+///
+/// Each scene on the path returned by
+/// [`Narrative::linearize_from`](crate::models::Narrative::linearize_from)
+/// under `policy` is written from the variant the path visits, with the scene's number (if any)
+/// on its heading. The numbers of removed scenes are written where they fall
+/// as `OMITTED` headings. Actions imported from other paragraph types are
+/// written back as those types. The title page is built from the storyboard
+/// title and authors, followed by the rest of any title page kept from import.
+///
+/// # Errors
+///
+/// - [`WriteError::UnknownCharacter`] if a dialogue's speaker is not among
+///   the storyboard's characters.
+/// - [`WriteError::Narrative`] if the path cannot be linearized.
+pub fn write(
+    storyboard: &Storyboard,
+    root: Id<SceneVariant>,
+    policy: &BranchPolicy,
+) -> Result<String, WriteError> {
+    let mut output = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"no\" ?>\n\
+         <FinalDraft DocumentType=\"Script\" Template=\"No\" Version=\"5\">\n\n  <Content>\n",
+    );
+
+    let types: HashMap<&str, &str> = storyboard
+        .metadata()
+        .properties
+        .get(PARAGRAPH_TYPES_PROPERTY)
+        .into_iter()
+        .flat_map(|kept| kept.lines())
+        .filter_map(|line| line.split_once(' '))
+        .collect();
+
+    for entry in storyboard.narrative().linearize_numbered(root, policy)? {
+        let (number, variant) = match entry {
+            ScriptScene::Scene {
+                number, variant, ..
+            } => (number, variant),
+            ScriptScene::Omitted(number) => {
+                write_paragraph(&mut output, 4, "Scene Heading", Some(number), OMITTED);
+                continue;
+            }
+        };
+
+        if let Some(heading) = variant.heading() {
+            write_paragraph(
+                &mut output,
+                4,
+                "Scene Heading",
+                number,
+                &heading.to_string(),
+            );
+        }
+
+        for element in variant.elements() {
+            match element {
+                SceneElement::Action(action) => {
+                    let id = action.id().to_string();
+                    let kind = types.get(id.as_str()).copied().unwrap_or("Action");
+                    write_paragraph(&mut output, 4, kind, None, action.as_str());
+                }
+                SceneElement::Dialogue(dialogue) => {
+                    let speaker = storyboard
+                        .character(&dialogue.speaker())
+                        .ok_or(WriteError::UnknownCharacter(dialogue.speaker()))?;
+                    write_paragraph(&mut output, 4, "Character", None, speaker.name());
+
+                    for block in dialogue.content() {
+                        match block {
+                            DialogueBlock::Parenthetical(p) => {
+                                let text = parenthetical_text(p);
+                                write_paragraph(&mut output, 4, "Parenthetical", None, &text);
+                            }
+                            DialogueBlock::Text(t) => {
+                                write_paragraph(&mut output, 4, "Dialogue", None, t.as_str());
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    output.push_str("  </Content>\n");
+
+    let title_page = title_page_lines(storyboard);
+    if !title_page.is_empty() {
+        output.push_str("\n  <TitlePage>\n    <Content>\n");
+        for line in title_page {
+            let _ = writeln!(
+                output,
+                "      <Paragraph Alignment=\"Center\" Type=\"Action\">\n        <Text>{}</Text>\n      </Paragraph>",
+                partial_escape(&line)
+            );
+        }
+        output.push_str("    </Content>\n  </TitlePage>\n");
+    }
+
+    output.push_str("\n</FinalDraft>\n");
+    Ok(output)
+}
+
+/// Returns the title page built from the title and authors, followed by the
+/// rest of any title page kept from import.
+fn title_page_lines(storyboard: &Storyboard) -> Vec<String> {
+    let mut authors: Vec<_> = storyboard.authors().iter().map(|a| a.name()).collect();
+    authors.sort_unstable();
+
+    let mut lines = Vec::new();
+    if let Some(title) = storyboard.title() {
+        lines.extend([title.to_string(), String::new()]);
+    }
+    if !authors.is_empty() {
+        lines.extend(["Written by".to_string(), String::new(), authors.join(" & ")]);
+    }
+    if let Some(kept) = storyboard.metadata().properties.get(TITLE_PAGE_PROPERTY) {
+        if lines.last().is_some_and(|l| !l.is_empty()) {
+            lines.push(String::new());
+        }
+        lines.extend(kept.lines().map(String::from));
+    }
+
+    lines
+}
+
+/// Appends a `<Paragraph>` of the given type, indented by `indent` spaces.
+fn write_paragraph(
+    output: &mut String,
+    indent: usize,
+    kind: &str,
+    number: Option<&SceneNumber>,
+    text: &str,
+) {
+    let pad = " ".repeat(indent);
+    let number = number
+        .map(|n| format!(" Number=\"{}\"", escape(n.as_str())))
+        .unwrap_or_default();
+
+    let _ = writeln!(
+        output,
+        "{pad}<Paragraph{number} Type=\"{kind}\">\n{pad}  <Text>{}</Text>\n{pad}</Paragraph>",
+        partial_escape(text)
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        formats::fdx::{FdxErrorKind, parse, write},
+        models::{
+            Author, AuthorName, BranchPolicy, HasMetadata, Id, SceneVariant, ScriptScene,
+            Storyboard, Title,
+        },
+    };
+
+    const FIXTURE: &str = include_str!("../../../data/screenplay1.fdx");
+
+    fn root(storyboard: &Storyboard) -> Id<SceneVariant> {
+        *storyboard
+            .narrative()
+            .graph()
+            .roots()
+            .iter()
+            .next()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_fixture_imports_every_scene_with_its_number() {
+        // ARRANGE & ACT
+        let storyboard = parse(FIXTURE).unwrap();
+        let scenes: Vec<_> = storyboard
+            .narrative()
+            .linearize_from(root(&storyboard), &BranchPolicy::default())
+            .unwrap();
+        // ASSERT
+        assert_eq!(storyboard.title().as_deref(), Some("Encryption"));
+        assert_eq!(storyboard.authors()[0].name(), "Donte");
+        assert_eq!(storyboard.characters().len(), 18);
+        assert_eq!(scenes.len(), 10);
+        let numbers: Vec<_> = scenes
+            .iter()
+            .map(|(s, _)| {
+                let number = storyboard.narrative().scene_number(&s.id()).unwrap();
+                number.as_str()
+            })
+            .collect();
+        assert_eq!(numbers, ["1", "2", "3", "4", "5", "6", "7", "8", "9", "10"]);
+    }
+
+    #[test]
+    fn test_fixture_round_trips_through_export() {
+        // ARRANGE
+        let storyboard = parse(FIXTURE).unwrap();
+        // ACT
+        let exported = write(&storyboard, root(&storyboard), &BranchPolicy::default()).unwrap();
+        let reimported = parse(&exported).unwrap();
+        // ASSERT
+        assert_eq!(
+            exported.matches("<Paragraph").count(),
+            FIXTURE.matches("<Paragraph").count()
+        );
+        assert_eq!(
+            write(&reimported, root(&reimported), &BranchPolicy::default()).unwrap(),
+            exported
+        );
+        assert_eq!(
+            reimported.metadata().properties,
+            storyboard.metadata().properties
+        );
+    }
+
+    #[test]
+    fn test_dialogue_without_character_fails() {
+        // ARRANGE
+        let document = r#"<FinalDraft><Content>
+            <Paragraph Type="Scene Heading"><Text>INT. HOUSE - DAY</Text></Paragraph>
+            <Paragraph Type="Dialogue"><Text>Hello?</Text></Paragraph>
+        </Content></FinalDraft>"#;
+        // ACT
+        let response = parse(document);
+        // ASSERT
+        assert_eq!(
+            response.map(|_| ()).unwrap_err().kind,
+            FdxErrorKind::DialogueWithoutCharacter
+        );
+    }
+
+    #[test]
+    fn test_escaped_text_round_trips() {
+        // ARRANGE
+        let document = r#"<FinalDraft><Content>
+            <Paragraph Type="Action"><Text>Salt &amp; pepper &lt;3</Text></Paragraph>
+        </Content></FinalDraft>"#;
+        let storyboard = parse(document).unwrap();
+        // ACT
+        let exported = write(&storyboard, root(&storyboard), &BranchPolicy::default()).unwrap();
+        // ASSERT
+        assert!(exported.contains("<Text>Salt &amp; pepper &lt;3</Text>"));
+    }
+
+    #[test]
+    fn test_omitted_scenes_round_trip_in_place() {
+        // ARRANGE
+        let document = r#"<FinalDraft><Content>
+            <Paragraph Number="1" Type="Scene Heading"><Text>INT. HOUSE - DAY</Text></Paragraph>
+            <Paragraph Number="2" Type="Scene Heading"><Text>OMITTED</Text></Paragraph>
+            <Paragraph Number="3" Type="Scene Heading"><Text>EXT. YARD - NIGHT</Text></Paragraph>
+        </Content></FinalDraft>"#;
+        let storyboard = parse(document).unwrap();
+        // ACT
+        let exported = write(&storyboard, root(&storyboard), &BranchPolicy::default()).unwrap();
+        let reimported = parse(&exported).unwrap();
+        let script = storyboard
+            .narrative()
+            .linearize_numbered(root(&storyboard), &BranchPolicy::default())
+            .unwrap();
+        // ASSERT
+        assert_eq!(script.len(), 3);
+        assert!(matches!(script[1], ScriptScene::Omitted(n) if n.as_str() == "2"));
+        assert!(exported.contains(
+            "<Paragraph Number=\"2\" Type=\"Scene Heading\">\n      <Text>OMITTED</Text>"
+        ));
+        assert_eq!(
+            write(&reimported, root(&reimported), &BranchPolicy::default()).unwrap(),
+            exported
+        );
+    }
+
+    #[test]
+    fn test_title_page_is_rebuilt_from_the_current_title_and_authors() {
+        // ARRANGE
+        let document = r#"<FinalDraft><Content>
+            <Paragraph Type="Scene Heading"><Text>INT. HOUSE - DAY</Text></Paragraph>
+        </Content><TitlePage><Content>
+            <Paragraph Type="Action"><Text>Encryption</Text></Paragraph>
+            <Paragraph Type="Action"><Text></Text></Paragraph>
+            <Paragraph Type="Action"><Text>Written by</Text></Paragraph>
+            <Paragraph Type="Action"><Text></Text></Paragraph>
+            <Paragraph Type="Action"><Text>Donte</Text></Paragraph>
+            <Paragraph Type="Action"><Text></Text></Paragraph>
+            <Paragraph Type="Action"><Text>Second Draft</Text></Paragraph>
+        </Content></TitlePage></FinalDraft>"#;
+        let mut storyboard = parse(document).unwrap();
+        let author_id = storyboard.authors()[0].id();
+        // ACT
+        storyboard
+            .update_title(Title::new("Decryption").unwrap())
+            .unwrap();
+        storyboard.remove_author(&author_id).unwrap();
+        storyboard
+            .add_author(Author::new(AuthorName::new("Dante").unwrap()))
+            .unwrap();
+        let exported = write(&storyboard, root(&storyboard), &BranchPolicy::default()).unwrap();
+        // ASSERT
+        let title_page = &exported[exported.find("<TitlePage>").unwrap()..];
+        let texts: Vec<_> = title_page
+            .split("<Text>")
+            .skip(1)
+            .map(|t| &t[..t.find("</Text>").unwrap()])
+            .collect();
+        assert_eq!(
+            texts,
+            [
+                "Decryption",
+                "",
+                "Written by",
+                "",
+                "Dante",
+                "",
+                "Second Draft"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_transitions_shots_and_general_paragraphs_keep_their_types() {
+        // ARRANGE
+        let document = r#"<FinalDraft><Content>
+            <Paragraph Type="Scene Heading"><Text>INT. HOUSE - DAY</Text></Paragraph>
+            <Paragraph Type="Shot"><Text>CLOSE ON THE DOOR</Text></Paragraph>
+            <Paragraph Type="Action"><Text>It creaks open.</Text></Paragraph>
+            <Paragraph Type="General"><Text>A note to the reader.</Text></Paragraph>
+            <Paragraph Type="Transition"><Text>CUT TO:</Text></Paragraph>
+        </Content></FinalDraft>"#;
+        let storyboard = parse(document).unwrap();
+        // ACT
+        let exported = write(&storyboard, root(&storyboard), &BranchPolicy::default()).unwrap();
+        let reimported = parse(&exported).unwrap();
+        // ASSERT
+        for paragraph in [
+            "Type=\"Shot\">\n      <Text>CLOSE ON THE DOOR</Text>",
+            "Type=\"Action\">\n      <Text>It creaks open.</Text>",
+            "Type=\"General\">\n      <Text>A note to the reader.</Text>",
+            "Type=\"Transition\">\n      <Text>CUT TO:</Text>",
+        ] {
+            assert!(exported.contains(paragraph), "missing {paragraph}");
+        }
+        assert_eq!(
+            write(&reimported, root(&reimported), &BranchPolicy::default()).unwrap(),
+            exported
+        );
+    }
+}
@@ -0,0 +1,851 @@
+//! Reading and writing screenplays in the [Fountain](https://fountain.io) plain-text format.
+
+use std::fmt::Write;
+
+use serde::Serialize;
+
+use crate::{
+    formats::{
+        HeadingError, OMITTED, WriteError,
+        builder::{ScriptBuilder, UNLOCKED},
+        has_camera_prefix, parenthetical_text, parse_scene_heading,
+    },
+    models::{
+        Author, AuthorName, BranchPolicy, Dialogue, DialogueBlock, DialogueText, Id,
+        NarrativeError, Parenthetical, SceneAction, SceneElement, SceneNumber, SceneVariant,
+        ScriptScene, Storyboard, Summary, Title,
+    },
+    utils::InputError,
+};
+
+/// An error raised while parsing a Fountain document.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct FountainError {
+    /// The 1-based line number the error was raised on.
+    pub line: usize,
+    /// What went wrong on that line.
+    pub kind: FountainErrorKind,
+}
+
+/// The reasons a Fountain document can fail to parse.
+#[derive(Debug, Serialize, PartialEq)]
+pub enum FountainErrorKind {
+    /// A piece of text on the line failed input validation.
+    InvalidInput(InputError),
+    /// The line looked like a scene heading but could not be read as one.
+    InvalidSceneHeading(HeadingError),
+    /// A lower-level [`NarrativeError`] occurred while chaining the parsed scenes.
+    Narrative(NarrativeError),
+}
+
+impl FountainError {
+    fn new(line: usize, kind: FountainErrorKind) -> Self {
+        Self { line, kind }
+    }
+
+    fn input(line: usize) -> impl FnOnce(InputError) -> Self {
+        move |e| Self::new(line, FountainErrorKind::InvalidInput(e))
+    }
+}
+
+/// Parses a Fountain document into a new [`Storyboard`].
+///
+/// Title-page `Title` and `Author`/`Authors` keys become the storyboard title
+/// and authors. Each scene heading starts a new [`Scene`] whose active variant
+/// receives the heading, action, and dialogue that follow it. Every distinct
+/// character cue becomes a [`Character`], matched case-insensitively and
+/// ignoring extensions such as `(V.O.)` or `(CONT'D)`.
+///
+/// Scenes are chained in document order into a single path through the
+/// scene graph, rooted at the first scene. Scene numbers (`#12A#`) are frozen
+/// as the narrative's numbering, and a numbered `.OMITTED` heading keeps its
+/// number as an omitted scene. Synopses (`= ...`) set the summary
+/// of the scene they appear in, or the storyboard summary before the first
+/// scene. Transitions and centered text are kept as action; sections, notes,
+/// boneyard comments, and page breaks are dropped.
+///
+/// # Errors
+///
+/// Returns a [`FountainError`] naming the line that could not be read, such as
+/// a heading with an unknown time of day or a parenthetical that fails
+/// validation.
+pub fn parse(input: &str) -> Result<Storyboard, FountainError> {
+    let lines = strip_comments(input);
+    let lines: Vec<&str> = lines.lines().collect();
+
+    let mut reader = FountainReader::default();
+    let body_start = reader.read_title_page(&lines)?;
+    reader.read_body(&lines, body_start)?;
+    reader
+        .script
+        .finish()
+        .map_err(|(line, e)| FountainError::new(line, FountainErrorKind::Narrative(e)))
+}
+
+/// Writes the path through the storyboard starting at `root` as a Fountain document.
+///
+/// The document opens with a title page built from the storyboard title and
+/// authors, followed by the storyboard summary as a synopsis. Each scene on
+/// the path returned by [`Narrative::linearize_from`] under `policy` is
+/// written from the variant the path visits: heading, synopsis, action, and
+/// dialogue with speakers resolved to character names. Numbered scenes carry
+/// their number on the heading, and the numbers of removed scenes are
+/// written where they fall as `.OMITTED` headings.
+///
+/// Lines that would otherwise be misread on import, such as action starting
+/// with `INT.` or a character name in mixed case, are written with Fountain's
+/// forcing markers so the output parses back into an equivalent storyboard.
+///
+/// # Errors
+///
+/// - [`WriteError::UnknownCharacter`] if a dialogue's speaker is not among
+///   the storyboard's characters.
+/// - [`WriteError::Narrative`] if the path cannot be linearized.
+///
+/// [`Narrative::linearize_from`]: crate::models::Narrative::linearize_from
+pub fn write(
+    storyboard: &Storyboard,
+    root: Id<SceneVariant>,
+    policy: &BranchPolicy,
+) -> Result<String, WriteError> {
+    let mut output = String::new();
+    write_title_page(storyboard, &mut output);
+
+    if !storyboard.summary().is_empty() {
+        push_block(&mut output, &format!("= {}", storyboard.summary().as_str()));
+    }
+
+    for entry in storyboard.narrative().linearize_numbered(root, policy)? {
+        let (number, variant) = match entry {
+            ScriptScene::Scene {
+                number, variant, ..
+            } => (number, variant),
+            ScriptScene::Omitted(number) => {
+                push_block(&mut output, &format!(".{OMITTED} #{number}#"));
+                continue;
+            }
+        };
+
+        if let Some(heading) = variant.heading() {
+            match number {
+                Some(number) => push_block(&mut output, &format!("{heading} #{number}#")),
+                None => push_block(&mut output, &heading.to_string()),
+            }
+        }
+
+        if !variant.summary().is_empty() {
+            push_block(&mut output, &format!("= {}", variant.summary().as_str()));
+        }
+
+        for element in variant.elements() {
+            match element {
+                SceneElement::Action(action) => push_block(&mut output, &escape_action(action)),
+                SceneElement::Dialogue(dialogue) => {
+                    let speaker = storyboard
+                        .character(&dialogue.speaker())
+                        .ok_or(WriteError::UnknownCharacter(dialogue.speaker()))?;
+                    push_block(&mut output, &dialogue_block(speaker.name(), dialogue));
+                }
+            }
+        }
+    }
+
+    Ok(output)
+}
+
+/// Writes the `Title` and `Author` keys, if either is set.
+fn write_title_page(storyboard: &Storyboard, output: &mut String) {
+    let mut authors: Vec<_> = storyboard.authors().iter().map(|a| a.name()).collect();
+    authors.sort_unstable();
+
+    if let Some(title) = storyboard.title() {
+        let _ = writeln!(output, "Title: {}", title.as_str());
+    }
+
+    match authors.as_slice() {
+        [] => {}
+        [author] => {
+            let _ = writeln!(output, "Author: {author}");
+        }
+        authors => {
+            output.push_str("Authors:\n");
+            for author in authors {
+                let _ = writeln!(output, "    {author}");
+            }
+        }
+    }
+}
+
+/// Appends a paragraph, separated from the previous one by a blank line.
+fn push_block(output: &mut String, block: &str) {
+    if !output.is_empty() {
+        output.push('\n');
+    }
+    output.push_str(block);
+    output.push('\n');
+}
+
+/// Writes an action paragraph, forcing it with `!` when it would otherwise be
+/// read as a heading, synopsis, cue, or other element.
+///
+/// Later lines are forced only when they start with a marker that would be
+/// stripped from them.
+fn escape_action(action: &SceneAction) -> String {
+    let mut lines = action.as_str().lines();
+    let first = lines.next().unwrap_or_default();
+    let multiline = action.as_str().contains('\n');
+    let ambiguous = is_scene_heading(first)
+        || first.starts_with(['!', '@', '#', '=', '>', '~', '[', '/'])
+        || title_page_key(first).is_some()
+        || (multiline && is_character_cue(first));
+
+    let mut text = if ambiguous {
+        format!("!{first}")
+    } else {
+        first.to_string()
+    };
+    for line in lines {
+        text.push('\n');
+        if line.starts_with(['!', '>']) {
+            text.push('!');
+        }
+        text.push_str(line);
+    }
+
+    text
+}
+
+/// Writes a character cue followed by its parentheticals and dialogue text.
+fn dialogue_block(speaker: &str, dialogue: &Dialogue) -> String {
+    let needs_forcing = speaker.chars().any(char::is_lowercase)
+        || !speaker.chars().any(char::is_alphabetic)
+        || is_scene_heading(speaker);
+    let mut block = if needs_forcing {
+        format!("@{speaker}")
+    } else {
+        speaker.to_string()
+    };
+
+    for content in dialogue.content() {
+        let line = match content {
+            DialogueBlock::Parenthetical(p) => parenthetical_text(p),
+            DialogueBlock::Text(t) => t.as_str().to_string(),
+        };
+        block.push('\n');
+        block.push_str(&line);
+    }
+
+    block
+}
+
+/// Removes boneyard (`/* */`) and note (`[[ ]]`) spans from the document.
+///
+/// Line breaks inside removed spans are kept so error line numbers still
+/// match the original input.
+fn strip_comments(input: &str) -> String {
+    let input = input.replace("\r\n", "\n");
+    let mut output = String::with_capacity(input.len());
+    let mut rest = input.as_str();
+
+    while !rest.is_empty() {
+        let next = [("/*", "*/"), ("[[", "]]")]
+            .into_iter()
+            .filter_map(|(open, close)| rest.find(open).map(|i| (i, open, close)))
+            .min_by_key(|(i, _, _)| *i);
+
+        let Some((start, open, close)) = next else {
+            output.push_str(rest);
+            break;
+        };
+
+        output.push_str(&rest[..start]);
+        let after_open = &rest[start + open.len()..];
+        let (removed, remaining) = match after_open.find(close) {
+            Some(end) => (&after_open[..end], &after_open[end + close.len()..]),
+            None => (after_open, ""),
+        };
+        output.extend(removed.chars().filter(|c| *c == '\n'));
+        rest = remaining;
+    }
+
+    output
+}
+
+/// Reads a Fountain document line by line into a [`ScriptBuilder`].
+#[derive(Default)]
+struct FountainReader {
+    script: ScriptBuilder,
+}
+
+impl FountainReader {
+    /// Reads the `Key: value` title page, if the document has one, and
+    /// returns the index of the first body line.
+    fn read_title_page(&mut self, lines: &[&str]) -> Result<usize, FountainError> {
+        if lines.first().is_none_or(|l| title_page_key(l).is_none()) {
+            return Ok(0);
+        }
+
+        let mut entries: Vec<(usize, String, Vec<String>)> = Vec::new();
+        let mut index = 0;
+
+        while let Some(line) = lines.get(index) {
+            if line.trim().is_empty() {
+                break;
+            }
+
+            if let Some((key, value)) = title_page_key(line) {
+                let values = Some(value.trim())
+                    .filter(|v| !v.is_empty())
+                    .map(String::from)
+                    .into_iter()
+                    .collect();
+                entries.push((index + 1, key.to_lowercase(), values));
+            } else if let Some((_, _, values)) = entries.last_mut() {
+                values.push(line.trim().to_string());
+            }
+
+            index += 1;
+        }
+
+        for (line, key, values) in entries {
+            match key.as_str() {
+                "title" => {
+                    let title = values
+                        .iter()
+                        .map(|v| strip_emphasis(v))
+                        .collect::<Vec<_>>()
+                        .join(" ");
+                    let title = Title::new(&title).map_err(FountainError::input(line))?;
+                    self.script
+                        .storyboard_mut()
+                        .update_title(title)
+                        .expect(UNLOCKED);
+                }
+                "author" | "authors" => {
+                    for value in values {
+                        let name = AuthorName::new(&value).map_err(FountainError::input(line))?;
+                        self.script
+                            .storyboard_mut()
+                            .add_author(Author::new(name))
+                            .expect(UNLOCKED);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(index)
+    }
+
+    /// Reads every body element from `start` to the end of the document.
+    fn read_body(&mut self, lines: &[&str], start: usize) -> Result<(), FountainError> {
+        let is_blank = |i: usize| lines.get(i).is_none_or(|l| l.trim().is_empty());
+        let mut index = start;
+
+        while let Some(raw) = lines.get(index) {
+            let line = raw.trim();
+            let line_number = index + 1;
+            let after_blank = index == 0 || is_blank(index - 1);
+
+            if line.is_empty() || line.starts_with('#') || is_page_break(line) {
+                index += 1;
+                continue;
+            }
+
+            if let Some(synopsis) = line.strip_prefix('=') {
+                self.set_summary(synopsis, line_number)?;
+                index += 1;
+                continue;
+            }
+
+            if after_blank && is_scene_heading(line) {
+                self.start_scene(line, line_number)?;
+                index += 1;
+                continue;
+            }
+
+            if after_blank && !is_blank(index + 1) && is_character_cue(line) {
+                index = self.read_dialogue(lines, index)?;
+                continue;
+            }
+
+            // Action runs to the end of the paragraph.
+            let mut paragraph = Vec::new();
+            while !is_blank(index) {
+                paragraph.push(action_text(lines[index].trim()));
+                index += 1;
+            }
+            let action = SceneAction::new(&paragraph.join("\n"))
+                .map_err(FountainError::input(line_number))?;
+            self.script
+                .add_element(line_number, SceneElement::Action(action));
+        }
+
+        Ok(())
+    }
+
+    /// Starts a new scene from a heading line, or records the number of an
+    /// omitted one.
+    fn start_scene(&mut self, line: &str, line_number: usize) -> Result<(), FountainError> {
+        let heading = line.strip_prefix('.').unwrap_or(line);
+        let (heading, number) = split_scene_number(heading);
+        let number = number
+            .map(SceneNumber::new)
+            .transpose()
+            .map_err(FountainError::input(line_number))?;
+
+        if heading.eq_ignore_ascii_case(OMITTED) {
+            if let Some(number) = number {
+                self.script.omit_scene(number);
+            }
+            return Ok(());
+        }
+
+        let heading = parse_scene_heading(heading).map_err(|e| {
+            FountainError::new(line_number, FountainErrorKind::InvalidSceneHeading(e))
+        })?;
+
+        self.script.start_scene(line_number, Some(heading), number);
+
+        Ok(())
+    }
+
+    /// Reads a character cue and the dialogue beneath it, returning the index
+    /// of the line after the dialogue.
+    fn read_dialogue(&mut self, lines: &[&str], start: usize) -> Result<usize, FountainError> {
+        let line_number = start + 1;
+        let speaker = self
+            .script
+            .character(cue_name(lines[start].trim()))
+            .map_err(FountainError::input(line_number))?;
+        let scene_id = self.script.current_scene(line_number).id();
+        let mut dialogue = Dialogue::new(scene_id, speaker);
+
+        let mut text: Vec<&str> = Vec::new();
+        let mut index = start + 1;
+
+        while let Some(line) = lines.get(index).map(|l| l.trim()) {
+            if line.is_empty() {
+                break;
+            }
+
+            if let Some(inner) = line.strip_prefix('(').and_then(|l| l.strip_suffix(')')) {
+                flush_dialogue_text(&mut dialogue, &mut text, index)?;
+                let parenthetical =
+                    Parenthetical::new(inner).map_err(FountainError::input(index + 1))?;
+                dialogue
+                    .add_dialogue_block(DialogueBlock::Parenthetical(parenthetical))
+                    .expect(UNLOCKED);
+            } else {
+                text.push(line.strip_prefix('~').unwrap_or(line));
+            }
+
+            index += 1;
+        }
+
+        flush_dialogue_text(&mut dialogue, &mut text, index)?;
+        self.script
+            .add_element(line_number, SceneElement::Dialogue(dialogue));
+
+        Ok(index)
+    }
+
+    /// Sets the summary of the current scene, or of the storyboard if no
+    /// scene has started yet.
+    fn set_summary(&mut self, synopsis: &str, line_number: usize) -> Result<(), FountainError> {
+        let summary = Summary::new(synopsis).map_err(FountainError::input(line_number))?;
+
+        if self.script.has_scenes() {
+            self.script
+                .current_variant(line_number)
+                .set_summary(summary)
+                .expect(UNLOCKED);
+        } else {
+            self.script
+                .storyboard_mut()
+                .update_summary(summary)
+                .expect(UNLOCKED);
+        }
+
+        Ok(())
+    }
+}
+
+/// Pushes any buffered dialogue lines onto `dialogue` as one text block.
+fn flush_dialogue_text(
+    dialogue: &mut Dialogue,
+    text: &mut Vec<&str>,
+    index: usize,
+) -> Result<(), FountainError> {
+    if text.is_empty() {
+        return Ok(());
+    }
+
+    let block = DialogueText::new(&text.join(" ")).map_err(FountainError::input(index))?;
+    dialogue
+        .add_dialogue_block(DialogueBlock::Text(block))
+        .expect(UNLOCKED);
+    text.clear();
+
+    Ok(())
+}
+
+/// Splits a title-page line into its key and inline value.
+fn title_page_key(line: &str) -> Option<(&str, &str)> {
+    let (key, value) = line.split_once(':')?;
+    let is_key = !key.is_empty()
+        && !line.starts_with(char::is_whitespace)
+        && key.chars().all(|c| c.is_alphabetic() || c == ' ');
+
+    is_key.then_some((key.trim(), value))
+}
+
+/// Removes Fountain emphasis markers (`*`, `_`) around title-page values.
+fn strip_emphasis(value: &str) -> &str {
+    value.trim_matches(|c| c == '*' || c == '_').trim()
+}
+
+/// Returns `true` for a forced (`.`) or prefixed (`INT.`, `EXT.`) scene heading.
+fn is_scene_heading(line: &str) -> bool {
+    match line.strip_prefix('.') {
+        Some(rest) => rest.starts_with(|c: char| c.is_alphanumeric()),
+        None => has_camera_prefix(line),
+    }
+}
+
+/// Splits a trailing `#12A#` scene number from a heading.
+fn split_scene_number(heading: &str) -> (&str, Option<&str>) {
+    heading
+        .strip_suffix('#')
+        .and_then(|h| h.rsplit_once('#'))
+        .map_or((heading, None), |(h, number)| (h.trim_end(), Some(number)))
+}
+
+/// Returns `true` for a `===` page break.
+fn is_page_break(line: &str) -> bool {
+    line.len() >= 3 && line.chars().all(|c| c == '=')
+}
+
+/// Returns `true` for a forced (`@`) cue or an all-caps line that is not a transition.
+fn is_character_cue(line: &str) -> bool {
+    if line.starts_with('@') {
+        return true;
+    }
+
+    let name = cue_name(line);
+    name.chars().any(char::is_alphabetic)
+        && !name.chars().any(char::is_lowercase)
+        && !line.ends_with("TO:")
+        && !line.starts_with(['!', '>', '~'])
+}
+
+/// Extracts the character name from a cue, dropping the `@` forcing marker,
+/// the `^` dual-dialogue marker, and any `(V.O.)`-style extension.
+fn cue_name(cue: &str) -> &str {
+    let cue = cue.strip_prefix('@').unwrap_or(cue);
+    let cue = cue.trim_end().strip_suffix('^').unwrap_or(cue);
+    cue.split('(').next().unwrap_or(cue).trim()
+}
+
+/// Removes forced-action (`!`), transition (`>`), and centering (`> <`) markers.
+fn action_text(line: &str) -> &str {
+    if let Some(rest) = line.strip_prefix('!') {
+        return rest;
+    }
+
+    match line.strip_prefix('>') {
+        Some(rest) => rest.strip_suffix('<').unwrap_or(rest).trim(),
+        None => line,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        formats::{
+            HeadingError, WriteError,
+            fountain::{FountainError, FountainErrorKind, parse, write},
+        },
+        models::{
+            BranchPolicy, CameraLocation, Dialogue, DialogueBlock, Id, Scene, SceneAction,
+            SceneElement, SceneTimeOfDay, Storyboard,
+        },
+        utils::InputError,
+    };
+
+    const SCRIPT: &str = "\
+Title: _**Encryption**_
+Author: Donte Ravae
+Draft date: 1/1/2026
+
+INT. WHITE HOUSE - OVAL OFFICE - NIGHT
+
+PRESIDENT MCKENZIE slouches in his chair.
+
+FIRST LADY
+(quietly)
+Kyle, this is too much.
+
+PRESIDENT MCKENZIE (V.O.)
+What I do is in the best interest
+of the country.
+
+EXT. AIRPORT HANGER - MORNING
+= Victor arrives.
+
+FIRST LADY
+Again.
+";
+
+    #[test]
+    fn test_title_page_sets_title_and_authors() {
+        // ARRANGE & ACT
+        let storyboard = parse(SCRIPT).unwrap();
+        // ASSERT
+        assert_eq!(storyboard.title().as_deref(), Some("Encryption"));
+        assert_eq!(storyboard.authors()[0].name(), "Donte Ravae");
+    }
+
+    #[test]
+    fn test_scenes_are_chained_into_a_single_rooted_path() {
+        // ARRANGE & ACT
+        let storyboard = parse(SCRIPT).unwrap();
+        let narrative = storyboard.narrative();
+        let root = *narrative.graph().roots().iter().next().unwrap();
+        let scenes = narrative
+            .linearize_from(root, &BranchPolicy::default())
+            .unwrap();
+        // ASSERT
+        assert_eq!(narrative.graph().roots().len(), 1);
+        assert_eq!(scenes.len(), 2);
+        let heading = scenes[0].1.heading().unwrap();
+        assert_eq!(heading.camera_location(), &CameraLocation::Interior);
+        assert_eq!(
+            heading.scene_location().as_str(),
+            "WHITE HOUSE - OVAL OFFICE"
+        );
+        assert_eq!(heading.time_of_day(), &SceneTimeOfDay::Night);
+        assert_eq!(scenes[1].1.summary().as_str(), "Victor arrives.");
+    }
+
+    #[test]
+    fn test_character_cues_become_characters_and_dialogue() {
+        // ARRANGE & ACT
+        let storyboard = parse(SCRIPT).unwrap();
+        let narrative = storyboard.narrative();
+        let root = *narrative.graph().roots().iter().next().unwrap();
+        let path = narrative
+            .linearize_from(root, &BranchPolicy::default())
+            .unwrap();
+        let elements = path[0].1.elements();
+        // ASSERT
+        assert_eq!(storyboard.characters().len(), 2);
+        assert!(matches!(elements[0], SceneElement::Action(_)));
+        let SceneElement::Dialogue(dialogue) = &elements[1] else {
+            panic!("expected dialogue");
+        };
+        assert!(matches!(
+            &dialogue.content()[0],
+            DialogueBlock::Parenthetical(p) if p.as_str() == "quietly"
+        ));
+        let SceneElement::Dialogue(dialogue) = &elements[2] else {
+            panic!("expected dialogue");
+        };
+        assert!(matches!(
+            &dialogue.content()[0],
+            DialogueBlock::Text(t) if t.as_str() == "What I do is in the best interest of the country."
+        ));
+    }
+
+    #[test]
+    fn test_unknown_time_of_day_reports_the_failing_line() {
+        // ARRANGE
+        let script = "INT. HOUSE - DAY\n\nEXT. YARD - HIGH NOON\n";
+        // ACT
+        let response = parse(script);
+        // ASSERT
+        assert_eq!(
+            response.err(),
+            Some(FountainError {
+                line: 3,
+                kind: FountainErrorKind::InvalidSceneHeading(HeadingError::UnknownTimeOfDay(
+                    "HIGH NOON".to_string()
+                )),
+            })
+        );
+    }
+
+    #[test]
+    fn test_headings_match_prefixes_without_uppercasing_the_line() {
+        // ARRANGE
+        let script = "int. house - day\n\nEﬆ. ROAD - DAY\n";
+        // ACT
+        let storyboard = parse(script).unwrap();
+        // ASSERT
+        let scenes: Vec<_> = storyboard.narrative().scenes().collect();
+        assert_eq!(scenes.len(), 1);
+        let variant = &scenes[0].variants()[scenes[0].active_variant()];
+        assert_eq!(
+            variant.heading().unwrap().camera_location(),
+            &CameraLocation::Interior
+        );
+        assert!(matches!(
+            &variant.elements()[0],
+            SceneElement::Action(action) if action.as_str() == "Eﬆ. ROAD - DAY"
+        ));
+    }
+
+    #[test]
+    fn test_invalid_parenthetical_reports_the_failing_line() {
+        // ARRANGE
+        let script = "INT. HOUSE - DAY\n\nJADE\n(handing over the envelope slowly, never looking up)\nHere.\n";
+        // ACT
+        let response = parse(script);
+        // ASSERT
+        assert_eq!(
+            response.err(),
+            Some(FountainError {
+                line: 4,
+                kind: FountainErrorKind::InvalidInput(InputError::TooManyChars),
+            })
+        );
+    }
+
+    #[test]
+    fn test_written_script_starts_with_a_title_page() {
+        // ARRANGE
+        let storyboard = parse(SCRIPT).unwrap();
+        let root = *storyboard
+            .narrative()
+            .graph()
+            .roots()
+            .iter()
+            .next()
+            .unwrap();
+        // ACT
+        let output = write(&storyboard, root, &BranchPolicy::default()).unwrap();
+        // ASSERT
+        assert!(output.starts_with("Title: Encryption\nAuthor: Donte Ravae\n\nINT. WHITE HOUSE"));
+        assert!(output.contains("FIRST LADY\n(quietly)\nKyle, this is too much.\n"));
+    }
+
+    #[test]
+    fn test_written_script_imports_back_to_an_equivalent_storyboard() {
+        // ARRANGE
+        let storyboard = parse(SCRIPT).unwrap();
+        let root = *storyboard
+            .narrative()
+            .graph()
+            .roots()
+            .iter()
+            .next()
+            .unwrap();
+        let output = write(&storyboard, root, &BranchPolicy::default()).unwrap();
+        // ACT
+        let reimported = parse(&output).unwrap();
+        let reimported_root = *reimported
+            .narrative()
+            .graph()
+            .roots()
+            .iter()
+            .next()
+            .unwrap();
+        // ASSERT
+        assert_eq!(reimported.title(), storyboard.title());
+        assert_eq!(reimported.characters().len(), storyboard.characters().len());
+        assert_eq!(
+            write(&reimported, reimported_root, &BranchPolicy::default()).unwrap(),
+            output
+        );
+    }
+
+    #[test]
+    fn test_scene_numbers_and_omitted_scenes_round_trip() {
+        // ARRANGE
+        let script = "INT. HOUSE - DAY #1#\n\n.OMITTED #2#\n\nEXT. YARD - NIGHT #2A#\n";
+        let storyboard = parse(script).unwrap();
+        let root = *storyboard
+            .narrative()
+            .graph()
+            .roots()
+            .iter()
+            .next()
+            .unwrap();
+        // ACT
+        let output = write(&storyboard, root, &BranchPolicy::default()).unwrap();
+        // ASSERT
+        assert_eq!(storyboard.narrative().scene_numbers().len(), 3);
+        assert_eq!(output, script);
+    }
+
+    #[test]
+    fn test_ambiguous_action_is_forced() {
+        // ARRANGE
+        let mut storyboard = Storyboard::default();
+        let mut scene = Scene::new();
+        let variant_id = *scene.active_variant();
+        scene
+            .variants_mut()
+            .get_mut(&variant_id)
+            .unwrap()
+            .add_element(SceneElement::Action(
+                SceneAction::new("INT. is painted on the door.").unwrap(),
+            ))
+            .unwrap();
+        storyboard.narrative_mut().add_scene(scene).unwrap();
+        // ACT
+        let output = write(&storyboard, variant_id, &BranchPolicy::default()).unwrap();
+        // ASSERT
+        assert_eq!(output, "!INT. is painted on the door.\n");
+    }
+
+    #[test]
+    fn test_multi_line_action_round_trips_with_its_line_breaks() {
+        // ARRANGE
+        let script =
+            "INT. HOUSE - DAY\n\nThe door creaks.\nIt slams shut.\n\n!SILENCE.\nThen footsteps.\n";
+        let storyboard = parse(script).unwrap();
+        let root = *storyboard
+            .narrative()
+            .graph()
+            .roots()
+            .iter()
+            .next()
+            .unwrap();
+        // ACT
+        let output = write(&storyboard, root, &BranchPolicy::default()).unwrap();
+        let path = storyboard
+            .narrative()
+            .linearize_from(root, &BranchPolicy::default())
+            .unwrap();
+        // ASSERT
+        let elements = path[0].1.elements();
+        let SceneElement::Action(action) = &elements[0] else {
+            panic!("expected action");
+        };
+        assert_eq!(action.as_str(), "The door creaks.\nIt slams shut.");
+        assert_eq!(elements.len(), 2);
+        assert_eq!(output, script);
+    }
+
+    #[test]
+    fn test_writing_dialogue_for_an_unknown_character_fails() {
+        // ARRANGE
+        let mut storyboard = Storyboard::default();
+        let mut scene = Scene::new();
+        let speaker = Id::new();
+        let dialogue = Dialogue::new(scene.id(), speaker);
+        let variant_id = *scene.active_variant();
+        scene
+            .variants_mut()
+            .get_mut(&variant_id)
+            .unwrap()
+            .add_element(SceneElement::Dialogue(dialogue))
+            .unwrap();
+        storyboard.narrative_mut().add_scene(scene).unwrap();
+        // ACT
+        let response = write(&storyboard, variant_id, &BranchPolicy::default());
+        // ASSERT
+        assert_eq!(response, Err(WriteError::UnknownCharacter(speaker)));
+    }
+}
@@ -0,0 +1,190 @@
+//! The shape of a narrative's scene graph, gathered once for the graph
+//! exporters, and what they share for drawing character relationships.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::models::{Character, Id, Narrative, Relationship, Scene, SceneVariant, Storyboard};
+
+/// Summaries longer than this many characters are cut short in node labels.
+const SUMMARY_LABEL_CHARS: usize = 60;
+
+/// A scene variant as drawn in an exported graph.
+pub(crate) struct Node {
+    pub(crate) id: Id<SceneVariant>,
+    /// The variant's scene heading.
+    pub(crate) heading: String,
+    /// The variant's summary, shortened for display. Empty if it has none.
+    pub(crate) summary: String,
+    pub(crate) root: bool,
+    pub(crate) unreachable: bool,
+    /// `true` if this is its scene's active variant.
+    pub(crate) active: bool,
+}
+
+/// The variants of one scene, drawn grouped together.
+pub(crate) struct Cluster {
+    pub(crate) scene: Id<Scene>,
+    /// The heading of the scene's active variant.
+    pub(crate) label: String,
+    pub(crate) nodes: Vec<Node>,
+}
+
+/// A narrative's scene graph in a stable drawing order.
+///
+/// Scenes appear in the order a depth-first walk from the roots first meets
+/// them, followed by scenes no root reaches. Within a scene the active
+/// variant comes first. Graph variants that belong to no scene are kept
+/// apart in `unowned`.
+pub(crate) struct GraphView {
+    pub(crate) clusters: Vec<Cluster>,
+    pub(crate) unowned: Vec<Node>,
+    pub(crate) edges: Vec<(Id<SceneVariant>, Id<SceneVariant>)>,
+}
+
+impl GraphView {
+    pub(crate) fn new(narrative: &Narrative) -> Self {
+        let graph = narrative.graph();
+        let unreachable = graph.unreachable_variants();
+        let by_uuid = |ids: &mut Vec<Id<SceneVariant>>| ids.sort_by_key(|id| id.uuid());
+
+        let mut roots: Vec<_> = graph.roots().iter().copied().collect();
+        by_uuid(&mut roots);
+        let mut rest: Vec<_> = unreachable.iter().copied().collect();
+        by_uuid(&mut rest);
+
+        // Story order first, then whatever no root reaches.
+        let mut order = Vec::new();
+        let mut seen = HashSet::new();
+        for variant in roots
+            .iter()
+            .flat_map(|root| graph.reachable_from(*root))
+            .chain(rest)
+        {
+            if seen.insert(variant) {
+                order.push(variant);
+            }
+        }
+
+        let node = |variant: &SceneVariant, active: bool| Node {
+            id: variant.id(),
+            heading: variant
+                .heading()
+                .map_or_else(|| "(no heading)".to_string(), |h| h.to_string()),
+            summary: shorten(variant.summary().as_str()),
+            root: graph.roots().contains(&variant.id()),
+            unreachable: unreachable.contains(&variant.id()),
+            active,
+        };
+
+        let mut clusters = Vec::new();
+        let mut placed: HashMap<Id<Scene>, usize> = HashMap::new();
+        let mut unowned = Vec::new();
+        for variant_id in &order {
+            let Some(scene) = narrative.scene_for_variant(variant_id) else {
+                unowned.push(Node {
+                    id: *variant_id,
+                    heading: "(no scene)".to_string(),
+                    summary: String::new(),
+                    root: graph.roots().contains(variant_id),
+                    unreachable: unreachable.contains(variant_id),
+                    active: false,
+                });
+                continue;
+            };
+            if placed.contains_key(&scene.id()) {
+                continue;
+            }
+
+            let mut variants: Vec<_> = scene.variants().values().collect();
+            variants.sort_by_key(|v| (v.id() != *scene.active_variant(), v.id().uuid()));
+            placed.insert(scene.id(), clusters.len());
+            clusters.push(Cluster {
+                scene: scene.id(),
+                label: scene
+                    .variants()
+                    .get(scene.active_variant())
+                    .and_then(|v| v.heading())
+                    .map_or_else(|| "Untitled scene".to_string(), |h| h.to_string()),
+                nodes: variants
+                    .into_iter()
+                    .map(|v| node(v, v.id() == *scene.active_variant()))
+                    .collect(),
+            });
+        }
+
+        let mut edges: Vec<_> = order
+            .iter()
+            .flat_map(|src| graph.next_variants(*src).map(move |dest| (*src, dest)))
+            .filter(|(_, dest)| graph.contains(*dest))
+            .collect();
+        edges.sort_by_key(|(src, dest)| (src.uuid(), dest.uuid()));
+
+        Self {
+            clusters,
+            unowned,
+            edges,
+        }
+    }
+
+    /// Returns every node, clustered ones first.
+    pub(crate) fn nodes(&self) -> impl Iterator<Item = &Node> {
+        self.clusters
+            .iter()
+            .flat_map(|c| c.nodes.iter())
+            .chain(self.unowned.iter())
+    }
+}
+
+/// Returns an identifier for a variant that every format accepts unquoted.
+pub(crate) fn node_id(id: Id<SceneVariant>) -> String {
+    format!("v{}", id.uuid().simple())
+}
+
+/// Returns an identifier for a scene's cluster that every format accepts unquoted.
+pub(crate) fn cluster_id(id: Id<Scene>) -> String {
+    format!("s{}", id.uuid().simple())
+}
+
+/// Returns an identifier for a character that every format accepts unquoted.
+pub(crate) fn character_id(id: Id<Character>) -> String {
+    format!("c{}", id.uuid().simple())
+}
+
+/// Returns the storyboard's characters ordered by name, then ID.
+pub(crate) fn sorted_characters(storyboard: &Storyboard) -> Vec<&Character> {
+    let mut characters = storyboard.characters();
+    characters.sort_by_key(|c| (c.name().to_uppercase(), c.id().uuid()));
+    characters
+}
+
+/// Returns the relationships ordered by ID.
+pub(crate) fn sorted_relationships<'a>(
+    relationships: &[&'a Relationship],
+) -> Vec<&'a Relationship> {
+    let mut relationships = relationships.to_vec();
+    relationships.sort_by_key(|r| r.id().uuid());
+    relationships
+}
+
+/// Returns an edge label for a relationship: its kind, and its note
+/// shortened as summaries are.
+pub(crate) fn relationship_label(relationship: &Relationship) -> String {
+    let note = shorten(relationship.note().as_str());
+    match note.is_empty() {
+        true => relationship.kind().to_string(),
+        false => format!("{}: {note}", relationship.kind()),
+    }
+}
+
+/// Cuts a summary down to its first line and at most
+/// [`SUMMARY_LABEL_CHARS`] characters, marking any cut with an ellipsis.
+fn shorten(summary: &str) -> String {
+    let first_line = summary.lines().next().unwrap_or_default().trim();
+    if first_line.chars().count() <= SUMMARY_LABEL_CHARS && first_line.len() == summary.trim().len()
+    {
+        return first_line.to_string();
+    }
+
+    let cut: String = first_line.chars().take(SUMMARY_LABEL_CHARS - 1).collect();
+    format!("{}…", cut.trim_end())
+}
@@ -0,0 +1,174 @@
+//! Writing a narrative's scene graph as [GraphML](http://graphml.graphdrawing.org).
+
+use std::fmt::Write;
+
+use quick_xml::escape::escape;
+
+use crate::{
+    formats::graph::{GraphView, Node, cluster_id, node_id},
+    models::Narrative,
+};
+
+/// The `<key>` declarations for the data every node and edge carries.
+const KEYS: &str = "  <key id=\"label\" for=\"node\" attr.name=\"label\" attr.type=\"string\"/>\n  \
+     <key id=\"heading\" for=\"node\" attr.name=\"heading\" attr.type=\"string\"/>\n  \
+     <key id=\"summary\" for=\"node\" attr.name=\"summary\" attr.type=\"string\"/>\n  \
+     <key id=\"kind\" for=\"node\" attr.name=\"kind\" attr.type=\"string\"/>\n  \
+     <key id=\"root\" for=\"node\" attr.name=\"root\" attr.type=\"boolean\">\n    <default>false</default>\n  </key>\n  \
+     <key id=\"active\" for=\"node\" attr.name=\"active\" attr.type=\"boolean\">\n    <default>false</default>\n  </key>\n  \
+     <key id=\"unreachable\" for=\"all\" attr.name=\"unreachable\" attr.type=\"boolean\">\n    <default>false</default>\n  </key>\n";
+
+/// Writes the narrative's scene graph as a GraphML document.
+///
+/// Each scene becomes a node of `kind` `scene` holding a nested graph of its
+/// variants, which tools such as yEd and Gephi show as a group. Variant
+/// nodes, of `kind` `variant`, carry their heading, summary, and a combined
+/// label, and flag whether they are a root, their scene's active variant, or
+/// unreachable from every root. Edges into unreachable variants are flagged
+/// as well, so a tool can style them apart.
+pub fn write(narrative: &Narrative) -> String {
+    let view = GraphView::new(narrative);
+    let mut output = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\" \
+         xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\" \
+         xsi:schemaLocation=\"http://graphml.graphdrawing.org/xmlns \
+         http://graphml.graphdrawing.org/xmlns/1.0/graphml.xsd\">\n",
+    );
+    output.push_str(KEYS);
+    output.push_str("  <graph id=\"narrative\" edgedefault=\"directed\">\n");
+
+    for cluster in &view.clusters {
+        let id = cluster_id(cluster.scene);
+        let _ = write!(
+            output,
+            "    <node id=\"{id}\">\n      <data key=\"label\">{}</data>\n      <data key=\"kind\">scene</data>\n      <graph id=\"{id}:\" edgedefault=\"directed\">\n",
+            escape(&cluster.label)
+        );
+        for node in &cluster.nodes {
+            write_node(&mut output, "        ", node);
+        }
+        output.push_str("      </graph>\n    </node>\n");
+    }
+
+    for node in &view.unowned {
+        write_node(&mut output, "    ", node);
+    }
+
+    let unreachable: Vec<_> = view
+        .nodes()
+        .filter(|n| n.unreachable)
+        .map(|n| n.id)
+        .collect();
+    for (src, dest) in &view.edges {
+        let (src, dest_id) = (node_id(*src), node_id(*dest));
+        if unreachable.contains(dest) {
+            let _ = writeln!(
+                output,
+                "    <edge source=\"{src}\" target=\"{dest_id}\">\n      <data key=\"unreachable\">true</data>\n    </edge>"
+            );
+        } else {
+            let _ = writeln!(output, "    <edge source=\"{src}\" target=\"{dest_id}\"/>");
+        }
+    }
+
+    output.push_str("  </graph>\n</graphml>\n");
+    output
+}
+
+/// Writes a variant node and its data.
+fn write_node(output: &mut String, indent: &str, node: &Node) {
+    let label = match node.summary.is_empty() {
+        true => node.heading.clone(),
+        false => format!("{}\n{}", node.heading, node.summary),
+    };
+
+    let _ = writeln!(output, "{indent}<node id=\"{}\">", node_id(node.id));
+    for (key, value) in [
+        ("label", label.as_str()),
+        ("heading", node.heading.as_str()),
+        ("summary", node.summary.as_str()),
+        ("kind", "variant"),
+    ] {
+        let _ = writeln!(
+            output,
+            "{indent}  <data key=\"{key}\">{}</data>",
+            escape(value)
+        );
+    }
+    for (key, flag) in [
+        ("root", node.root),
+        ("active", node.active),
+        ("unreachable", node.unreachable),
+    ] {
+        if flag {
+            let _ = writeln!(output, "{indent}  <data key=\"{key}\">true</data>");
+        }
+    }
+    let _ = writeln!(output, "{indent}</node>");
+}
+
+#[cfg(test)]
+mod tests {
+    use quick_xml::{Reader, events::Event};
+
+    use crate::{
+        formats::{fountain, graphml::write},
+        models::{Narrative, Scene},
+    };
+
+    /// Two scenes in a chain from a root, and one scene no root reaches.
+    fn generate_test_components() -> Narrative {
+        let storyboard =
+            fountain::parse("INT. HOUSE & GARDEN - DAY\n\nRain.\n\nEXT. YARD - NIGHT\n\nWind.\n")
+                .unwrap();
+        let mut narrative = storyboard.narrative().clone();
+        narrative.add_scene(Scene::new()).unwrap();
+        narrative
+    }
+
+    /// Returns the name of every element in the document, failing on
+    /// malformed XML.
+    fn element_names(xml: &str) -> Vec<String> {
+        let mut reader = Reader::from_str(xml);
+        let mut names = Vec::new();
+        loop {
+            match reader.read_event().unwrap() {
+                Event::Start(e) | Event::Empty(e) => {
+                    names.push(String::from_utf8(e.name().as_ref().to_vec()).unwrap())
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+        names
+    }
+
+    #[test]
+    fn test_scenes_become_nodes_with_nested_graphs() {
+        // ARRANGE
+        let narrative = generate_test_components();
+        // ACT
+        let document = write(&narrative);
+        // ASSERT
+        let names = element_names(&document);
+        assert_eq!(names.iter().filter(|n| *n == "graph").count(), 4);
+        assert_eq!(names.iter().filter(|n| *n == "node").count(), 6);
+        assert_eq!(names.iter().filter(|n| *n == "edge").count(), 1);
+        assert!(document.contains("INT. HOUSE &amp; GARDEN - DAY"));
+    }
+
+    #[test]
+    fn test_roots_and_unreachable_variants_are_flagged() {
+        // ARRANGE
+        let narrative = generate_test_components();
+        // ACT
+        let document = write(&narrative);
+        // ASSERT
+        assert_eq!(document.matches("<data key=\"root\">true").count(), 1);
+        assert_eq!(
+            document.matches("<data key=\"unreachable\">true").count(),
+            1
+        );
+    }
+}
@@ -0,0 +1,207 @@
+//! Writing a narrative's scene graph, and the relationships between a
+//! storyboard's characters, as [Mermaid](https://mermaid.js.org) flowcharts.
+
+use std::fmt::Write;
+
+use crate::{
+    formats::graph::{
+        GraphView, Node, character_id, cluster_id, node_id, relationship_label, sorted_characters,
+        sorted_relationships,
+    },
+    models::{Narrative, Relationship, Storyboard},
+};
+
+/// Writes the narrative's scene graph as a top-down Mermaid flowchart.
+///
+/// Each scene becomes a subgraph holding its variants, titled with the
+/// active variant's heading. Variants are labelled with their heading and
+/// summary. Roots, inactive variants, and variants no root can reach each
+/// get a class, defined at the end of the chart, and edges into unreachable
+/// variants are drawn dotted.
+///
+/// The output can be pasted into a Markdown ```` ```mermaid ```` block.
+pub fn write(narrative: &Narrative) -> String {
+    let view = GraphView::new(narrative);
+    let mut output = String::from("flowchart TD\n");
+
+    for cluster in &view.clusters {
+        let _ = writeln!(
+            output,
+            "  subgraph {}[\"{}\"]",
+            cluster_id(cluster.scene),
+            escape(&cluster.label)
+        );
+        for node in &cluster.nodes {
+            write_node(&mut output, "    ", node);
+        }
+        output.push_str("  end\n");
+    }
+
+    for node in &view.unowned {
+        write_node(&mut output, "  ", node);
+    }
+
+    let unreachable: Vec<_> = view
+        .nodes()
+        .filter(|n| n.unreachable)
+        .map(|n| n.id)
+        .collect();
+    for (src, dest) in &view.edges {
+        let arrow = if unreachable.contains(dest) {
+            "-.->"
+        } else {
+            "-->"
+        };
+        let _ = writeln!(output, "  {} {arrow} {}", node_id(*src), node_id(*dest));
+    }
+
+    output.push_str(
+        "  classDef root stroke-width:3px,font-weight:bold\n  \
+         classDef inactive stroke-dasharray:5 5\n  \
+         classDef unreachable fill:#f4cccc,stroke:#cc0000\n",
+    );
+    for (class, include) in [
+        ("root", (|n: &Node| n.root) as fn(&Node) -> bool),
+        ("inactive", |n: &Node| !n.active),
+        ("unreachable", |n: &Node| n.unreachable),
+    ] {
+        let members: Vec<_> = view
+            .nodes()
+            .filter(|n| include(n))
+            .map(|n| node_id(n.id))
+            .collect();
+        if !members.is_empty() {
+            let _ = writeln!(output, "  class {} {class}", members.join(","));
+        }
+    }
+
+    output
+}
+
+/// Writes the given relationships between the storyboard's characters as a
+/// left-to-right Mermaid flowchart.
+///
+/// Every character becomes a node labelled with their name, related or not.
+/// Each relationship is a labelled edge from the first character to the
+/// second; relationships scoped to a span of scenes are drawn dotted. Pass
+/// [`Storyboard::relationships`] for the whole story, or
+/// [`Storyboard::relationships_as_of`] for one moment in it.
+pub fn write_relationships(storyboard: &Storyboard, relationships: &[&Relationship]) -> String {
+    let mut output = String::from("flowchart LR\n");
+
+    for character in sorted_characters(storyboard) {
+        let _ = writeln!(
+            output,
+            "  {}([\"{}\"])",
+            character_id(character.id()),
+            escape(character.name())
+        );
+    }
+
+    for relationship in sorted_relationships(relationships) {
+        let scoped = relationship.starts().is_some() || relationship.ends().is_some();
+        let arrow = if scoped { "-.->" } else { "-->" };
+        let _ = writeln!(
+            output,
+            "  {} {arrow}|\"{}\"| {}",
+            character_id(relationship.from()),
+            escape(&relationship_label(relationship)),
+            character_id(relationship.to())
+        );
+    }
+
+    output
+}
+
+/// Writes a node with its label.
+fn write_node(output: &mut String, indent: &str, node: &Node) {
+    let mut label = escape(&node.heading);
+    if !node.summary.is_empty() {
+        label.push_str("<br/>");
+        label.push_str(&escape(&node.summary));
+    }
+
+    let _ = writeln!(output, "{indent}{}[\"{label}\"]", node_id(node.id));
+}
+
+/// Escapes text for a quoted Mermaid label, which takes HTML entities.
+fn escape(text: &str) -> String {
+    text.replace('&', "#amp;")
+        .replace('"', "#quot;")
+        .replace('<', "#lt;")
+        .replace('>', "#gt;")
+        .replace('\n', "<br/>")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        formats::{
+            fountain,
+            mermaid::{write, write_relationships},
+        },
+        models::{
+            Character, CharacterName, Narrative, Relationship, RelationshipKind, Scene, Storyboard,
+        },
+    };
+
+    /// Two scenes in a chain from a root, and one scene no root reaches.
+    fn generate_test_components() -> (Narrative, Scene) {
+        let storyboard =
+            fountain::parse("INT. HOUSE - DAY\n\nRain.\n\nEXT. YARD - NIGHT\n\nWind.\n").unwrap();
+        let mut narrative = storyboard.narrative().clone();
+        let orphan = Scene::new();
+        narrative.add_scene(orphan.clone()).unwrap();
+        (narrative, orphan)
+    }
+
+    #[test]
+    fn test_scenes_become_subgraphs_with_linked_variants() {
+        // ARRANGE
+        let (narrative, _) = generate_test_components();
+        // ACT
+        let chart = write(&narrative);
+        // ASSERT
+        assert!(chart.starts_with("flowchart TD\n"));
+        assert_eq!(chart.matches("  subgraph s").count(), 3);
+        assert_eq!(chart.matches("  end\n").count(), 3);
+        assert!(chart.contains("[\"INT. HOUSE - DAY"));
+        assert_eq!(chart.matches(" --> ").count(), 1);
+    }
+
+    #[test]
+    fn test_unreachable_variants_get_their_own_class() {
+        // ARRANGE
+        let (narrative, orphan) = generate_test_components();
+        // ACT
+        let chart = write(&narrative);
+        // ASSERT
+        let orphan_id = format!("v{}", orphan.active_variant().uuid().simple());
+        assert!(chart.contains(&format!("class {orphan_id} unreachable")));
+        assert!(chart.contains("classDef root"));
+    }
+
+    #[test]
+    fn test_scoped_relationships_are_drawn_dotted() {
+        // ARRANGE
+        let (narrative, orphan) = generate_test_components();
+        let mut storyboard = Storyboard::default();
+        *storyboard.narrative_mut() = narrative;
+        let kit = Character::new(CharacterName::new("Kit").unwrap());
+        let ray = Character::new(CharacterName::new("Ray").unwrap());
+        let sisters = Relationship::new(kit.id(), ray.id(), RelationshipKind::Sibling);
+        let mut rivals = Relationship::new(ray.id(), kit.id(), RelationshipKind::Rival);
+        rivals.set_span(Some(orphan.id()), None);
+        storyboard.add_character(kit).unwrap();
+        storyboard.add_character(ray).unwrap();
+        storyboard.add_relationship(sisters).unwrap();
+        storyboard.add_relationship(rivals).unwrap();
+        // ACT
+        let chart = write_relationships(&storyboard, &storyboard.relationships());
+        // ASSERT
+        assert!(chart.starts_with("flowchart LR\n"));
+        assert_eq!(chart.matches("([\"").count(), 2);
+        assert!(chart.contains(" -->|\"sibling of\"| "));
+        assert!(chart.contains(" -.->|\"rival of\"| "));
+    }
+}
@@ -0,0 +1,154 @@
+//! Conversions between a [`Storyboard`](crate::models::Storyboard) and the
+//! file formats screenwriters exchange scripts in, and exports of the story's
+//! structure for graph tools.
+
+mod builder;
+pub mod dot;
+pub mod fdx;
+pub mod fountain;
+mod graph;
+pub mod graphml;
+pub mod mermaid;
+pub mod pdf;
+
+use serde::Serialize;
+
+use crate::{
+    layout::LayoutError,
+    models::{
+        CameraLocation, Character, Id, NarrativeError, Parenthetical, SceneHeading, SceneLocation,
+        SceneTimeOfDay,
+    },
+    utils::InputError,
+};
+
+/// The heading text scripts print in place of a removed scene, beside its number.
+pub(crate) const OMITTED: &str = "OMITTED";
+
+/// Errors that can occur while reading a scene heading such as `INT. HOUSE - NIGHT`.
+#[derive(Debug, Serialize, PartialEq)]
+pub enum HeadingError {
+    /// The heading does not begin with `INT.`, `EXT.`, `INT./EXT.`, or `I/E`.
+    UnknownCameraLocation(String),
+    /// The heading has no ` - ` separating the location from the time of day.
+    MissingTimeOfDay,
+    /// The time of day is not one the engine models.
+    UnknownTimeOfDay(String),
+    /// The location text failed input validation.
+    InvalidLocation(InputError),
+}
+
+/// Errors that can occur while writing a storyboard out to a file format.
+#[derive(Debug, Serialize, PartialEq)]
+pub enum WriteError {
+    /// A dialogue's speaker is not among the storyboard's characters.
+    UnknownCharacter(Id<Character>),
+    /// The path through the story could not be worked out.
+    Narrative(NarrativeError),
+}
+
+impl From<NarrativeError> for WriteError {
+    fn from(value: NarrativeError) -> Self {
+        WriteError::Narrative(value)
+    }
+}
+
+impl From<LayoutError> for WriteError {
+    fn from(value: LayoutError) -> Self {
+        match value {
+            LayoutError::UnknownCharacter(id) => WriteError::UnknownCharacter(id),
+            LayoutError::Narrative(error) => WriteError::Narrative(error),
+        }
+    }
+}
+
+/// Heading prefixes and the camera location each maps to.
+///
+/// Longer prefixes come first so `INT./EXT.` is not read as `INT.`.
+const CAMERA_PREFIXES: [(&str, CameraLocation); 6] = [
+    ("INT./EXT", CameraLocation::InteriorExterior),
+    ("INT/EXT", CameraLocation::InteriorExterior),
+    ("I/E", CameraLocation::InteriorExterior),
+    ("INT", CameraLocation::Interior),
+    ("EXT", CameraLocation::Exterior),
+    ("EST", CameraLocation::Exterior),
+];
+
+/// Returns `true` if `text` begins with a recognized camera location prefix.
+pub(crate) fn has_camera_prefix(text: &str) -> bool {
+    split_camera_prefix(text).is_some()
+}
+
+/// Parses a scene heading of the form `INT. LOCATION - TIME`.
+///
+/// The location is everything between the camera prefix and the last ` - `,
+/// so headings like `INT. WHITE HOUSE - OVAL OFFICE - NIGHT` keep their
+/// sub-location.
+pub(crate) fn parse_scene_heading(text: &str) -> Result<SceneHeading, HeadingError> {
+    let (camera_location, rest) = split_camera_prefix(text)
+        .ok_or_else(|| HeadingError::UnknownCameraLocation(text.to_string()))?;
+
+    let (location, time) = rest
+        .rsplit_once(" - ")
+        .ok_or(HeadingError::MissingTimeOfDay)?;
+
+    let time_of_day = parse_time_of_day(time.trim())
+        .ok_or_else(|| HeadingError::UnknownTimeOfDay(time.trim().to_string()))?;
+    let scene_location = SceneLocation::new(location).map_err(HeadingError::InvalidLocation)?;
+
+    Ok(SceneHeading::new(
+        camera_location,
+        scene_location,
+        time_of_day,
+    ))
+}
+
+/// Returns a parenthetical wrapped in parentheses, as it appears in a script.
+///
+/// Older projects stored some parentheticals with their parentheses, so text
+/// that is already wrapped is returned as-is.
+pub(crate) fn parenthetical_text(parenthetical: &Parenthetical) -> String {
+    let text = parenthetical.as_str();
+
+    if text.starts_with('(') && text.ends_with(')') {
+        text.to_string()
+    } else {
+        format!("({text})")
+    }
+}
+
+/// Splits a heading into its camera location and the remaining text.
+///
+/// The prefixes are ASCII, so they are matched against the original text
+/// without uppercasing it; uppercasing can change the byte length of other
+/// characters, such as `ﬆ` becoming `ST`.
+fn split_camera_prefix(text: &str) -> Option<(CameraLocation, &str)> {
+    CAMERA_PREFIXES.iter().find_map(|(prefix, location)| {
+        let head = text.get(..prefix.len())?;
+        if !head.eq_ignore_ascii_case(prefix) {
+            return None;
+        }
+
+        let rest = &text[prefix.len()..];
+        if !(rest.starts_with('.') || rest.starts_with(' ')) {
+            return None;
+        }
+
+        Some((location.clone(), rest.trim_start_matches('.').trim()))
+    })
+}
+
+/// Maps the time-of-day suffix of a heading onto [`SceneTimeOfDay`].
+fn parse_time_of_day(text: &str) -> Option<SceneTimeOfDay> {
+    match text.to_uppercase().as_str() {
+        "MORNING" => Some(SceneTimeOfDay::Morning),
+        "DAWN" => Some(SceneTimeOfDay::Dawn),
+        "DAY" => Some(SceneTimeOfDay::Day),
+        "DUSK" => Some(SceneTimeOfDay::Dusk),
+        "EVENING" => Some(SceneTimeOfDay::Evening),
+        "NIGHT" => Some(SceneTimeOfDay::Night),
+        "LATER" | "MOMENTS LATER" => Some(SceneTimeOfDay::Later),
+        "CONTINUOUS" => Some(SceneTimeOfDay::Continuous),
+        _ => None,
+    }
+}
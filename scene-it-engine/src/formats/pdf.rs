@@ -0,0 +1,333 @@
+//! Rendering screenplays to PDF in the industry-standard format.
+//!
+//! Pages are US Letter, set in 12pt Courier: ten characters to the inch and
+//! six lines to the inch. Courier is one of the fourteen standard PDF fonts,
+//! so every viewer already has it and nothing needs to be embedded. The
+//! renderer writes the PDF objects directly, with no system fonts or
+//! external binaries involved.
+
+use std::fmt::Write;
+
+use crate::{
+    formats::WriteError,
+    layout::paginate,
+    models::{BranchPolicy, Id, SceneVariant, Storyboard},
+};
+
+/// Points per inch in PDF user space.
+const POINTS_PER_INCH: f32 = 72.0;
+/// US Letter page width, in points.
+const PAGE_WIDTH: f32 = 8.5 * POINTS_PER_INCH;
+/// US Letter page height, in points.
+const PAGE_HEIGHT: f32 = 11.0 * POINTS_PER_INCH;
+/// Courier 12pt sets six lines to the inch.
+const LINE_HEIGHT: f32 = 12.0;
+/// Courier 12pt sets ten characters to the inch.
+const CHAR_WIDTH: f32 = 7.2;
+/// Distance from the top of the page to the first line of text, in inches.
+const TOP_MARGIN: f32 = 1.0;
+/// Right edge of the scene number set left of a heading, in inches.
+const LEFT_SCENE_NUMBER_END: f32 = 1.25;
+/// Left edge of the scene number set right of a heading, in inches.
+const RIGHT_SCENE_NUMBER_INDENT: f32 = 7.625;
+/// Left edge of the asterisk marking a revised line, in inches; past the
+/// scene numbers in the right margin.
+const REVISION_MARK_INDENT: f32 = 8.0;
+
+/// Renders the path through the storyboard starting at `root` as a PDF.
+///
+/// The document opens with a title page built from the storyboard title and
+/// authors, followed by the script pages produced by [`paginate`], each line
+/// set at the standard screenplay indent for its kind. Script pages are
+/// numbered in the top-right corner from page two on; by convention the
+/// first page carries no number. Scene numbers are set in both margins beside
+/// their headings, and lines changed in the storyboard's current revision set
+/// are marked with an asterisk in the right margin.
+///
+/// # Errors
+///
+/// - [`WriteError::UnknownCharacter`] if a dialogue's speaker is not among
+///   the storyboard's characters.
+/// - [`WriteError::Narrative`] if the path cannot be linearized.
+pub fn render(
+    storyboard: &Storyboard,
+    root: Id<SceneVariant>,
+    policy: &BranchPolicy,
+) -> Result<Vec<u8>, WriteError> {
+    let pagination = paginate(storyboard, root, policy)?;
+    let mut pages = vec![title_page(storyboard)];
+
+    for page in &pagination.pages {
+        let mut content = String::new();
+        if page.number > 1 {
+            let label = format!("{}.", page.number);
+            let x = PAGE_WIDTH - POINTS_PER_INCH - label.len() as f32 * CHAR_WIDTH;
+            draw_text(&mut content, x, PAGE_HEIGHT - 0.5 * POINTS_PER_INCH, &label);
+        }
+        for (row, line) in page.lines.iter().enumerate() {
+            if let Some(line) = line {
+                let x = line.kind.indent() * POINTS_PER_INCH;
+                draw_text(&mut content, x, baseline(row), &line.text);
+                if let Some(number) = &line.scene_number {
+                    let label = number.as_str();
+                    let left = LEFT_SCENE_NUMBER_END * POINTS_PER_INCH
+                        - label.chars().count() as f32 * CHAR_WIDTH;
+                    let right = RIGHT_SCENE_NUMBER_INDENT * POINTS_PER_INCH;
+                    draw_text(&mut content, left, baseline(row), label);
+                    draw_text(&mut content, right, baseline(row), label);
+                }
+                if line.revised {
+                    let x = REVISION_MARK_INDENT * POINTS_PER_INCH;
+                    draw_text(&mut content, x, baseline(row), "*");
+                }
+            }
+        }
+        pages.push(content);
+    }
+
+    Ok(write_document(&pages))
+}
+
+/// Builds the content stream for the title page.
+///
+/// The title sits a third of the way down the page with the author credit
+/// beneath it, both centered.
+fn title_page(storyboard: &Storyboard) -> String {
+    let mut authors: Vec<_> = storyboard
+        .authors()
+        .iter()
+        .map(|a| a.name().to_string())
+        .collect();
+    authors.sort_unstable();
+
+    let mut block = Vec::new();
+    if let Some(title) = storyboard.title() {
+        block.extend([title.to_uppercase(), String::new()]);
+    }
+    if !authors.is_empty() {
+        block.extend(["Written by".to_string(), String::new(), authors.join(" & ")]);
+    }
+
+    let mut content = String::new();
+    for (row, text) in block.iter().enumerate() {
+        let x = (PAGE_WIDTH - text.chars().count() as f32 * CHAR_WIDTH) / 2.0;
+        draw_text(&mut content, x, baseline(row + 18), text);
+    }
+
+    content
+}
+
+/// Returns the baseline of the given line on the page, in points from the bottom.
+fn baseline(row: usize) -> f32 {
+    PAGE_HEIGHT - TOP_MARGIN * POINTS_PER_INCH - (row + 1) as f32 * LINE_HEIGHT
+}
+
+/// Appends a text-drawing operation to a content stream.
+fn draw_text(content: &mut String, x: f32, y: f32, text: &str) {
+    if text.is_empty() {
+        return;
+    }
+
+    let _ = writeln!(
+        content,
+        "BT /F1 12 Tf {x:.2} {y:.2} Td ({}) Tj ET",
+        encode_text(text)
+    );
+}
+
+/// Encodes text as the body of a PDF literal string in WinAnsiEncoding.
+///
+/// Characters outside WinAnsi are replaced with `?`.
+fn encode_text(text: &str) -> String {
+    let mut encoded = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        let byte = match c {
+            '\u{20}'..='\u{7e}' | '\u{a0}'..='\u{ff}' => c as u32 as u8,
+            '\u{2018}' => 0x91,
+            '\u{2019}' => 0x92,
+            '\u{201c}' => 0x93,
+            '\u{201d}' => 0x94,
+            '\u{2022}' => 0x95,
+            '\u{2013}' => 0x96,
+            '\u{2014}' => 0x97,
+            '\u{2026}' => 0x85,
+            _ => b'?',
+        };
+
+        match byte {
+            b'(' | b')' | b'\\' => {
+                encoded.push('\\');
+                encoded.push(byte as char);
+            }
+            0x20..=0x7e => encoded.push(byte as char),
+            _ => {
+                let _ = write!(encoded, "\\{byte:03o}");
+            }
+        }
+    }
+
+    encoded
+}
+
+/// Assembles page content streams into a complete PDF file.
+///
+/// Object 1 is the catalog, object 2 the page tree, and object 3 the Courier
+/// font; each page then takes two objects, its content stream and the page
+/// dictionary itself.
+fn write_document(pages: &[String]) -> Vec<u8> {
+    let mut objects: Vec<String> = Vec::new();
+    let page_ids: Vec<usize> = (0..pages.len()).map(|i| 5 + 2 * i).collect();
+    let kids = page_ids
+        .iter()
+        .map(|id| format!("{id} 0 R"))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    objects.push("<< /Type /Catalog /Pages 2 0 R >>".to_string());
+    objects.push(format!(
+        "<< /Type /Pages /Kids [{kids}] /Count {} >>",
+        pages.len()
+    ));
+    objects.push(
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Courier /Encoding /WinAnsiEncoding >>"
+            .to_string(),
+    );
+
+    for (content, page_id) in pages.iter().zip(&page_ids) {
+        objects.push(format!(
+            "<< /Length {} >>\nstream\n{content}endstream",
+            content.len()
+        ));
+        objects.push(format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {PAGE_WIDTH} {PAGE_HEIGHT}] \
+             /Resources << /Font << /F1 3 0 R >> >> /Contents {} 0 R >>",
+            page_id - 1
+        ));
+    }
+
+    let mut output = String::from("%PDF-1.4\n");
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (index, object) in objects.iter().enumerate() {
+        offsets.push(output.len());
+        let _ = write!(output, "{} 0 obj\n{object}\nendobj\n", index + 1);
+    }
+
+    let xref = output.len();
+    let _ = write!(
+        output,
+        "xref\n0 {}\n0000000000 65535 f \n",
+        objects.len() + 1
+    );
+    for offset in offsets {
+        let _ = writeln!(output, "{offset:010} 00000 n ");
+    }
+    let _ = write!(
+        output,
+        "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{xref}\n%%EOF\n",
+        objects.len() + 1
+    );
+
+    output.into_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        formats::{
+            fdx,
+            pdf::{encode_text, render},
+        },
+        models::{
+            BranchPolicy, RevisionColor, RevisionNote, SceneAction, SceneElement, Storyboard,
+        },
+    };
+
+    const FIXTURE: &str = include_str!("../../../data/screenplay1.fdx");
+
+    fn rendered_fixture() -> String {
+        let storyboard: Storyboard = fdx::parse(FIXTURE).unwrap();
+        let root = *storyboard
+            .narrative()
+            .graph()
+            .roots()
+            .iter()
+            .next()
+            .unwrap();
+        String::from_utf8(render(&storyboard, root, &BranchPolicy::default()).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_rendered_document_is_a_courier_pdf() {
+        // ARRANGE & ACT
+        let pdf = rendered_fixture();
+        // ASSERT
+        assert!(pdf.starts_with("%PDF-1.4\n"));
+        assert!(pdf.ends_with("%%EOF\n"));
+        assert!(pdf.contains("/BaseFont /Courier"));
+    }
+
+    #[test]
+    fn test_rendered_document_has_a_title_page_and_numbered_pages() {
+        // ARRANGE & ACT
+        let pdf = rendered_fixture();
+        let page_count = pdf.matches("/Type /Page ").count();
+        // ASSERT
+        assert!(page_count > 2);
+        assert!(pdf.contains("(ENCRYPTION) Tj"));
+        assert!(pdf.contains("(2.) Tj"));
+        assert!(pdf.contains(&format!("({}.) Tj", page_count - 1)));
+    }
+
+    #[test]
+    fn test_scene_numbers_are_set_in_both_margins() {
+        // ARRANGE & ACT
+        let pdf = rendered_fixture();
+        // ASSERT
+        assert_eq!(pdf.matches("(1) Tj").count(), 2);
+        assert_eq!(pdf.matches("(10) Tj").count(), 2);
+    }
+
+    #[test]
+    fn test_only_revised_lines_carry_a_revision_mark() {
+        // ARRANGE
+        let mut storyboard: Storyboard = fdx::parse(FIXTURE).unwrap();
+        let root = *storyboard
+            .narrative()
+            .graph()
+            .roots()
+            .iter()
+            .next()
+            .unwrap();
+        let unrevised =
+            String::from_utf8(render(&storyboard, root, &BranchPolicy::default()).unwrap())
+                .unwrap();
+        storyboard
+            .open_revision(
+                RevisionColor::Blue,
+                RevisionNote::new("Blue pages").unwrap(),
+            )
+            .unwrap();
+        let added = SceneElement::Action(SceneAction::new("Sirens, far off.").unwrap());
+        storyboard
+            .narrative_mut()
+            .variant_mut(&root)
+            .unwrap()
+            .add_element(added)
+            .unwrap();
+        // ACT
+        let pdf = String::from_utf8(render(&storyboard, root, &BranchPolicy::default()).unwrap())
+            .unwrap();
+        // ASSERT
+        assert!(!unrevised.contains("(*) Tj"));
+        assert_eq!(pdf.matches("(*) Tj").count(), 1);
+    }
+
+    #[test]
+    fn test_text_is_escaped_for_pdf_strings() {
+        // ARRANGE & ACT
+        let encoded = encode_text("(beat) \\ it’s");
+        // ASSERT
+        assert_eq!(encoded, "\\(beat\\) \\\\ it\\222s");
+    }
+}
@@ -0,0 +1,682 @@
+use serde::{Deserialize, Serialize};
+
+use std::collections::{HashMap, HashSet};
+
+use crate::models::{Id, Narrative, Scene, SceneVariant};
+
+/// Horizontal distance between the centres of neighbouring variants of a scene.
+pub const NODE_SPACING: f32 = 200.0;
+
+/// Extra horizontal space left between the variants of different scenes.
+pub const SCENE_GAP: f32 = 60.0;
+
+/// Vertical distance between layers.
+pub const LAYER_SPACING: f32 = 140.0;
+
+/// Rounds of barycenter ordering tried when laying out from scratch.
+const SWEEPS: usize = 8;
+
+/// Where a scene variant sits in a [`GraphLayout`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct NodePosition {
+    pub variant: Id<SceneVariant>,
+    /// The scene that owns the variant, or `None` if it belongs to none.
+    pub scene: Option<Id<Scene>>,
+    /// The layer the variant is drawn in, counted from the top.
+    pub layer: usize,
+    /// The horizontal position of the node's centre.
+    pub x: f32,
+    /// The vertical position of the node's centre.
+    pub y: f32,
+}
+
+/// The course of an edge, from the centre of `src` to the centre of `dest`.
+///
+/// Edges that skip layers bend once in every layer they pass through, so
+/// that they are routed around the nodes there rather than through them.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct EdgeRoute {
+    pub src: Id<SceneVariant>,
+    pub dest: Id<SceneVariant>,
+    /// The points the edge passes through, `src` first and `dest` last.
+    pub points: Vec<(f32, f32)>,
+}
+
+/// Node coordinates and edge routes for drawing a scene graph.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct GraphLayout {
+    /// Every variant in the graph, ordered by layer and then left to right.
+    pub nodes: Vec<NodePosition>,
+    /// Every edge in the graph, ordered by `src` and then `dest`.
+    pub edges: Vec<EdgeRoute>,
+}
+
+impl GraphLayout {
+    /// Returns where the variant sits, if it is in the layout.
+    pub fn position(&self, variant: Id<SceneVariant>) -> Option<&NodePosition> {
+        self.nodes.iter().find(|n| n.variant == variant)
+    }
+
+    /// Returns the number of places where two edges cross between
+    /// neighbouring layers.
+    pub fn crossings(&self) -> usize {
+        let segments: Vec<_> = self
+            .edges
+            .iter()
+            .flat_map(|e| e.points.windows(2).map(|w| (w[0], w[1])))
+            .filter(|(a, b)| a.1 < b.1)
+            .collect();
+
+        let mut count = 0;
+        for (i, (a1, b1)) in segments.iter().enumerate() {
+            for (a2, b2) in &segments[i + 1..] {
+                let same_band = a1.1 == a2.1 && b1.1 == b2.1;
+                if same_band && (a1.0 - a2.0) * (b1.0 - b2.0) < 0.0 {
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+}
+
+/// Lays out the narrative's scene graph from scratch.
+///
+/// This is a Sugiyama-style layered layout:
+///
+/// 1. The variants of each scene are grouped into a block drawn side by
+///    side, active variant first. Variants that belong to no scene form
+///    blocks of their own.
+/// 2. Blocks are assigned to layers so that every edge points downward,
+///    each block one layer below its lowest predecessor. Edges that would
+///    close a loop, or that run between variants of the same scene, are
+///    drawn but do not affect layering.
+/// 3. Edges that skip layers get a bend point in each layer they cross, and
+///    blocks within each layer are reordered by the barycenter heuristic to
+///    keep crossings low.
+/// 4. Each block is placed under the average of its predecessors, pushed
+///    right as needed to keep [`NODE_SPACING`] between variants and a
+///    further [`SCENE_GAP`] between scenes.
+///
+/// The result depends only on the graph, so the same narrative always lays
+/// out the same way. The leftmost node is at `x = 0` and the top layer at
+/// `y = 0`.
+pub fn layout_graph(narrative: &Narrative) -> GraphLayout {
+    Layered::new(narrative).layout(None)
+}
+
+/// Lays out the narrative's scene graph after a small change, keeping nodes
+/// close to where they were in `previous`.
+///
+/// Blocks that were already laid out keep their left-to-right order and aim
+/// for their old positions; new blocks and bend points go to the average
+/// position of their neighbours. Nodes move only as far as layering and
+/// spacing require, so linking two variants does not rearrange the rest of
+/// the graph. Unlike [`layout_graph`], coordinates are not shifted back to
+/// start at zero, since that would move every node.
+///
+/// After many changes the layout can drift from what [`layout_graph`] would
+/// produce; laying out from scratch tidies it up.
+pub fn update_layout(narrative: &Narrative, previous: &GraphLayout) -> GraphLayout {
+    Layered::new(narrative).layout(Some(previous))
+}
+
+/// A group of nodes laid out together within a layer: the variants of one
+/// scene, a variant that belongs to no scene, or an edge's bend point.
+struct Block {
+    /// The variants in the block, in drawing order. Empty for a bend point.
+    members: Vec<Id<SceneVariant>>,
+    scene: Option<Id<Scene>>,
+}
+
+impl Block {
+    /// Distance from the centre of the first member to that of the last.
+    fn span(&self) -> f32 {
+        self.members.len().saturating_sub(1) as f32 * NODE_SPACING
+    }
+
+    fn is_bend(&self) -> bool {
+        self.members.is_empty()
+    }
+}
+
+/// The working state of a layered layout.
+struct Layered {
+    blocks: Vec<Block>,
+    /// The layer of each block.
+    layer: Vec<usize>,
+    /// The blocks in each layer, left to right.
+    layers: Vec<Vec<usize>>,
+    /// Links between blocks in neighbouring layers, upper block first.
+    segments: Vec<(usize, usize)>,
+    /// Each edge as the chain of blocks it passes through.
+    routes: Vec<(Id<SceneVariant>, Id<SceneVariant>, Vec<usize>)>,
+}
+
+impl Layered {
+    fn new(narrative: &Narrative) -> Self {
+        let graph = narrative.graph();
+        let by_uuid = |ids: &mut Vec<Id<SceneVariant>>| ids.sort_by_key(|id| id.uuid());
+
+        // Blocks: each scene's variants, then variants without a scene.
+        let mut scenes: Vec<_> = narrative.scenes().collect();
+        scenes.sort_by_key(|s| s.id().uuid());
+        let mut blocks = Vec::new();
+        let mut block_of = HashMap::new();
+        for scene in scenes {
+            let mut members: Vec<_> = scene
+                .variant_ids()
+                .filter(|v| graph.contains(**v))
+                .copied()
+                .collect();
+            members.sort_by_key(|v| (v != scene.active_variant(), v.uuid()));
+            if members.is_empty() {
+                continue;
+            }
+            for member in &members {
+                block_of.insert(*member, blocks.len());
+            }
+            blocks.push(Block {
+                members,
+                scene: Some(scene.id()),
+            });
+        }
+        let mut unowned: Vec<_> = graph
+            .variants()
+            .filter(|v| !block_of.contains_key(v))
+            .collect();
+        by_uuid(&mut unowned);
+        for variant in unowned {
+            block_of.insert(variant, blocks.len());
+            blocks.push(Block {
+                members: vec![variant],
+                scene: None,
+            });
+        }
+
+        let mut variants: Vec<_> = graph.variants().collect();
+        by_uuid(&mut variants);
+        let mut edges = Vec::new();
+        for src in &variants {
+            let mut dests: Vec<_> = graph
+                .next_variants(*src)
+                .filter(|d| graph.contains(*d))
+                .collect();
+            by_uuid(&mut dests);
+            edges.extend(dests.into_iter().map(|dest| (*src, dest)));
+        }
+
+        // Depth-first from the roots' blocks, then the rest, dropping block
+        // edges that lead back onto the path.
+        let mut successors: Vec<Vec<usize>> = vec![Vec::new(); blocks.len()];
+        for (src, dest) in &edges {
+            let (from, to) = (block_of[src], block_of[dest]);
+            if from != to && !successors[from].contains(&to) {
+                successors[from].push(to);
+            }
+        }
+        let mut roots: Vec<_> = graph
+            .roots()
+            .iter()
+            .filter(|r| graph.contains(**r))
+            .copied()
+            .collect();
+        by_uuid(&mut roots);
+        let starts: Vec<_> = roots
+            .iter()
+            .map(|r| block_of[r])
+            .chain(0..blocks.len())
+            .collect();
+
+        let mut discovered = Vec::new();
+        let mut seen = HashSet::new();
+        let mut kept: Vec<Vec<usize>> = vec![Vec::new(); blocks.len()];
+        for start in starts {
+            if !seen.insert(start) {
+                continue;
+            }
+            let mut path = vec![start];
+            discovered.push(start);
+            let mut stack = vec![successors[start].iter().rev().copied().collect::<Vec<_>>()];
+            while let Some(pending) = stack.last_mut() {
+                let Some(next) = pending.pop() else {
+                    stack.pop();
+                    path.pop();
+                    continue;
+                };
+                if path.contains(&next) {
+                    continue;
+                }
+                if let Some(&current) = path.last() {
+                    kept[current].push(next);
+                }
+                if seen.insert(next) {
+                    discovered.push(next);
+                    path.push(next);
+                    stack.push(successors[next].iter().rev().copied().collect());
+                }
+            }
+        }
+
+        // Longest-path layering over the kept edges, in topological order.
+        let mut indegree = vec![0; blocks.len()];
+        for to in kept.iter().flatten() {
+            indegree[*to] += 1;
+        }
+        let mut layer = vec![0; blocks.len()];
+        let mut ready: Vec<_> = discovered
+            .iter()
+            .rev()
+            .filter(|b| indegree[**b] == 0)
+            .copied()
+            .collect();
+        while let Some(block) = ready.pop() {
+            for &next in &kept[block] {
+                layer[next] = layer[next].max(layer[block] + 1);
+                indegree[next] -= 1;
+                if indegree[next] == 0 {
+                    ready.push(next);
+                }
+            }
+        }
+
+        // Routes, with a bend point in every layer an edge skips.
+        let mut segments = Vec::new();
+        let mut routes = Vec::new();
+        for (src, dest) in edges {
+            let (from, to) = (block_of[&src], block_of[&dest]);
+            let mut chain = vec![from];
+            if layer[to] > layer[from] {
+                for bend_layer in layer[from] + 1..layer[to] {
+                    chain.push(blocks.len());
+                    layer.push(bend_layer);
+                    blocks.push(Block {
+                        members: Vec::new(),
+                        scene: None,
+                    });
+                }
+                chain.push(to);
+                segments.extend(chain.windows(2).map(|w| (w[0], w[1])));
+            } else {
+                chain.push(to);
+            }
+            routes.push((src, dest, chain));
+        }
+
+        let mut layers = vec![Vec::new(); layer.iter().max().map_or(0, |l| l + 1)];
+        let bends = discovered.len()..blocks.len();
+        for block in discovered.into_iter().chain(bends) {
+            layers[layer[block]].push(block);
+        }
+
+        Self {
+            blocks,
+            layer,
+            layers,
+            segments,
+            routes,
+        }
+    }
+
+    fn layout(mut self, previous: Option<&GraphLayout>) -> GraphLayout {
+        let desired = match previous {
+            Some(previous) => {
+                let (anchors, placed) = self.anchors(previous);
+                // On a tie, a block that was already placed keeps its spot.
+                for blocks in &mut self.layers {
+                    blocks.sort_by(|a, b| {
+                        anchors[*a]
+                            .total_cmp(&anchors[*b])
+                            .then(placed[*b].cmp(&placed[*a]))
+                    });
+                }
+                anchors.into_iter().map(Some).collect()
+            }
+            None => {
+                self.reduce_crossings();
+                vec![None; self.blocks.len()]
+            }
+        };
+
+        let left = self.place(desired, previous.is_none());
+        self.finish(&left)
+    }
+
+    /// Returns a target centre for every block, and whether the block was
+    /// in the previous layout.
+    ///
+    /// A block's target is its old position if it had one, otherwise the
+    /// average target of its neighbours. Blocks with neither go last.
+    fn anchors(&self, previous: &GraphLayout) -> (Vec<f32>, Vec<bool>) {
+        let old: HashMap<_, _> = previous.nodes.iter().map(|n| (n.variant, n.x)).collect();
+        let mut anchors: Vec<Option<f32>> = self
+            .blocks
+            .iter()
+            .map(|block| {
+                let xs: Vec<_> = block.members.iter().filter_map(|m| old.get(m)).collect();
+                (!xs.is_empty()).then(|| xs.iter().copied().sum::<f32>() / xs.len() as f32)
+            })
+            .collect();
+        let placed = anchors.iter().map(Option::is_some).collect();
+
+        // Fill in the gaps from above, then from below for anything left.
+        for downward in [true, false] {
+            let order: Vec<_> = match downward {
+                true => self.layers.iter().flatten().copied().collect(),
+                false => self.layers.iter().rev().flatten().copied().collect(),
+            };
+            for block in order {
+                if anchors[block].is_some() {
+                    continue;
+                }
+                let known: Vec<_> = self
+                    .neighbours(block, downward)
+                    .filter_map(|n| anchors[n])
+                    .collect();
+                if !known.is_empty() {
+                    anchors[block] = Some(known.iter().sum::<f32>() / known.len() as f32);
+                }
+            }
+        }
+
+        let anchors = anchors.into_iter().map(|a| a.unwrap_or(f32::MAX)).collect();
+        (anchors, placed)
+    }
+
+    /// Returns the blocks linked to `block` in the layer above, or below.
+    fn neighbours(&self, block: usize, above: bool) -> impl Iterator<Item = usize> + '_ {
+        self.segments
+            .iter()
+            .filter_map(move |&(upper, lower)| match above {
+                true => (lower == block).then_some(upper),
+                false => (upper == block).then_some(lower),
+            })
+    }
+
+    /// Reorders each layer by the barycenter of its neighbours, sweeping down
+    /// and up, and keeps the ordering with the fewest crossings.
+    fn reduce_crossings(&mut self) {
+        let mut best = self.layers.clone();
+        let mut fewest = self.count_crossings();
+
+        for sweep in 0..SWEEPS {
+            let downward = sweep % 2 == 0;
+            let range: Vec<_> = match downward {
+                true => (1..self.layers.len()).collect(),
+                false => (0..self.layers.len().saturating_sub(1)).rev().collect(),
+            };
+            for index in range {
+                let fixed = if downward { index - 1 } else { index + 1 };
+                let position: HashMap<_, _> = self.layers[fixed]
+                    .iter()
+                    .enumerate()
+                    .map(|(p, b)| (*b, p as f32))
+                    .collect();
+
+                let keys: HashMap<_, _> = self.layers[index]
+                    .iter()
+                    .enumerate()
+                    .map(|(current, block)| {
+                        let linked: Vec<_> = self
+                            .neighbours(*block, downward)
+                            .filter_map(|n| position.get(&n))
+                            .collect();
+                        let key = match linked.is_empty() {
+                            true => current as f32,
+                            false => linked.iter().copied().sum::<f32>() / linked.len() as f32,
+                        };
+                        (*block, key)
+                    })
+                    .collect();
+                self.layers[index].sort_by(|a, b| keys[a].total_cmp(&keys[b]));
+            }
+
+            let crossings = self.count_crossings();
+            if crossings < fewest {
+                fewest = crossings;
+                best = self.layers.clone();
+            }
+        }
+
+        self.layers = best;
+    }
+
+    fn count_crossings(&self) -> usize {
+        let position: HashMap<_, _> = self
+            .layers
+            .iter()
+            .flat_map(|blocks| blocks.iter().enumerate().map(|(p, b)| (*b, p)))
+            .collect();
+
+        let mut count = 0;
+        for (i, (a1, b1)) in self.segments.iter().enumerate() {
+            for (a2, b2) in &self.segments[i + 1..] {
+                if self.layer[*a1] != self.layer[*a2] {
+                    continue;
+                }
+                let (top, bottom) = (
+                    position[a1].cmp(&position[a2]),
+                    position[b1].cmp(&position[b2]),
+                );
+                if top.is_ne() && bottom.is_ne() && top != bottom {
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+
+    /// Places the blocks of each layer left to right, each as close to its
+    /// target as spacing allows, and returns the centre of each block's
+    /// first node.
+    ///
+    /// Blocks without a target aim for the average centre of their
+    /// neighbours in the layer above.
+    fn place(&self, desired: Vec<Option<f32>>, normalize: bool) -> Vec<f32> {
+        let mut left = vec![0.0; self.blocks.len()];
+        let centre = |left: &[f32], block: usize| left[block] + self.blocks[block].span() / 2.0;
+
+        for blocks in &self.layers {
+            let mut previous: Option<usize> = None;
+            for &block in blocks {
+                let target = desired[block].filter(|x| *x < f32::MAX).or_else(|| {
+                    let above: Vec<_> = self
+                        .neighbours(block, true)
+                        .map(|n| centre(&left, n))
+                        .collect();
+                    (!above.is_empty()).then(|| above.iter().sum::<f32>() / above.len() as f32)
+                });
+                let wanted = target.map(|x| x - self.blocks[block].span() / 2.0);
+
+                let earliest = previous.map(|p| {
+                    let gap = match self.blocks[p].is_bend() || self.blocks[block].is_bend() {
+                        true => NODE_SPACING / 2.0,
+                        false => NODE_SPACING + SCENE_GAP,
+                    };
+                    left[p] + self.blocks[p].span() + gap
+                });
+
+                left[block] = match (wanted, earliest) {
+                    (Some(w), Some(e)) => w.max(e),
+                    (Some(w), None) => w,
+                    (None, Some(e)) => e,
+                    (None, None) => 0.0,
+                };
+                previous = Some(block);
+            }
+        }
+
+        if normalize {
+            let min = left.iter().copied().fold(f32::MAX, f32::min);
+            if min.is_finite() {
+                left.iter_mut().for_each(|x| *x -= min);
+            }
+        }
+
+        left
+    }
+
+    fn finish(self, left: &[f32]) -> GraphLayout {
+        let y = |block: usize| self.layer[block] as f32 * LAYER_SPACING;
+
+        let mut nodes = Vec::new();
+        let mut centres = HashMap::new();
+        for blocks in &self.layers {
+            for &block in blocks {
+                for (index, variant) in self.blocks[block].members.iter().enumerate() {
+                    let x = left[block] + index as f32 * NODE_SPACING;
+                    centres.insert(*variant, (x, y(block)));
+                    nodes.push(NodePosition {
+                        variant: *variant,
+                        scene: self.blocks[block].scene,
+                        layer: self.layer[block],
+                        x,
+                        y: y(block),
+                    });
+                }
+            }
+        }
+
+        let edges = self
+            .routes
+            .iter()
+            .map(|(src, dest, chain)| {
+                let bends = chain[1..chain.len() - 1].iter().map(|b| (left[*b], y(*b)));
+                EdgeRoute {
+                    src: *src,
+                    dest: *dest,
+                    points: std::iter::once(centres[src])
+                        .chain(bends)
+                        .chain(std::iter::once(centres[dest]))
+                        .collect(),
+                }
+            })
+            .collect();
+
+        GraphLayout { nodes, edges }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        layout::{LAYER_SPACING, NODE_SPACING, layout_graph, update_layout},
+        models::{Id, Narrative, Scene, SceneVariant},
+    };
+
+    /// Three scenes linked in a chain from a root.
+    fn generate_test_components() -> (Narrative, Vec<Id<SceneVariant>>) {
+        let mut narrative = Narrative::default();
+        let mut variants = Vec::new();
+        for _ in 0..3 {
+            variants.push(add_scene(&mut narrative));
+        }
+        narrative.set_variant_as_root(variants[0]).unwrap();
+        narrative.link_variants(variants[0], variants[1]).unwrap();
+        narrative.link_variants(variants[1], variants[2]).unwrap();
+
+        (narrative, variants)
+    }
+
+    fn add_scene(narrative: &mut Narrative) -> Id<SceneVariant> {
+        let scene = Scene::new();
+        let variant = *scene.active_variant();
+        narrative.add_scene(scene).unwrap();
+        variant
+    }
+
+    #[test]
+    fn test_chained_scenes_are_laid_out_in_layers() {
+        // ARRANGE
+        let (narrative, variants) = generate_test_components();
+        // ACT
+        let layout = layout_graph(&narrative);
+        // ASSERT
+        for (layer, variant) in variants.iter().enumerate() {
+            let node = layout.position(*variant).unwrap();
+            assert_eq!(node.layer, layer);
+            assert_eq!(node.y, layer as f32 * LAYER_SPACING);
+            assert_eq!(node.x, 0.0);
+        }
+        assert_eq!(layout, layout_graph(&narrative));
+    }
+
+    #[test]
+    fn test_variants_of_a_scene_sit_side_by_side() {
+        // ARRANGE
+        let (mut narrative, variants) = generate_test_components();
+        let scene_id = narrative.scene_for_variant(&variants[1]).unwrap().id();
+        let mut scene = narrative.scene(&scene_id).unwrap().clone();
+        let alternate = SceneVariant::new();
+        let alternate_id = alternate.id();
+        scene.variants_mut().insert(alternate_id, alternate);
+        narrative.remove_scene(scene_id).unwrap();
+        narrative.add_scene(scene).unwrap();
+        for (src, dest) in [
+            (variants[0], variants[1]),
+            (variants[1], variants[2]),
+            (variants[0], alternate_id),
+            (alternate_id, variants[2]),
+        ] {
+            narrative.link_variants(src, dest).unwrap();
+        }
+        // ACT
+        let layout = layout_graph(&narrative);
+        // ASSERT
+        let active = layout.position(variants[1]).unwrap();
+        let other = layout.position(alternate_id).unwrap();
+        assert_eq!(active.layer, other.layer);
+        assert_eq!(other.x - active.x, NODE_SPACING);
+        assert_eq!(layout.crossings(), 0);
+    }
+
+    #[test]
+    fn test_edges_that_skip_a_layer_bend_around_it() {
+        // ARRANGE
+        let (mut narrative, variants) = generate_test_components();
+        narrative.link_variants(variants[0], variants[2]).unwrap();
+        // ACT
+        let layout = layout_graph(&narrative);
+        // ASSERT
+        let skip = layout
+            .edges
+            .iter()
+            .find(|e| e.src == variants[0] && e.dest == variants[2])
+            .unwrap();
+        assert_eq!(skip.points.len(), 3);
+        assert_ne!(skip.points[1].0, layout.position(variants[1]).unwrap().x);
+    }
+
+    #[test]
+    fn test_branching_layouts_avoid_crossings() {
+        // ARRANGE
+        let mut narrative = Narrative::default();
+        let v: Vec<_> = (0..7).map(|_| add_scene(&mut narrative)).collect();
+        narrative.set_variant_as_root(v[0]).unwrap();
+        for (src, dest) in [(0, 1), (0, 2), (1, 3), (1, 4), (2, 5), (2, 6), (4, 5)] {
+            narrative.link_variants(v[src], v[dest]).unwrap();
+        }
+        // ACT
+        let layout = layout_graph(&narrative);
+        // ASSERT
+        assert_eq!(layout.crossings(), 0);
+    }
+
+    #[test]
+    fn test_updating_a_layout_keeps_existing_nodes_in_place() {
+        // ARRANGE
+        let (mut narrative, variants) = generate_test_components();
+        let before = layout_graph(&narrative);
+        let added = add_scene(&mut narrative);
+        narrative.link_variants(variants[1], added).unwrap();
+        // ACT
+        let after = update_layout(&narrative, &before);
+        // ASSERT
+        for variant in &variants {
+            assert_eq!(after.position(*variant), before.position(*variant));
+        }
+        let node = after.position(added).unwrap();
+        assert_eq!(node.layer, 2);
+        assert!(node.x > after.position(variants[2]).unwrap().x);
+    }
+}
@@ -0,0 +1,15 @@
+//! Arranging a story on the page, and its scene graph on screen.
+
+mod graph;
+mod pagination;
+mod revision;
+
+pub use graph::{
+    EdgeRoute, GraphLayout, LAYER_SPACING, NODE_SPACING, NodePosition, SCENE_GAP, layout_graph,
+    update_layout,
+};
+pub use pagination::{
+    LINES_PER_PAGE, LayoutError, Line, LineKind, Page, Pagination, ScenePosition,
+    estimated_eighths, paginate,
+};
+pub use revision::{RevisedScene, RevisionReport, revision_report};
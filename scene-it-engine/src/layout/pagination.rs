@@ -0,0 +1,707 @@
+use serde::Serialize;
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{
+    formats::{OMITTED, parenthetical_text},
+    models::{
+        BranchPolicy, Character, DialogueBlock, Id, NarrativeError, Scene, SceneElement,
+        SceneNumber, SceneVariant, ScriptScene, Storyboard,
+    },
+};
+
+/// Lines of script that fit between the one-inch top and bottom margins of a
+/// US Letter page set in 12pt Courier.
+pub const LINES_PER_PAGE: usize = 54;
+
+/// Scene lengths are measured in eighths of a page.
+const EIGHTHS_PER_PAGE: usize = 8;
+
+/// The fewest lines an action paragraph may leave on either side of a page break.
+const MIN_ACTION_LINES: usize = 2;
+
+/// Errors that can occur while paginating a story.
+#[derive(Debug, Serialize, PartialEq)]
+pub enum LayoutError {
+    /// A dialogue's speaker is not among the storyboard's characters.
+    UnknownCharacter(Id<Character>),
+    /// The path through the story could not be worked out.
+    Narrative(NarrativeError),
+}
+
+impl From<NarrativeError> for LayoutError {
+    fn from(value: NarrativeError) -> Self {
+        LayoutError::Narrative(value)
+    }
+}
+
+/// The kind of a line on a script page, which fixes its indent and width.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub enum LineKind {
+    SceneHeading,
+    Action,
+    /// A character cue, including the `NAME (CONT'D)` cue that resumes split dialogue.
+    Character,
+    Parenthetical,
+    Dialogue,
+    /// The `(MORE)` marker left at the foot of a page when dialogue continues overleaf.
+    More,
+}
+
+impl LineKind {
+    /// Left edge of the line, in inches from the left edge of the page.
+    pub fn indent(self) -> f32 {
+        match self {
+            LineKind::SceneHeading | LineKind::Action => 1.5,
+            LineKind::Character | LineKind::More => 3.7,
+            LineKind::Parenthetical => 3.1,
+            LineKind::Dialogue => 2.5,
+        }
+    }
+
+    /// Maximum characters per line.
+    pub fn width(self) -> usize {
+        match self {
+            LineKind::SceneHeading | LineKind::Action => 60,
+            LineKind::Character | LineKind::More => 38,
+            LineKind::Parenthetical => 25,
+            LineKind::Dialogue => 35,
+        }
+    }
+}
+
+/// One line of text set on a page.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Line {
+    pub kind: LineKind,
+    pub text: String,
+    /// Whether the line sets text changed in the storyboard's current
+    /// revision set, and so carries a revision mark.
+    pub revised: bool,
+    /// The scene number printed in both margins, set on the first line of
+    /// a numbered scene's heading.
+    pub scene_number: Option<SceneNumber>,
+}
+
+/// A single script page. Blank lines are `None`.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Page {
+    /// The 1-based page number.
+    pub number: usize,
+    pub lines: Vec<Option<Line>>,
+}
+
+/// Where a scene falls in the paginated script.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ScenePosition {
+    pub scene: Id<Scene>,
+    pub variant: Id<SceneVariant>,
+    /// The scene's number, if the script is numbered.
+    pub number: Option<SceneNumber>,
+    /// The 1-based page the scene starts on.
+    pub page: usize,
+    /// How far down its first page the scene starts, in eighths (0 to 7).
+    pub eighth: usize,
+    /// The scene's length in eighths of a page. A scene with any content
+    /// counts as at least one eighth.
+    pub length_eighths: usize,
+    /// The 1-based pages that carry the scene's revised lines, in order.
+    pub revised_pages: Vec<usize>,
+}
+
+/// A story laid out onto script pages.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Pagination {
+    pub pages: Vec<Page>,
+    /// One entry per scene, in story order.
+    pub scenes: Vec<ScenePosition>,
+}
+
+impl Pagination {
+    /// Returns the number of script pages.
+    pub fn page_count(&self) -> usize {
+        self.pages.len()
+    }
+
+    /// Returns the total length of the story in eighths of a page.
+    pub fn total_eighths(&self) -> usize {
+        self.scenes.iter().map(|s| s.length_eighths).sum()
+    }
+}
+
+/// A run of lines that is placed as a unit, splitting only where the
+/// screenplay rules allow.
+struct Block {
+    /// Index of the scene the block belongs to, or `None` for the heading
+    /// left by an omitted scene.
+    scene: Option<usize>,
+    kind: BlockKind,
+    lines: Vec<Line>,
+}
+
+enum BlockKind {
+    SceneHeading,
+    Action,
+    /// A speech, with the speaker's name for the `(CONT'D)` cue.
+    Dialogue(String),
+}
+
+/// Paginates the path through the storyboard starting at `root`.
+///
+/// Each scene on the path returned by
+/// [`Narrative::linearize_from`](crate::models::Narrative::linearize_from)
+/// under `policy` is laid out from the variant the path visits, one blank line between elements, and
+/// broken onto pages of [`LINES_PER_PAGE`] lines using screenplay rules:
+///
+/// - A scene heading is never left alone at the foot of a page; it moves
+///   to the next page unless the start of what follows fits beneath it.
+/// - Action splits only where at least two lines stay on each side.
+/// - Dialogue splits with `(MORE)` at the foot of the page and resumes
+///   under a `NAME (CONT'D)` cue. The cue always keeps at least one line of
+///   speech with it, and a parenthetical never ends the page.
+///
+/// If the script is numbered, each scene's number goes on the first line of
+/// its heading, and the number of each removed scene is set where it falls
+/// under an `OMITTED` heading; see [`Narrative::linearize_numbered`](crate::models::Narrative::linearize_numbered).
+///
+/// # Errors
+///
+/// - [`LayoutError::UnknownCharacter`] if a dialogue's speaker is not among
+///   the storyboard's characters.
+/// - [`LayoutError::Narrative`] if the path cannot be linearized.
+pub fn paginate(
+    storyboard: &Storyboard,
+    root: Id<SceneVariant>,
+    policy: &BranchPolicy,
+) -> Result<Pagination, LayoutError> {
+    let mut scenes = Vec::new();
+    let mut blocks = Vec::new();
+    let revision = storyboard.current_revision();
+
+    let script = storyboard.narrative().linearize_numbered(root, policy)?;
+    for entry in script {
+        let (number, scene, variant) = match entry {
+            ScriptScene::Scene {
+                number,
+                scene,
+                variant,
+            } => (number, scene, variant),
+            ScriptScene::Omitted(number) => {
+                let mut block = Block::new(
+                    None,
+                    BlockKind::SceneHeading,
+                    LineKind::SceneHeading,
+                    OMITTED,
+                    false,
+                );
+                block.lines[0].scene_number = Some(number.clone());
+                blocks.push(block);
+                continue;
+            }
+        };
+
+        let index = Some(scenes.len());
+        scenes.push((scene.id(), variant.id(), number.cloned()));
+
+        if let Some(heading) = variant.heading() {
+            let mut block = Block::new(
+                index,
+                BlockKind::SceneHeading,
+                LineKind::SceneHeading,
+                &heading.to_string().to_uppercase(),
+                revision.is_some_and(|r| r.is_heading_revised(variant)),
+            );
+            block.lines[0].scene_number = number.cloned();
+            blocks.push(block);
+        }
+
+        for element in variant.elements() {
+            let revised = revision.is_some_and(|r| r.is_element_revised(variant, element));
+            match element {
+                SceneElement::Action(action) => {
+                    blocks.push(Block::new(
+                        index,
+                        BlockKind::Action,
+                        LineKind::Action,
+                        action.as_str(),
+                        revised,
+                    ));
+                }
+                SceneElement::Dialogue(dialogue) => {
+                    let speaker = storyboard
+                        .character(&dialogue.speaker())
+                        .ok_or(LayoutError::UnknownCharacter(dialogue.speaker()))?;
+                    let name = speaker.name().to_uppercase();
+                    let mut block = Block::new(
+                        index,
+                        BlockKind::Dialogue(name.clone()),
+                        LineKind::Character,
+                        &name,
+                        revised,
+                    );
+
+                    for content in dialogue.content() {
+                        let (kind, text) = match content {
+                            DialogueBlock::Parenthetical(p) => {
+                                (LineKind::Parenthetical, parenthetical_text(p))
+                            }
+                            DialogueBlock::Text(t) => (LineKind::Dialogue, t.as_str().to_string()),
+                        };
+                        block
+                            .lines
+                            .extend(wrap(&text, kind.width()).into_iter().map(|text| Line {
+                                kind,
+                                text,
+                                revised,
+                                scene_number: None,
+                            }));
+                    }
+
+                    blocks.push(block);
+                }
+            }
+        }
+    }
+
+    let mut layout = Layout::default();
+    for index in 0..blocks.len() {
+        layout.place(&blocks, index);
+    }
+
+    Ok(layout.finish(scenes))
+}
+
+/// Estimates the length of a scene variant in eighths of a page, as if it
+/// were set on its own from the top of a page.
+///
+/// This counts the lines [`paginate`] would set, one blank line between
+/// elements, but ignores page breaks and the `(MORE)` and `(CONT'D)` lines
+/// they add. It needs no storyboard, so it suits comparing variants and
+/// story paths. A variant with any content counts as at least one eighth.
+pub fn estimated_eighths(variant: &SceneVariant) -> usize {
+    let mut blocks = Vec::new();
+    if let Some(heading) = variant.heading() {
+        blocks.push(wrap(&heading.to_string(), LineKind::SceneHeading.width()).len());
+    }
+
+    for element in variant.elements() {
+        match element {
+            SceneElement::Action(action) => {
+                blocks.push(wrap(action.as_str(), LineKind::Action.width()).len());
+            }
+            SceneElement::Dialogue(dialogue) => {
+                let speech: usize = dialogue
+                    .content()
+                    .iter()
+                    .map(|content| match content {
+                        DialogueBlock::Parenthetical(p) => {
+                            wrap(&parenthetical_text(p), LineKind::Parenthetical.width()).len()
+                        }
+                        DialogueBlock::Text(t) => {
+                            wrap(t.as_str(), LineKind::Dialogue.width()).len()
+                        }
+                    })
+                    .sum();
+                blocks.push(1 + speech);
+            }
+        }
+    }
+
+    let separators = blocks.len().saturating_sub(1);
+    lines_to_eighths(blocks.iter().sum::<usize>() + separators)
+}
+
+/// Converts a length in lines to eighths of a page, counting any content as
+/// at least one eighth.
+fn lines_to_eighths(length: usize) -> usize {
+    let lines_per_eighth = LINES_PER_PAGE as f32 / EIGHTHS_PER_PAGE as f32;
+    let eighths = (length as f32 / lines_per_eighth).round() as usize;
+    if length > 0 { eighths.max(1) } else { 0 }
+}
+
+impl Block {
+    fn new(
+        scene: Option<usize>,
+        kind: BlockKind,
+        line_kind: LineKind,
+        text: &str,
+        revised: bool,
+    ) -> Self {
+        let lines = wrap(text, line_kind.width())
+            .into_iter()
+            .map(|text| Line {
+                kind: line_kind,
+                text,
+                revised,
+                scene_number: None,
+            })
+            .collect();
+
+        Self { scene, kind, lines }
+    }
+
+    /// Returns how many of the block's lines may be set in `space` lines,
+    /// or `None` if the block must start on the next page.
+    fn first_chunk(&self, space: usize) -> Option<usize> {
+        first_chunk(&self.kind, &self.lines, space)
+    }
+}
+
+/// Returns how many of `lines` may be set in `space` lines, or `None` if
+/// they must start on the next page.
+///
+/// For dialogue the count excludes the `(MORE)` line, which is reserved out
+/// of `space`.
+fn first_chunk(kind: &BlockKind, lines: &[Line], space: usize) -> Option<usize> {
+    let len = lines.len();
+    if len <= space {
+        return Some(len);
+    }
+
+    match kind {
+        BlockKind::SceneHeading => None,
+        BlockKind::Action => {
+            let split = space.min(len.saturating_sub(MIN_ACTION_LINES));
+            (split >= MIN_ACTION_LINES).then_some(split)
+        }
+        BlockKind::Dialogue(_) => {
+            // Leave room for (MORE), keep the cue with at least one line of
+            // speech, and never end the page on a parenthetical.
+            (2..=space.saturating_sub(1).min(len - 1))
+                .rev()
+                .find(|&split| lines[split - 1].kind == LineKind::Dialogue)
+        }
+    }
+}
+
+/// A `(page index, row)` location on the pages being filled.
+type Mark = (usize, usize);
+
+/// Pages being filled, with the start and end of each scene as it is placed.
+#[derive(Default)]
+struct Layout {
+    pages: Vec<Vec<Option<Line>>>,
+    /// Start and end of each scene with content, by scene index.
+    extents: BTreeMap<usize, (Mark, Mark)>,
+    /// Indices of the pages carrying revised lines, by scene index.
+    revised: BTreeMap<usize, BTreeSet<usize>>,
+}
+
+impl Layout {
+    fn current(&mut self) -> &mut Vec<Option<Line>> {
+        if self.pages.is_empty() {
+            self.pages.push(Vec::new());
+        }
+        self.pages.last_mut().expect("a page was just ensured")
+    }
+
+    /// Lines left on the current page once the separating blank line is set.
+    fn space(&mut self) -> usize {
+        let used = self.current().len();
+        let separator = usize::from(used > 0);
+        LINES_PER_PAGE.saturating_sub(used + separator)
+    }
+
+    fn break_page(&mut self) {
+        self.pages.push(Vec::new());
+    }
+
+    /// Places `blocks[index]`, breaking pages as the rules require.
+    fn place(&mut self, blocks: &[Block], index: usize) {
+        let block = &blocks[index];
+        let mut space = self.space();
+
+        if matches!(block.kind, BlockKind::SceneHeading) {
+            let after_heading = space.saturating_sub(block.lines.len() + 1);
+            let next_fits = blocks
+                .get(index + 1)
+                .filter(|next| next.scene == block.scene)
+                .is_none_or(|next| next.first_chunk(after_heading).is_some());
+
+            if block.lines.len() > space || !next_fits {
+                self.break_page();
+                space = self.space();
+            }
+        }
+
+        let mut lines: Vec<Line> = block.lines.clone();
+        loop {
+            let chunk = match first_chunk(&block.kind, &lines, space) {
+                Some(chunk) => chunk,
+                // Nothing fits the rules even on a fresh page, so split where the page runs out.
+                None if self.current().is_empty() => {
+                    let more = usize::from(matches!(block.kind, BlockKind::Dialogue(_)));
+                    lines.len().min(space - more)
+                }
+                None => {
+                    self.break_page();
+                    space = self.space();
+                    continue;
+                }
+            };
+
+            self.set(block.scene, lines.drain(..chunk));
+            if lines.is_empty() {
+                break;
+            }
+
+            if let BlockKind::Dialogue(name) = &block.kind {
+                // The cue carries the mark of the speech it resumes.
+                let revised = lines[0].revised;
+                self.current().push(Some(Line {
+                    kind: LineKind::More,
+                    text: "(MORE)".to_string(),
+                    revised,
+                    scene_number: None,
+                }));
+                lines.insert(
+                    0,
+                    Line {
+                        kind: LineKind::Character,
+                        text: format!("{name} (CONT'D)"),
+                        revised,
+                        scene_number: None,
+                    },
+                );
+            }
+
+            self.break_page();
+            space = self.space();
+        }
+    }
+
+    /// Sets lines on the current page, after a blank line if the page has text.
+    ///
+    /// Lines that belong to no scene are set without being measured.
+    fn set(&mut self, scene: Option<usize>, lines: impl Iterator<Item = Line>) {
+        let page = self.current();
+        if !page.is_empty() {
+            page.push(None);
+        }
+        let start = (self.pages.len() - 1, self.current().len());
+
+        self.current().extend(lines.map(Some));
+        let end = (self.pages.len() - 1, self.current().len());
+        let Some(scene) = scene else {
+            return;
+        };
+
+        let page = self.pages.len() - 1;
+        if self.pages[page][start.1..]
+            .iter()
+            .flatten()
+            .any(|l| l.revised)
+        {
+            self.revised.entry(scene).or_default().insert(page);
+        }
+
+        self.extents
+            .entry(scene)
+            .and_modify(|(_, scene_end)| *scene_end = end)
+            .or_insert((start, end));
+    }
+
+    fn finish(self, scenes: Vec<(Id<Scene>, Id<SceneVariant>, Option<SceneNumber>)>) -> Pagination {
+        let mut previous_end = (0, 0);
+        let positions = scenes
+            .into_iter()
+            .enumerate()
+            .map(|(index, (scene, variant, number))| {
+                // A scene with no content starts, and ends, where the last one ended.
+                let ((start_page, start_row), end) = self
+                    .extents
+                    .get(&index)
+                    .copied()
+                    .unwrap_or((previous_end, previous_end));
+                previous_end = end;
+
+                let length = (end.0 - start_page) * LINES_PER_PAGE + end.1 - start_row;
+                ScenePosition {
+                    scene,
+                    variant,
+                    number,
+                    page: start_page + 1,
+                    eighth: start_row * EIGHTHS_PER_PAGE / LINES_PER_PAGE,
+                    length_eighths: lines_to_eighths(length),
+                    revised_pages: self
+                        .revised
+                        .get(&index)
+                        .map(|pages| pages.iter().map(|page| page + 1).collect())
+                        .unwrap_or_default(),
+                }
+            })
+            .collect();
+
+        let pages = self
+            .pages
+            .into_iter()
+            .enumerate()
+            .map(|(index, lines)| Page {
+                number: index + 1,
+                lines,
+            })
+            .collect();
+
+        Pagination {
+            pages,
+            scenes: positions,
+        }
+    }
+}
+
+/// Word-wraps `text` to lines of at most `width` characters.
+///
+/// Line breaks in `text` are kept, and words longer than a full line are
+/// split across lines.
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+
+    for (index, line) in text.lines().enumerate() {
+        if index > 0 && !current.is_empty() {
+            lines.push(std::mem::take(&mut current));
+        }
+        wrap_line(line, width, &mut lines, &mut current);
+    }
+
+    if !current.is_empty() || lines.is_empty() {
+        lines.push(current);
+    }
+
+    lines
+}
+
+/// Word-wraps one line of text onto `lines`, leaving its last, unfinished
+/// line in `current`.
+fn wrap_line(text: &str, width: usize, lines: &mut Vec<String>, current: &mut String) {
+    for word in text.split_whitespace() {
+        let mut word: Vec<char> = word.chars().collect();
+
+        while word.len() > width {
+            if !current.is_empty() {
+                lines.push(std::mem::take(current));
+            }
+            lines.push(word.drain(..width).collect());
+        }
+
+        let word: String = word.into_iter().collect();
+        let needed =
+            current.chars().count() + usize::from(!current.is_empty()) + word.chars().count();
+        if needed > width {
+            lines.push(std::mem::take(current));
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(&word);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        formats::fountain,
+        layout::{LINES_PER_PAGE, LineKind, Pagination, estimated_eighths, paginate},
+        models::BranchPolicy,
+    };
+
+    /// Paginates a Fountain script.
+    fn paginate_script(script: &str) -> Pagination {
+        let storyboard = fountain::parse(script).unwrap();
+        let root = *storyboard
+            .narrative()
+            .graph()
+            .roots()
+            .iter()
+            .next()
+            .unwrap();
+        paginate(&storyboard, root, &BranchPolicy::default()).unwrap()
+    }
+
+    /// A scene heading followed by `count` one-line action paragraphs.
+    fn filler(count: usize) -> String {
+        let mut script = String::from("INT. HOUSE - DAY\n\n");
+        for _ in 0..count {
+            script.push_str("Beat.\n\n");
+        }
+        script
+    }
+
+    /// Dialogue text that wraps to exactly `lines` lines.
+    fn speech(lines: usize) -> String {
+        vec!["word"; lines * 7].join(" ")
+    }
+
+    #[test]
+    fn test_scene_heading_is_not_left_alone_at_the_foot_of_a_page() {
+        // ARRANGE
+        let script = filler(25) + "EXT. YARD - NIGHT\n\nRain.\n";
+        // ACT
+        let pagination = paginate_script(&script);
+        // ASSERT
+        assert_eq!(pagination.pages[0].lines.len(), 51);
+        let first = pagination.pages[1].lines[0].as_ref().unwrap();
+        assert_eq!(first.kind, LineKind::SceneHeading);
+        assert_eq!(pagination.scenes[1].page, 2);
+        assert_eq!(pagination.scenes[1].eighth, 0);
+    }
+
+    #[test]
+    fn test_split_dialogue_gets_more_and_contd() {
+        // ARRANGE
+        let script = filler(22) + &format!("JADE\n{}\n", speech(20));
+        // ACT
+        let pagination = paginate_script(&script);
+        // ASSERT
+        let first_page = &pagination.pages[0].lines;
+        assert_eq!(first_page.len(), LINES_PER_PAGE);
+        assert_eq!(first_page.last().unwrap().as_ref().unwrap().text, "(MORE)");
+        let resumed = pagination.pages[1].lines[0].as_ref().unwrap();
+        assert_eq!(resumed.kind, LineKind::Character);
+        assert_eq!(resumed.text, "JADE (CONT'D)");
+    }
+
+    #[test]
+    fn test_parenthetical_never_ends_a_page() {
+        // ARRANGE
+        let script = filler(22) + &format!("JADE\n{}\n(beat)\n{}\n", speech(5), speech(10));
+        // ACT
+        let pagination = paginate_script(&script);
+        // ASSERT
+        let first_page = &pagination.pages[0].lines;
+        let before_more = first_page[first_page.len() - 2].as_ref().unwrap();
+        assert_eq!(before_more.kind, LineKind::Dialogue);
+        let resumed = &pagination.pages[1].lines;
+        assert_eq!(resumed[1].as_ref().unwrap().kind, LineKind::Parenthetical);
+    }
+
+    #[test]
+    fn test_scene_positions_are_reported_in_eighths() {
+        // ARRANGE
+        let script = filler(3) + "EXT. YARD - NIGHT\n\nRain.\n";
+        // ACT
+        let pagination = paginate_script(&script);
+        // ASSERT
+        assert_eq!(pagination.page_count(), 1);
+        assert_eq!(pagination.scenes[0].page, 1);
+        assert_eq!(pagination.scenes[0].eighth, 0);
+        assert_eq!(pagination.scenes[0].length_eighths, 1);
+        assert_eq!(pagination.scenes[1].eighth, 1);
+        assert_eq!(pagination.total_eighths(), 2);
+    }
+
+    #[test]
+    fn test_estimated_length_matches_a_scene_set_on_one_page() {
+        // ARRANGE
+        let script = filler(20) + &format!("JADE\n{}\n", speech(3));
+        let storyboard = fountain::parse(&script).unwrap();
+        let scene = storyboard.narrative().scenes().next().unwrap();
+        let variant = &scene.variants()[scene.active_variant()];
+        // ACT
+        let estimate = estimated_eighths(variant);
+        // ASSERT
+        let pagination = paginate_script(&script);
+        assert_eq!(pagination.page_count(), 1);
+        assert_eq!(estimate, pagination.scenes[0].length_eighths);
+    }
+}
@@ -0,0 +1,167 @@
+use serde::Serialize;
+
+use std::collections::BTreeSet;
+
+use crate::{
+    layout::{LayoutError, paginate},
+    models::{BranchPolicy, Id, RevisionColor, Scene, SceneNumber, SceneVariant, Storyboard},
+};
+
+/// A scene changed in the current revision set.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct RevisedScene {
+    pub scene: Id<Scene>,
+    pub variant: Id<SceneVariant>,
+    /// The scene's number, if the script is numbered.
+    pub number: Option<SceneNumber>,
+    /// The 1-based pages to reissue for the scene, in order.
+    pub pages: Vec<usize>,
+}
+
+/// What a revision set changed along one path through the story, and the
+/// pages that must go out on its color.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct RevisionReport {
+    pub color: RevisionColor,
+    /// The changed scenes, in story order.
+    pub scenes: Vec<RevisedScene>,
+    /// Every page to reissue, in order.
+    pub pages: Vec<usize>,
+}
+
+/// Reports what the storyboard's current revision set changed on the path
+/// starting at `root`, paginated as [`paginate`] lays it out.
+///
+/// A scene is listed if anything in the variant the path visits changed
+/// since the set was opened. Its pages are the ones carrying its revision
+/// marks; a scene that only lost text has none, so its first page stands in.
+///
+/// Returns `None` if no revision set has been opened.
+///
+/// # Errors
+///
+/// - [`LayoutError::UnknownCharacter`] if a dialogue's speaker is not among
+///   the storyboard's characters.
+/// - [`LayoutError::Narrative`] if the path cannot be linearized.
+pub fn revision_report(
+    storyboard: &Storyboard,
+    root: Id<SceneVariant>,
+    policy: &BranchPolicy,
+) -> Result<Option<RevisionReport>, LayoutError> {
+    let Some(revision) = storyboard.current_revision() else {
+        return Ok(None);
+    };
+
+    let pagination = paginate(storyboard, root, policy)?;
+    let narrative = storyboard.narrative();
+    let mut pages = BTreeSet::new();
+
+    let scenes: Vec<_> = pagination
+        .scenes
+        .into_iter()
+        .filter(|position| {
+            narrative
+                .scene(&position.scene)
+                .and_then(|scene| scene.variants().get(&position.variant))
+                .is_some_and(|variant| revision.is_variant_revised(variant))
+        })
+        .map(|position| {
+            let scene_pages = match position.revised_pages.is_empty() {
+                true => vec![position.page],
+                false => position.revised_pages,
+            };
+            pages.extend(scene_pages.iter().copied());
+
+            RevisedScene {
+                scene: position.scene,
+                variant: position.variant,
+                number: position.number,
+                pages: scene_pages,
+            }
+        })
+        .collect();
+
+    Ok(Some(RevisionReport {
+        color: revision.color(),
+        scenes,
+        pages: pages.into_iter().collect(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        formats::fountain,
+        layout::{paginate, revision_report},
+        models::{
+            BranchPolicy, Id, RevisionColor, RevisionNote, SceneAction, SceneElement, SceneVariant,
+            Storyboard,
+        },
+    };
+
+    const SCRIPT: &str = "INT. LAB - NIGHT\n\nThe machine hums.\n\nEXT. ROOF - DAWN\n\nWind.\n";
+
+    fn generate_test_components() -> (Storyboard, Id<SceneVariant>) {
+        let storyboard = fountain::parse(SCRIPT).unwrap();
+        let root = *storyboard
+            .narrative()
+            .graph()
+            .roots()
+            .iter()
+            .next()
+            .unwrap();
+        (storyboard, root)
+    }
+
+    #[test]
+    fn test_report_lists_only_changed_scenes_and_their_pages() {
+        // ARRANGE
+        let (mut storyboard, root) = generate_test_components();
+        storyboard
+            .open_revision(
+                RevisionColor::Blue,
+                RevisionNote::new("Blue pages").unwrap(),
+            )
+            .unwrap();
+        let path = storyboard
+            .narrative()
+            .linearize_from(root, &BranchPolicy::default())
+            .unwrap();
+        let (roof, roof_variant) = (path[1].0.id(), path[1].1.id());
+        let added = SceneElement::Action(SceneAction::new("A gull lands.").unwrap());
+        storyboard
+            .narrative_mut()
+            .variant_mut(&roof_variant)
+            .unwrap()
+            .add_element(added)
+            .unwrap();
+        // ACT
+        let report = revision_report(&storyboard, root, &BranchPolicy::default())
+            .unwrap()
+            .unwrap();
+        let pagination = paginate(&storyboard, root, &BranchPolicy::default()).unwrap();
+        // ASSERT
+        assert_eq!(report.color, RevisionColor::Blue);
+        assert_eq!(report.scenes.len(), 1);
+        assert_eq!(report.scenes[0].scene, roof);
+        assert_eq!(report.pages, [1]);
+        let revised: Vec<_> = pagination.pages[0]
+            .lines
+            .iter()
+            .flatten()
+            .filter(|line| line.revised)
+            .map(|line| line.text.as_str())
+            .collect();
+        assert_eq!(revised, ["A gull lands."]);
+    }
+
+    #[test]
+    fn test_no_report_without_a_revision_set() {
+        // ARRANGE
+        let (storyboard, root) = generate_test_components();
+        // ACT
+        let report = revision_report(&storyboard, root, &BranchPolicy::default()).unwrap();
+        // ASSERT
+        assert!(report.is_none());
+    }
+}
@@ -1,2 +1,8 @@
-mod models;
+pub mod diff;
+pub mod formats;
+pub mod layout;
+pub mod models;
+pub mod persistence;
 mod utils;
+
+pub use utils::InputError;
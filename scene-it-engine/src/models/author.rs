@@ -25,6 +25,9 @@ impl AuthorName {
 pub struct Author {
     id: Id<Self>,
     name: AuthorName,
+    /// Whether the author may override locks.
+    #[serde(default)]
+    admin: bool,
     metadata: Metadata,
 }
 
@@ -33,17 +36,28 @@ impl Author {
         Self {
             id: Id::new(),
             name,
+            admin: false,
             metadata: Metadata::new(),
         }
     }
 
     pub fn id(&self) -> Id<Self> {
-        self.id.clone()
+        self.id
     }
 
     pub fn name(&self) -> &str {
         self.name.as_str()
     }
+
+    /// Returns `true` if the author may override locks.
+    pub fn is_admin(&self) -> bool {
+        self.admin
+    }
+
+    /// Grants or withdraws the right to override locks.
+    pub fn set_admin(&mut self, admin: bool) {
+        self.admin = admin;
+    }
 }
 
 #[cfg(test)]
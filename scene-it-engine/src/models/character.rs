@@ -1,5 +1,12 @@
 use crate::{
-    models::{Id, metadata::Metadata},
+    models::{
+        HasMetadata, Id,
+        metadata::Metadata,
+        narrative::NarrativeError,
+        scene::SceneVariant,
+        scene_element::{Dialogue, SceneElement},
+        summary::Summary,
+    },
     utils::{InputError, validate_input},
 };
 use serde::{Deserialize, Serialize};
@@ -10,12 +17,58 @@ impl CharacterName {
     pub fn new(input: &str) -> Result<Self, InputError> {
         Ok(Self(validate_input(input, Some(100))?))
     }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Errors that can occur while managing the characters of a
+/// [`Storyboard`](crate::models::Storyboard).
+#[derive(Debug, Serialize, PartialEq)]
+pub enum CharacterError {
+    /// The referenced character is not in the storyboard.
+    UnknownCharacter(Id<Character>),
+    /// The character still speaks these dialogues, which would be left
+    /// without a speaker.
+    HasDialogue(Vec<Id<Dialogue>>),
+    /// The storyboard is locked, or a dialogue to be given to another
+    /// speaker is.
+    Narrative(NarrativeError),
 }
 
+impl From<NarrativeError> for CharacterError {
+    fn from(value: NarrativeError) -> Self {
+        CharacterError::Narrative(value)
+    }
+}
+
+/// A character in the story and their profile.
+///
+/// Besides their name, a character may go by aliases such as nicknames;
+/// a cue under any of them is the character's. The profile fields are free
+/// text and start out empty.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Character {
     id: Id<Self>,
     name: CharacterName,
+    /// Other names the character goes by, in the order they were added.
+    #[serde(default)]
+    aliases: Vec<CharacterName>,
+    #[serde(default)]
+    age: Option<u32>,
+    /// How the character looks and comes across.
+    #[serde(default)]
+    description: Summary,
+    /// What happened to the character before the story begins.
+    #[serde(default)]
+    backstory: Summary,
+    /// What the character wants.
+    #[serde(default)]
+    motivation: Summary,
+    /// How the character changes over the story.
+    #[serde(default)]
+    arc: Summary,
     metadata: Metadata,
 }
 
@@ -24,14 +77,217 @@ impl Character {
         Self {
             id: Id::new(),
             name,
+            aliases: Vec::new(),
+            age: None,
+            description: Summary::default(),
+            backstory: Summary::default(),
+            motivation: Summary::default(),
+            arc: Summary::default(),
             metadata: Metadata::new(),
         }
     }
 
     pub fn id(&self) -> Id<Self> {
-        self.id.clone()
+        self.id
+    }
+
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    pub fn set_name(&mut self, name: CharacterName) {
+        self.name = name;
+        self.touch();
+    }
+
+    pub fn aliases(&self) -> &[CharacterName] {
+        &self.aliases
+    }
+
+    /// Adds an alias, unless the character already answers to it.
+    ///
+    /// Returns `true` if the alias was added.
+    pub fn add_alias(&mut self, alias: CharacterName) -> bool {
+        if self.answers_to(alias.as_str()) {
+            return false;
+        }
+
+        self.aliases.push(alias);
+        self.touch();
+        true
+    }
+
+    /// Removes an alias, matched case-insensitively.
+    ///
+    /// Returns `true` if the character had the alias.
+    pub fn remove_alias(&mut self, alias: &str) -> bool {
+        let count = self.aliases.len();
+        self.aliases.retain(|a| !same_name(a.as_str(), alias));
+        let removed = self.aliases.len() < count;
+        if removed {
+            self.touch();
+        }
+        removed
+    }
+
+    /// Returns `true` if `name` is the character's name or one of their
+    /// aliases, ignoring case.
+    pub fn answers_to(&self, name: &str) -> bool {
+        self.names().any(|n| same_name(n, name))
+    }
+
+    pub fn age(&self) -> Option<u32> {
+        self.age
+    }
+
+    pub fn set_age(&mut self, age: Option<u32>) {
+        self.age = age;
+        self.touch();
+    }
+
+    pub fn description(&self) -> &Summary {
+        &self.description
+    }
+
+    pub fn set_description(&mut self, description: Summary) {
+        self.description = description;
+        self.touch();
+    }
+
+    pub fn backstory(&self) -> &Summary {
+        &self.backstory
+    }
+
+    pub fn set_backstory(&mut self, backstory: Summary) {
+        self.backstory = backstory;
+        self.touch();
+    }
+
+    pub fn motivation(&self) -> &Summary {
+        &self.motivation
+    }
+
+    pub fn set_motivation(&mut self, motivation: Summary) {
+        self.motivation = motivation;
+        self.touch();
+    }
+
+    pub fn arc(&self) -> &Summary {
+        &self.arc
+    }
+
+    pub fn set_arc(&mut self, arc: Summary) {
+        self.arc = arc;
+        self.touch();
+    }
+
+    /// Returns `true` if the character speaks in `variant`, or is named in
+    /// capitals in its action, as a character is when introduced.
+    pub fn appears_in(&self, variant: &SceneVariant) -> bool {
+        variant.elements().iter().any(|element| match element {
+            SceneElement::Dialogue(dialogue) => dialogue.speaker() == self.id,
+            SceneElement::Action(action) => self.names().any(|name| {
+                let name = name.to_uppercase();
+                action
+                    .as_str()
+                    .match_indices(&name)
+                    .any(|(start, _)| is_whole_word(action.as_str(), start, name.len()))
+            }),
+        })
+    }
+
+    /// Returns the character's name followed by their aliases.
+    fn names(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(&self.name)
+            .chain(&self.aliases)
+            .map(CharacterName::as_str)
     }
 }
 
+impl HasMetadata for Character {
+    fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+    fn metadata_mut(&mut self) -> &mut Metadata {
+        &mut self.metadata
+    }
+}
+
+/// Returns `true` if two names are the same, ignoring case.
+fn same_name(a: &str, b: &str) -> bool {
+    a.to_uppercase() == b.to_uppercase()
+}
+
+/// Returns `true` if the `len` bytes of `text` at `start` are not part of a
+/// longer word.
+fn is_whole_word(text: &str, start: usize, len: usize) -> bool {
+    let before = text[..start].chars().next_back();
+    let after = text[start + len..].chars().next();
+    !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+}
+
 #[cfg(test)]
-mod tests {}
+mod tests {
+    use crate::models::{
+        Character, CharacterName, HasMetadata, SceneAction, SceneElement, SceneVariant, Summary,
+    };
+
+    fn generate_test_components() -> Character {
+        let mut character = Character::new(CharacterName::new("Katherine").unwrap());
+        character.add_alias(CharacterName::new("Kit").unwrap());
+        character
+    }
+
+    #[test]
+    fn test_characters_answer_to_their_aliases_in_any_case() {
+        // ARRANGE
+        let mut character = generate_test_components();
+        // ACT
+        let duplicate = character.add_alias(CharacterName::new("KIT").unwrap());
+        // ASSERT
+        assert!(!duplicate);
+        assert!(character.answers_to("KATHERINE"));
+        assert!(character.answers_to("kit"));
+        assert!(character.remove_alias("Kit"));
+        assert!(!character.answers_to("Kit"));
+    }
+
+    #[test]
+    fn test_editing_a_profile_updates_its_metadata() {
+        // ARRANGE
+        let mut character = generate_test_components();
+        let version = character.metadata().version;
+        // ACT
+        let duplicate = character.add_alias(CharacterName::new("kit").unwrap());
+        let after_duplicate = character.metadata().version;
+        character.add_alias(CharacterName::new("Kat").unwrap());
+        character.set_age(Some(34));
+        character.set_motivation(Summary::new("Find her brother").unwrap());
+        // ASSERT
+        assert!(!duplicate);
+        assert_eq!(after_duplicate, version);
+        assert_eq!(character.metadata().version, version + 3);
+    }
+
+    #[test]
+    fn test_characters_appear_where_named_in_capitals() {
+        // ARRANGE
+        let character = generate_test_components();
+        let mut introduced = SceneVariant::new();
+        let mut passing = SceneVariant::new();
+        // ACT
+        introduced
+            .add_element(SceneElement::Action(
+                SceneAction::new("KIT, 30s, kicks the door in.").unwrap(),
+            ))
+            .unwrap();
+        passing
+            .add_element(SceneElement::Action(
+                SceneAction::new("A KITTEN sleeps. Kit is not here.").unwrap(),
+            ))
+            .unwrap();
+        // ASSERT
+        assert!(character.appears_in(&introduced));
+        assert!(!character.appears_in(&passing));
+    }
+}
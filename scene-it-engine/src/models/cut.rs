@@ -0,0 +1,267 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    models::{
+        HasMetadata, Id,
+        metadata::Metadata,
+        narrative::{BranchPolicy, NarrativeError},
+        scene::SceneVariant,
+    },
+    utils::{InputError, validate_input},
+};
+
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct CutName(String);
+
+impl CutName {
+    pub fn new(input: &str) -> Result<Self, InputError> {
+        Ok(Self(validate_input(input, Some(100))?))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Errors that can occur while working with the cuts of a
+/// [`Storyboard`](crate::models::Storyboard).
+#[derive(Debug, Serialize, PartialEq)]
+pub enum CutError {
+    /// The referenced cut does not exist in the storyboard.
+    UnknownCut(Id<Cut>),
+    /// A variant the cut names is not in the narrative, the cut's path could
+    /// not be worked out, or the storyboard is locked.
+    Narrative(NarrativeError),
+}
+
+impl From<NarrativeError> for CutError {
+    fn from(value: NarrativeError) -> Self {
+        CutError::Narrative(value)
+    }
+}
+
+/// A named, saved path through the story, such as a festival cut or a
+/// director's cut.
+///
+/// A cut starts at a root variant and records the variant it takes at each
+/// branch. Branches it has no choice for are resolved as
+/// [`BranchPolicy::Choices`] resolves them, by preferring the active variant.
+///
+/// Cuts are created and edited through the
+/// [`Storyboard`](crate::models::Storyboard), which checks the variants they
+/// name against the narrative.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Cut {
+    id: Id<Self>,
+    name: CutName,
+    /// The variant the cut starts from.
+    root: Id<SceneVariant>,
+    /// The successor chosen at each branch, keyed by the branching variant.
+    choices: HashMap<Id<SceneVariant>, Id<SceneVariant>>,
+    metadata: Metadata,
+}
+
+impl Cut {
+    pub(crate) fn new(name: CutName, root: Id<SceneVariant>) -> Self {
+        Self {
+            id: Id::new(),
+            name,
+            root,
+            choices: HashMap::new(),
+            metadata: Metadata::new(),
+        }
+    }
+
+    pub fn id(&self) -> Id<Self> {
+        self.id
+    }
+
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    pub fn root(&self) -> Id<SceneVariant> {
+        self.root
+    }
+
+    /// Returns the successor chosen at each branch, keyed by the branching variant.
+    pub fn choices(&self) -> &HashMap<Id<SceneVariant>, Id<SceneVariant>> {
+        &self.choices
+    }
+
+    /// Returns the policy that walks the graph along this cut.
+    pub fn policy(&self) -> BranchPolicy {
+        BranchPolicy::Choices(self.choices.clone())
+    }
+
+    /// Returns `true` if the cut names `variant_id` as its root or in a choice.
+    pub fn references(&self, variant_id: Id<SceneVariant>) -> bool {
+        self.root == variant_id
+            || self
+                .choices
+                .iter()
+                .any(|(at, choice)| *at == variant_id || *choice == variant_id)
+    }
+
+    /// Returns a copy of the cut under a new name, with a fresh ID and metadata.
+    pub(crate) fn duplicate(&self, name: CutName) -> Self {
+        Self {
+            choices: self.choices.clone(),
+            ..Self::new(name, self.root)
+        }
+    }
+
+    pub(crate) fn rename(&mut self, name: CutName) {
+        self.name = name;
+        self.touch();
+    }
+
+    pub(crate) fn set_choice(&mut self, at: Id<SceneVariant>, choice: Id<SceneVariant>) {
+        self.choices.insert(at, choice);
+        self.touch();
+    }
+
+    pub(crate) fn clear_choice(&mut self, at: Id<SceneVariant>) -> bool {
+        let cleared = self.choices.remove(&at).is_some();
+        if cleared {
+            self.touch();
+        }
+        cleared
+    }
+}
+
+impl HasMetadata for Cut {
+    fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+    fn metadata_mut(&mut self) -> &mut Metadata {
+        &mut self.metadata
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::{
+        Cut, CutError, CutName, HasMetadata, Id, NarrativeError, Scene, SceneGraphError,
+        SceneVariant, Storyboard,
+    };
+
+    /// A storyboard whose root branches to two scenes that rejoin at a third.
+    fn generate_test_components() -> (Storyboard, Vec<Id<SceneVariant>>) {
+        let mut storyboard = Storyboard::default();
+        let narrative = storyboard.narrative_mut();
+        let mut variants = Vec::new();
+        for _ in 0..4 {
+            let scene = Scene::new();
+            variants.push(*scene.active_variant());
+            narrative.add_scene(scene).unwrap();
+        }
+        narrative.set_variant_as_root(variants[0]).unwrap();
+        for (src, dest) in [(0, 1), (0, 2), (1, 3), (2, 3)] {
+            narrative
+                .link_variants(variants[src], variants[dest])
+                .unwrap();
+        }
+
+        (storyboard, variants)
+    }
+
+    fn name(input: &str) -> CutName {
+        CutName::new(input).unwrap()
+    }
+
+    fn path_ids(storyboard: &Storyboard, cut_id: Id<Cut>) -> Vec<Id<SceneVariant>> {
+        storyboard
+            .linearize_cut(cut_id)
+            .unwrap()
+            .into_iter()
+            .map(|(_, variant)| variant.id())
+            .collect()
+    }
+
+    #[test]
+    fn test_linearizing_a_cut_follows_its_choices() {
+        // ARRANGE
+        let (mut storyboard, v) = generate_test_components();
+        let festival = storyboard.create_cut(name("Festival"), v[0]).unwrap();
+        let studio = storyboard.create_cut(name("Studio"), v[0]).unwrap();
+        storyboard.choose_in_cut(festival, v[0], v[1]).unwrap();
+        storyboard.choose_in_cut(studio, v[0], v[2]).unwrap();
+        // ACT & ASSERT
+        assert_eq!(path_ids(&storyboard, festival), vec![v[0], v[1], v[3]]);
+        assert_eq!(path_ids(&storyboard, studio), vec![v[0], v[2], v[3]]);
+    }
+
+    #[test]
+    fn test_renaming_duplicating_and_deleting_cuts() {
+        // ARRANGE
+        let (mut storyboard, v) = generate_test_components();
+        let cut_id = storyboard.create_cut(name("Festival"), v[0]).unwrap();
+        storyboard.choose_in_cut(cut_id, v[0], v[2]).unwrap();
+        let version = storyboard.cut(&cut_id).unwrap().metadata().version;
+        // ACT
+        storyboard.rename_cut(cut_id, name("Director's")).unwrap();
+        let copy_id = storyboard.duplicate_cut(cut_id, name("Studio")).unwrap();
+        let deleted = storyboard.delete_cut(cut_id).unwrap();
+        // ASSERT
+        assert_eq!(deleted.name(), "Director's");
+        assert!(deleted.metadata().version > version);
+        let copy = storyboard.cut(&copy_id).unwrap();
+        assert_eq!(copy.name(), "Studio");
+        assert_eq!(copy.choices(), deleted.choices());
+        assert_eq!(storyboard.cuts().len(), 1);
+        assert_eq!(
+            storyboard.delete_cut(cut_id),
+            Err(CutError::UnknownCut(cut_id))
+        );
+    }
+
+    #[test]
+    fn test_cuts_reject_unknown_roots_and_invalid_choices() {
+        // ARRANGE
+        let (mut storyboard, v) = generate_test_components();
+        let unknown = Id::new();
+        let cut_id = storyboard.create_cut(name("Festival"), v[0]).unwrap();
+        // ACT
+        let created = storyboard.create_cut(name("Studio"), unknown);
+        let chosen = storyboard.choose_in_cut(cut_id, v[0], v[3]);
+        // ASSERT
+        assert_eq!(
+            created,
+            Err(CutError::Narrative(NarrativeError::Graph(
+                SceneGraphError::UnknownVariant(unknown)
+            )))
+        );
+        assert_eq!(
+            chosen,
+            Err(CutError::Narrative(NarrativeError::InvalidChoice {
+                variant: v[0],
+                choice: v[3]
+            }))
+        );
+    }
+
+    #[test]
+    fn test_removing_a_scene_on_a_cuts_path_breaks_it() {
+        // ARRANGE
+        let (mut storyboard, v) = generate_test_components();
+        let festival = storyboard.create_cut(name("Festival"), v[0]).unwrap();
+        let studio = storyboard.create_cut(name("Studio"), v[0]).unwrap();
+        storyboard.choose_in_cut(festival, v[0], v[1]).unwrap();
+        storyboard.choose_in_cut(studio, v[0], v[2]).unwrap();
+        let scene_id = storyboard
+            .narrative()
+            .scene_for_variant(&v[2])
+            .unwrap()
+            .id();
+        // ACT
+        let preview = storyboard.cuts_broken_by_removing(scene_id).unwrap();
+        storyboard.narrative_mut().remove_scene(scene_id).unwrap();
+        // ASSERT
+        assert_eq!(preview, vec![studio]);
+        assert_eq!(storyboard.broken_cuts(), vec![studio]);
+        assert_eq!(path_ids(&storyboard, festival), vec![v[0], v[1], v[3]]);
+    }
+}
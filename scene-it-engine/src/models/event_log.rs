@@ -0,0 +1,427 @@
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+use crate::models::{
+    Id,
+    author::Author,
+    storyboard::{ChangeError, Storyboard, StoryboardChange},
+};
+
+/// A change to a storyboard, stamped with when it was made and by whom.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Event {
+    #[serde(with = "time::serde::iso8601")]
+    pub timestamp: OffsetDateTime,
+    pub author: Id<Author>,
+    pub change: StoryboardChange,
+}
+
+/// An error raised while replaying an [`EventLog`].
+#[derive(Debug, Serialize, PartialEq)]
+pub struct ReplayError {
+    /// The index of the event that could not be applied.
+    pub position: usize,
+    pub kind: ChangeError,
+}
+
+/// The state of the storyboard after the first `position` events.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Snapshot {
+    position: usize,
+    storyboard: Storyboard,
+}
+
+/// An append-only record of every change made to a storyboard.
+///
+/// Callers make changes through the storyboard as usual and record each one:
+/// a [`StoryboardChange`] for storyboard-level edits, and the
+/// [`NarrativeUpdate`](crate::models::NarrativeUpdate)s returned by narrative
+/// edits. Replaying the log applies the changes in order to an empty
+/// [`Storyboard::default`], or to the storyboard the log was started from
+/// with [`EventLog::starting_from`], rebuilding the story.
+///
+/// # Snapshots
+///
+/// [`EventLog::snapshot`] stores the replayed storyboard at the current end
+/// of the log. Later replays start from the most recent snapshot at or
+/// before the requested position instead of from an empty storyboard.
+/// Snapshots never replace events; the full history stays available.
+///
+/// # Metadata
+///
+/// Replay rebuilds structure and content. Scene metadata is touched as
+/// replayed updates are applied, so timestamps reflect the replay rather
+/// than the original edits, which the events themselves record.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct EventLog {
+    events: Vec<Event>,
+    /// Ordered by position.
+    snapshots: Vec<Snapshot>,
+}
+
+impl EventLog {
+    /// Creates an empty log.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an empty log whose replays start from `storyboard`, such as
+    /// one made with [`Storyboard::with_admin`], instead of from an empty one.
+    pub fn starting_from(storyboard: Storyboard) -> Self {
+        Self {
+            events: Vec::new(),
+            snapshots: vec![Snapshot {
+                position: 0,
+                storyboard,
+            }],
+        }
+    }
+
+    /// Returns every recorded event, oldest first.
+    pub fn events(&self) -> &[Event] {
+        &self.events
+    }
+
+    /// Returns the events recorded for the given author, oldest first.
+    pub fn events_by(&self, author: Id<Author>) -> impl Iterator<Item = &Event> {
+        self.events.iter().filter(move |e| e.author == author)
+    }
+
+    /// Returns the number of recorded events.
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    /// Returns `true` if no events have been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Appends a change made by `author`, stamped with the current time.
+    pub fn record(&mut self, author: Id<Author>, change: impl Into<StoryboardChange>) {
+        self.events.push(Event {
+            timestamp: OffsetDateTime::now_utc(),
+            author,
+            change: change.into(),
+        });
+    }
+
+    /// Appends several changes made by `author`, such as the updates
+    /// returned by a single narrative edit.
+    pub fn record_all<C: Into<StoryboardChange>>(
+        &mut self,
+        author: Id<Author>,
+        changes: impl IntoIterator<Item = C>,
+    ) {
+        for change in changes {
+            self.record(author, change);
+        }
+    }
+
+    /// Rebuilds the storyboard from the whole log.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ReplayError`] naming the first event that could not be
+    /// applied.
+    pub fn replay(&self) -> Result<Storyboard, ReplayError> {
+        self.replay_to(self.events.len())
+    }
+
+    /// Rebuilds the storyboard as it stood after the first `position`
+    /// events. Positions past the end of the log replay the whole log.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ReplayError`] naming the first event that could not be
+    /// applied.
+    pub fn replay_to(&self, position: usize) -> Result<Storyboard, ReplayError> {
+        let position = position.min(self.events.len());
+        let (start, mut storyboard) = self
+            .snapshots
+            .iter()
+            .rev()
+            .find(|s| s.position <= position)
+            .map(|s| (s.position, s.storyboard.clone()))
+            .unwrap_or_default();
+
+        for (index, event) in self.events[start..position].iter().enumerate() {
+            storyboard
+                .apply_change(&event.change)
+                .map_err(|kind| ReplayError {
+                    position: start + index,
+                    kind,
+                })?;
+        }
+
+        Ok(storyboard)
+    }
+
+    /// Stores a snapshot of the storyboard at the current end of the log.
+    ///
+    /// Does nothing if the latest snapshot is already at the end.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ReplayError`] if the log cannot be replayed, in which case
+    /// no snapshot is stored.
+    pub fn snapshot(&mut self) -> Result<(), ReplayError> {
+        let position = self.events.len();
+        if self
+            .snapshots
+            .last()
+            .is_some_and(|s| s.position == position)
+        {
+            return Ok(());
+        }
+
+        let storyboard = self.replay_to(position)?;
+        self.snapshots.push(Snapshot {
+            position,
+            storyboard,
+        });
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::{
+        Author, AuthorName, ChangeError, Character, CharacterError, CharacterName, Dialogue,
+        EventLog, Id, NarrativeError, NarrativeUpdate, Relationship, RelationshipError,
+        RelationshipKind, ReplayError, Scene, SceneElement, SceneGraphError, SceneGraphUpdate,
+        SceneVariant, Storyboard, StoryboardChange, Title,
+    };
+
+    /// Builds a storyboard through its public API, recording every change.
+    fn generate_test_components() -> (Storyboard, EventLog, Vec<Id<SceneVariant>>) {
+        let mut storyboard = Storyboard::default();
+        let mut log = EventLog::new();
+        let author = Author::new(AuthorName::new("Donte").unwrap());
+        let author_id = author.id();
+
+        storyboard.add_author(author.clone()).unwrap();
+        log.record(author_id, StoryboardChange::AuthorAdded(author));
+
+        let title = Title::new("Encryption").unwrap();
+        storyboard.update_title(title.clone()).unwrap();
+        log.record(author_id, StoryboardChange::TitleUpdated(title));
+
+        let mut variants = Vec::new();
+        for _ in 0..3 {
+            let scene = Scene::new();
+            variants.push(*scene.active_variant());
+            log.record_all(
+                author_id,
+                storyboard.narrative_mut().add_scene(scene).unwrap(),
+            );
+        }
+        let narrative = storyboard.narrative_mut();
+        log.record(
+            author_id,
+            narrative.set_variant_as_root(variants[0]).unwrap(),
+        );
+        log.record(
+            author_id,
+            narrative.link_variants(variants[0], variants[1]).unwrap(),
+        );
+        log.record(
+            author_id,
+            narrative.link_variants(variants[1], variants[2]).unwrap(),
+        );
+
+        (storyboard, log, variants)
+    }
+
+    fn assert_same_story(left: &Storyboard, right: &Storyboard) {
+        assert_eq!(left.title(), right.title());
+        assert_eq!(left.authors().len(), right.authors().len());
+        assert_eq!(left.narrative().graph(), right.narrative().graph());
+        let mut left_scenes: Vec<_> = left.narrative().scenes().map(|s| s.id()).collect();
+        let mut right_scenes: Vec<_> = right.narrative().scenes().map(|s| s.id()).collect();
+        left_scenes.sort_by_key(|id| id.uuid());
+        right_scenes.sort_by_key(|id| id.uuid());
+        assert_eq!(left_scenes, right_scenes);
+    }
+
+    #[test]
+    fn test_replay_rebuilds_the_storyboard() {
+        // ARRANGE
+        let (storyboard, log, _) = generate_test_components();
+        // ACT
+        let replayed = log.replay().unwrap();
+        // ASSERT
+        assert_same_story(&replayed, &storyboard);
+    }
+
+    #[test]
+    fn test_replay_to_a_position_shows_the_story_at_that_point() {
+        // ARRANGE
+        let (_, log, _) = generate_test_components();
+        // ACT
+        let before_title = log.replay_to(1).unwrap();
+        let after_title = log.replay_to(2).unwrap();
+        // ASSERT
+        assert!(before_title.title().is_none());
+        assert_eq!(after_title.title().as_ref().unwrap().as_str(), "Encryption");
+        assert_eq!(after_title.narrative().scenes().count(), 0);
+    }
+
+    #[test]
+    fn test_replay_resumes_from_the_latest_snapshot() {
+        // ARRANGE
+        let (mut storyboard, mut log, variants) = generate_test_components();
+        let author = log.events()[0].author;
+        log.snapshot().unwrap();
+        let update = storyboard
+            .narrative_mut()
+            .link_variants(variants[0], variants[2])
+            .unwrap();
+        log.record(author, update);
+        // ACT
+        let replayed = log.replay().unwrap();
+        let earlier = log.replay_to(2).unwrap();
+        // ASSERT
+        assert_same_story(&replayed, &storyboard);
+        assert_eq!(earlier.narrative().scenes().count(), 0);
+    }
+
+    #[test]
+    fn test_log_round_trips_through_json() {
+        // ARRANGE
+        let (storyboard, mut log, _) = generate_test_components();
+        log.snapshot().unwrap();
+        // ACT
+        let json = serde_json::to_string(&log).unwrap();
+        let restored: EventLog = serde_json::from_str(&json).unwrap();
+        // ASSERT
+        assert_eq!(restored.events(), log.events());
+        assert_same_story(&restored.replay().unwrap(), &storyboard);
+    }
+
+    #[test]
+    fn test_replaying_an_invalid_event_reports_its_position() {
+        // ARRANGE
+        let (_, mut log, variants) = generate_test_components();
+        let author = log.events()[0].author;
+        let unknown = Id::new();
+        log.record(
+            author,
+            NarrativeUpdate::Graph(SceneGraphUpdate::EdgeAdded {
+                src: variants[0],
+                dest: unknown,
+            }),
+        );
+        // ACT
+        let response = log.replay();
+        // ASSERT
+        assert_eq!(
+            response.err(),
+            Some(ReplayError {
+                position: log.len() - 1,
+                kind: ChangeError::Narrative(NarrativeError::Graph(
+                    SceneGraphError::UnknownVariant(unknown)
+                )),
+            })
+        );
+    }
+
+    #[test]
+    fn test_replaying_the_removal_of_a_speaking_character_fails() {
+        // ARRANGE
+        let (mut storyboard, mut log, variants) = generate_test_components();
+        let author = log.events()[0].author;
+        let kit = Character::new(CharacterName::new("Kit").unwrap());
+        let kit_id = kit.id();
+        storyboard.add_character(kit.clone()).unwrap();
+        log.record(author, StoryboardChange::CharacterAdded(kit));
+        let scene_id = storyboard
+            .narrative()
+            .scene_for_variant(&variants[0])
+            .unwrap()
+            .id();
+        let dialogue = Dialogue::new(scene_id, kit_id);
+        let dialogue_id = dialogue.id();
+        let update = storyboard
+            .narrative_mut()
+            .edit_variant(variants[0], |v| {
+                v.add_element(SceneElement::Dialogue(dialogue))
+            })
+            .unwrap();
+        log.record(author, update);
+        log.record(
+            author,
+            StoryboardChange::CharacterRemoved {
+                character: kit_id,
+                lines_to: None,
+            },
+        );
+        // ACT
+        let response = log.replay();
+        // ASSERT
+        assert_eq!(
+            response.err(),
+            Some(ReplayError {
+                position: log.len() - 1,
+                kind: ChangeError::Character(CharacterError::HasDialogue(vec![dialogue_id])),
+            })
+        );
+    }
+
+    #[test]
+    fn test_replaying_a_relationship_with_an_unknown_character_fails() {
+        // ARRANGE
+        let (_, mut log, _) = generate_test_components();
+        let author = log.events()[0].author;
+        let kit = Character::new(CharacterName::new("Kit").unwrap());
+        let kit_id = kit.id();
+        let stranger = Id::new();
+        log.record(author, StoryboardChange::CharacterAdded(kit));
+        log.record(
+            author,
+            StoryboardChange::RelationshipAdded(Relationship::new(
+                kit_id,
+                stranger,
+                RelationshipKind::Rival,
+            )),
+        );
+        // ACT
+        let response = log.replay();
+        // ASSERT
+        assert_eq!(
+            response.err(),
+            Some(ReplayError {
+                position: log.len() - 1,
+                kind: ChangeError::Relationship(RelationshipError::UnknownCharacter(stranger)),
+            })
+        );
+    }
+
+    #[test]
+    fn test_replay_starts_from_the_storyboard_the_log_started_from() {
+        // ARRANGE
+        let admin = Author::new(AuthorName::new("Showrunner").unwrap());
+        let admin_id = admin.id();
+        let mut storyboard = Storyboard::with_admin(admin);
+        let mut log = EventLog::starting_from(storyboard.clone());
+        let writer = Author::new(AuthorName::new("Staff Writer").unwrap());
+        let writer_id = writer.id();
+        storyboard.add_author(writer.clone()).unwrap();
+        log.record(admin_id, StoryboardChange::AuthorAdded(writer));
+        storyboard.set_admin(admin_id, writer_id, true).unwrap();
+        log.record(
+            admin_id,
+            StoryboardChange::AdminSet {
+                by: admin_id,
+                author: writer_id,
+                admin: true,
+            },
+        );
+        // ACT
+        let replayed = log.replay().unwrap();
+        // ASSERT
+        assert_eq!(replayed.authors().len(), 2);
+        assert!(replayed.authors().iter().all(|a| a.is_admin()));
+    }
+}
@@ -0,0 +1,485 @@
+use serde::Serialize;
+
+use crate::models::narrative::{Narrative, NarrativeError, NarrativeUpdate};
+
+/// A group of updates that is undone and redone as a single step.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Transaction {
+    /// A description of the edit for display, e.g. "Move scene".
+    label: Option<String>,
+    /// The updates in the order they were applied.
+    updates: Vec<NarrativeUpdate>,
+}
+
+impl Transaction {
+    fn new(label: Option<String>) -> Self {
+        Self {
+            label,
+            updates: Vec::new(),
+        }
+    }
+
+    /// Returns the transaction's label, if it was given one.
+    pub fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    /// Returns the updates in the order they were applied.
+    pub fn updates(&self) -> &[NarrativeUpdate] {
+        &self.updates
+    }
+
+    /// Returns the updates that reverse this transaction, in the order to apply them.
+    pub fn inverse(&self) -> Vec<NarrativeUpdate> {
+        self.updates
+            .iter()
+            .rev()
+            .map(NarrativeUpdate::inverse)
+            .collect()
+    }
+}
+
+/// Undo and redo for edits made to a [`Narrative`].
+///
+/// The history does not make edits itself. Callers make them through the
+/// narrative as usual and [`record`](History::record) the updates each one
+/// returns. Undoing applies the inverse updates; redoing applies the
+/// originals again.
+///
+/// # Transactions
+///
+/// Each recorded call is its own undo step unless it falls between
+/// [`begin`](History::begin) and [`commit`](History::commit), in which case
+/// everything recorded in between is undone together. Groups may nest; only
+/// the outermost commit closes the step.
+///
+/// Recording a new edit clears the redo stack, as in any editor.
+///
+/// # Metadata
+///
+/// Undo and redo restore structure and scene content. Like any other edit
+/// they touch the metadata of the scenes involved rather than rewinding it,
+/// so timestamps keep recording when a scene last changed.
+#[derive(Debug, Default)]
+pub struct History {
+    undo: Vec<Transaction>,
+    redo: Vec<Transaction>,
+    /// The transaction being grouped, and how deeply `begin` has been nested.
+    open: Option<(Transaction, usize)>,
+}
+
+impl History {
+    /// Creates an empty history.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the updates returned by an edit.
+    ///
+    /// Empty edits are not recorded, since there is nothing to undo.
+    pub fn record(&mut self, updates: impl IntoIterator<Item = NarrativeUpdate>) {
+        let updates: Vec<_> = updates.into_iter().collect();
+        if updates.is_empty() {
+            return;
+        }
+
+        self.redo.clear();
+        match &mut self.open {
+            Some((transaction, _)) => transaction.updates.extend(updates),
+            None => self.undo.push(Transaction {
+                label: None,
+                updates,
+            }),
+        }
+    }
+
+    /// Starts grouping recorded edits into one undo step.
+    ///
+    /// The label of the outermost group is kept.
+    pub fn begin(&mut self, label: &str) {
+        match &mut self.open {
+            Some((_, depth)) => *depth += 1,
+            None => self.open = Some((Transaction::new(Some(label.to_string())), 1)),
+        }
+    }
+
+    /// Closes the innermost group started by [`begin`](History::begin).
+    ///
+    /// Closing the outermost group makes everything recorded since it began
+    /// a single undo step. Does nothing if no group is open.
+    pub fn commit(&mut self) {
+        if let Some((_, depth)) = &mut self.open {
+            *depth -= 1;
+            if *depth == 0 {
+                self.close_open_group();
+            }
+        }
+    }
+
+    /// Reverses everything recorded in the open group and discards it.
+    ///
+    /// Use this when an edit fails partway through a group.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error from the narrative if the reversal cannot be
+    /// applied, which means the narrative was changed outside the history.
+    pub fn abort(&mut self, narrative: &mut Narrative) -> Result<(), NarrativeError> {
+        match self.open.take() {
+            Some((transaction, _)) => narrative.apply_updates(&transaction.inverse()),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns `true` if there is a step to undo.
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    /// Returns `true` if there is a step to redo.
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Returns the step that [`undo`](History::undo) would reverse.
+    pub fn next_undo(&self) -> Option<&Transaction> {
+        self.undo.last()
+    }
+
+    /// Returns the step that [`redo`](History::redo) would reapply.
+    pub fn next_redo(&self) -> Option<&Transaction> {
+        self.redo.last()
+    }
+
+    /// Reverses the most recent step.
+    ///
+    /// Returns the updates applied to the narrative, so views can be patched
+    /// just as for the original edit, or an empty `Vec` if there is nothing
+    /// to undo. Any open group is committed first.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error from the narrative if the step cannot be reversed,
+    /// which means the narrative was changed outside the history. The
+    /// narrative and the history are then left unchanged.
+    pub fn undo(
+        &mut self,
+        narrative: &mut Narrative,
+    ) -> Result<Vec<NarrativeUpdate>, NarrativeError> {
+        self.close_open_group();
+        let Some(transaction) = self.undo.pop() else {
+            return Ok(Vec::new());
+        };
+
+        let updates = transaction.inverse();
+        if let Err(error) = narrative.apply_updates(&updates) {
+            self.undo.push(transaction);
+            return Err(error);
+        }
+
+        self.redo.push(transaction);
+        Ok(updates)
+    }
+
+    /// Reapplies the most recently undone step.
+    ///
+    /// Returns the updates applied to the narrative, or an empty `Vec` if
+    /// there is nothing to redo.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error from the narrative if the step cannot be reapplied.
+    /// The narrative and the history are then left unchanged.
+    pub fn redo(
+        &mut self,
+        narrative: &mut Narrative,
+    ) -> Result<Vec<NarrativeUpdate>, NarrativeError> {
+        self.close_open_group();
+        let Some(transaction) = self.redo.pop() else {
+            return Ok(Vec::new());
+        };
+
+        if let Err(error) = narrative.apply_updates(&transaction.updates) {
+            self.redo.push(transaction);
+            return Err(error);
+        }
+
+        let updates = transaction.updates.clone();
+        self.undo.push(transaction);
+        Ok(updates)
+    }
+
+    fn close_open_group(&mut self) {
+        if let Some((transaction, _)) = self.open.take()
+            && !transaction.updates.is_empty()
+        {
+            self.undo.push(transaction);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use proptest::prelude::*;
+
+    use crate::models::{
+        History, Id, Narrative, NarrativeError, NarrativeUpdate, Scene, SceneAction, SceneElement,
+        SceneGraph, SceneGraphError, SceneGraphUpdate, SceneVariant, Summary,
+    };
+
+    /// Everything undo promises to restore: the graph, and each scene's
+    /// active variant and variants.
+    type Shape = (
+        SceneGraph,
+        HashMap<Id<Scene>, (Id<SceneVariant>, HashMap<Id<SceneVariant>, SceneVariant>)>,
+    );
+
+    fn shape(narrative: &Narrative) -> Shape {
+        let scenes = narrative
+            .scenes()
+            .map(|s| (s.id(), (*s.active_variant(), s.variants().clone())))
+            .collect();
+        (narrative.graph().clone(), scenes)
+    }
+
+    /// Three scenes linked in a chain from a root.
+    fn generate_test_components() -> (Narrative, Vec<Id<SceneVariant>>) {
+        let mut narrative = Narrative::default();
+        let mut variants = Vec::new();
+        for _ in 0..3 {
+            let scene = Scene::new();
+            variants.push(*scene.active_variant());
+            narrative.add_scene(scene).unwrap();
+        }
+        narrative.set_variant_as_root(variants[0]).unwrap();
+        narrative.link_variants(variants[0], variants[1]).unwrap();
+        narrative.link_variants(variants[1], variants[2]).unwrap();
+
+        (narrative, variants)
+    }
+
+    fn sorted_variants(narrative: &Narrative) -> Vec<Id<SceneVariant>> {
+        let mut variants: Vec<_> = narrative
+            .scenes()
+            .flat_map(|s| s.variant_ids().copied().collect::<Vec<_>>())
+            .collect();
+        variants.sort_by_key(|v| v.uuid());
+        variants
+    }
+
+    /// Applies one randomly chosen edit, picking its targets by index.
+    fn random_edit(
+        narrative: &mut Narrative,
+        kind: u8,
+        a: usize,
+        b: usize,
+        c: usize,
+    ) -> Result<Vec<NarrativeUpdate>, NarrativeError> {
+        let variants = sorted_variants(narrative);
+        if variants.is_empty() {
+            return narrative.add_scene(Scene::new());
+        }
+        let pick = |i: usize| variants[i % variants.len()];
+
+        match kind {
+            0 => narrative.add_scene(Scene::new()),
+            1 => {
+                let scene = narrative.scene_for_variant(&pick(a)).unwrap().id();
+                narrative.remove_scene(scene)
+            }
+            2 => narrative.link_variants(pick(a), pick(b)).map(|u| vec![u]),
+            3 => narrative.unlink_variants(pick(a), pick(b)).map(|u| vec![u]),
+            4 => narrative.set_variant_as_root(pick(a)).map(|u| vec![u]),
+            5 => narrative.remove_variant_as_root(pick(a)).map(|u| vec![u]),
+            _ => {
+                let variant = pick(a);
+                let parents: Vec<_> = variants
+                    .iter()
+                    .copied()
+                    .filter(|p| narrative.graph().next_variants(*p).any(|n| n == variant))
+                    .collect();
+                if parents.is_empty() {
+                    return Ok(Vec::new());
+                }
+                let src = parents[b % parents.len()];
+                narrative
+                    .move_variant(variant, src, pick(c))
+                    .map(|u| vec![u])
+            }
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn test_undo_and_redo_restore_the_narrative_exactly(
+            edits in prop::collection::vec(
+                (0..7u8, any::<usize>(), any::<usize>(), any::<usize>(), any::<bool>()),
+                1..40,
+            )
+        ) {
+            // ARRANGE
+            let (mut narrative, _) = generate_test_components();
+            let mut history = History::new();
+            let mut snapshots = vec![shape(&narrative)];
+
+            // ACT: make each edit, sometimes grouping two into one step.
+            for (kind, a, b, c, grouped) in edits {
+                let mut recorded = false;
+                let mut edit = |narrative: &mut Narrative, history: &mut History, a, b, c| {
+                    let updates = random_edit(narrative, kind, a, b, c).unwrap_or_default();
+                    recorded |= !updates.is_empty();
+                    history.record(updates);
+                };
+                if grouped {
+                    history.begin("Grouped edit");
+                    edit(&mut narrative, &mut history, a, b, c);
+                    edit(&mut narrative, &mut history, c, a, b);
+                    history.commit();
+                } else {
+                    edit(&mut narrative, &mut history, a, b, c);
+                }
+                if recorded {
+                    snapshots.push(shape(&narrative));
+                }
+            }
+
+            // ASSERT: each undo steps back exactly one snapshot...
+            for expected in snapshots.iter().rev().skip(1) {
+                history.undo(&mut narrative).unwrap();
+                prop_assert_eq!(&shape(&narrative), expected);
+            }
+            prop_assert!(!history.can_undo());
+
+            // ...and each redo steps forward exactly one.
+            for expected in snapshots.iter().skip(1) {
+                history.redo(&mut narrative).unwrap();
+                prop_assert_eq!(&shape(&narrative), expected);
+            }
+            prop_assert!(!history.can_redo());
+        }
+    }
+
+    #[test]
+    fn test_grouped_edits_are_undone_together() {
+        // ARRANGE
+        let (mut narrative, variants) = generate_test_components();
+        let before = shape(&narrative);
+        let mut history = History::new();
+        // ACT
+        history.begin("Relink");
+        history.record([narrative.unlink_variants(variants[1], variants[2]).unwrap()]);
+        history.record([narrative.link_variants(variants[0], variants[2]).unwrap()]);
+        history.commit();
+        let undone = history.undo(&mut narrative).unwrap();
+        // ASSERT
+        assert_eq!(undone.len(), 2);
+        assert_eq!(shape(&narrative), before);
+        assert_eq!(history.next_redo().unwrap().label(), Some("Relink"));
+    }
+
+    #[test]
+    fn test_removing_a_scene_is_undone_with_its_edges() {
+        // ARRANGE
+        let (mut narrative, variants) = generate_test_components();
+        let before = shape(&narrative);
+        let scene = narrative.scene_for_variant(&variants[1]).unwrap().id();
+        let mut history = History::new();
+        // ACT
+        history.record(narrative.remove_scene(scene).unwrap());
+        history.undo(&mut narrative).unwrap();
+        // ASSERT
+        assert_eq!(shape(&narrative), before);
+    }
+
+    #[test]
+    fn test_recording_an_edit_clears_redo() {
+        // ARRANGE
+        let (mut narrative, variants) = generate_test_components();
+        let mut history = History::new();
+        history.record([narrative.unlink_variants(variants[1], variants[2]).unwrap()]);
+        history.undo(&mut narrative).unwrap();
+        // ACT
+        history.record([narrative.link_variants(variants[0], variants[2]).unwrap()]);
+        // ASSERT
+        assert!(!history.can_redo());
+        assert_eq!(history.redo(&mut narrative), Ok(Vec::new()));
+    }
+
+    #[test]
+    fn test_undo_that_no_longer_applies_leaves_everything_unchanged() {
+        // ARRANGE
+        let (mut narrative, variants) = generate_test_components();
+        let mut history = History::new();
+        history.record([narrative.link_variants(variants[0], variants[2]).unwrap()]);
+        // The edge is removed behind the history's back.
+        narrative.unlink_variants(variants[0], variants[2]).unwrap();
+        let before = shape(&narrative);
+        // ACT
+        let response = history.undo(&mut narrative);
+        // ASSERT
+        assert_eq!(
+            response,
+            Err(NarrativeError::Graph(SceneGraphError::StaleUpdate(
+                SceneGraphUpdate::EdgeRemoved {
+                    src: variants[0],
+                    dest: variants[2]
+                }
+            )))
+        );
+        assert_eq!(shape(&narrative), before);
+        assert!(history.can_undo());
+    }
+
+    #[test]
+    fn test_aborting_a_group_reverses_it() {
+        // ARRANGE
+        let (mut narrative, variants) = generate_test_components();
+        let before = shape(&narrative);
+        let mut history = History::new();
+        history.begin("Relink");
+        history.record([narrative.unlink_variants(variants[1], variants[2]).unwrap()]);
+        // ACT
+        history.abort(&mut narrative).unwrap();
+        // ASSERT
+        assert_eq!(shape(&narrative), before);
+        assert!(!history.can_undo());
+    }
+
+    #[test]
+    fn test_variant_content_edits_are_undone_and_redone() {
+        // ARRANGE
+        let (mut narrative, variants) = generate_test_components();
+        let mut history = History::new();
+        let before = shape(&narrative);
+        let action = SceneElement::Action(SceneAction::new("Rain.").unwrap());
+        // ACT
+        history.begin("Draft the opening");
+        for edit in [
+            narrative.edit_variant(variants[0], |v| v.add_element(action.clone())),
+            narrative.edit_variant(variants[0], |v| {
+                v.set_summary(Summary::new("It rains.").unwrap())
+            }),
+        ] {
+            history.record([edit.unwrap()]);
+        }
+        history.commit();
+        let edited = shape(&narrative);
+        history.undo(&mut narrative).unwrap();
+        let undone = shape(&narrative);
+        history.redo(&mut narrative).unwrap();
+        // ASSERT
+        let content = |shape: &Shape| {
+            shape
+                .1
+                .values()
+                .flat_map(|(_, variants)| variants.values())
+                .map(|v| (v.id(), (v.summary().clone(), v.elements().to_vec())))
+                .collect::<HashMap<_, _>>()
+        };
+        assert_eq!(content(&undone), content(&before));
+        assert_eq!(content(&shape(&narrative)), content(&edited));
+        assert_ne!(content(&edited), content(&before));
+    }
+}
@@ -1,7 +1,11 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use time::OffsetDateTime;
 
-use crate::utils::{InputError, validate_input};
+use crate::{
+    models::{Dialogue, Id, Scene, SceneVariant},
+    utils::{InputError, validate_input},
+};
 
 #[derive(Debug, Hash, PartialEq, Eq, Clone, Deserialize, Serialize)]
 pub struct RevisionNote(String);
@@ -12,6 +16,16 @@ impl RevisionNote {
     }
 }
 
+/// Something whose metadata is locked, named by the `Locked` errors that
+/// refuse to change it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum LockedEntity {
+    Storyboard,
+    Scene(Id<Scene>),
+    Variant(Id<SceneVariant>),
+    Dialogue(Id<Dialogue>),
+}
+
 #[derive(Debug, Hash, PartialEq, Eq, Clone, Deserialize, Serialize)]
 pub struct Metadata {
     #[serde(with = "time::serde::iso8601")]
@@ -20,8 +34,10 @@ pub struct Metadata {
     pub updated_at: OffsetDateTime,
     pub version: u32,
     pub revision_notes: Vec<RevisionNote>,
-    pub tags: Vec<String>, // Optional
-    pub locked: bool,
+    pub tags: Vec<String>,   // Optional
+    pub(crate) locked: bool, // Cleared only by an admin, through the storyboard
+    #[serde(default)]
+    pub properties: BTreeMap<String, String>, // Format-specific values kept for round trips
 }
 
 impl Metadata {
@@ -34,6 +50,7 @@ impl Metadata {
             revision_notes: Vec::new(),
             tags: Vec::new(),
             locked: false,
+            properties: BTreeMap::new(),
         }
     }
 
@@ -43,6 +60,18 @@ impl Metadata {
         self.updated_at = now;
         self.version += 1;
     }
+
+    /// Sets the lock flag, recording `note` as the reason.
+    pub(crate) fn set_locked(&mut self, locked: bool, note: RevisionNote) {
+        self.locked = locked;
+        self.add_revision_note(note);
+    }
+}
+
+impl Default for Metadata {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 pub trait HasMetadata {
@@ -54,6 +83,28 @@ pub trait HasMetadata {
         meta.updated_at = OffsetDateTime::now_utc();
         meta.version += 1;
     }
+
+    /// Returns `true` if changes to this are refused.
+    fn is_locked(&self) -> bool {
+        self.metadata().locked
+    }
+
+    /// Locks this against changes, recording `note` as the reason.
+    ///
+    /// Only an admin author can lift the lock, with
+    /// [`Storyboard::unlock_entity`](crate::models::Storyboard::unlock_entity).
+    fn lock(&mut self, note: RevisionNote) {
+        self.metadata_mut().set_locked(true, note);
+    }
+}
+
+/// Lifting locks, kept inside the crate so that it only happens through
+/// [`Storyboard::unlock_entity`](crate::models::Storyboard::unlock_entity).
+pub(crate) trait Unlock: HasMetadata {
+    /// Lifts the lock, recording `note` as the reason.
+    fn unlock(&mut self, note: RevisionNote) {
+        self.metadata_mut().set_locked(false, note);
+    }
 }
 
 #[cfg(test)]
@@ -1,17 +1,40 @@
 mod author;
 mod character;
+mod cut;
+mod event_log;
+mod history;
 mod metadata;
 mod narrative;
+mod relationship;
+mod revision;
 mod scene;
 mod scene_element;
 mod scene_graph;
+mod scene_number;
 mod storyboard;
 mod summary;
 mod title;
 
 pub use {
-    metadata::HasMetadata,
-    scene::{Scene, SceneVariant},
+    author::{Author, AuthorName},
+    character::{Character, CharacterError, CharacterName},
+    cut::{Cut, CutError, CutName},
+    event_log::{Event, EventLog, ReplayError},
+    history::{History, Transaction},
+    metadata::{HasMetadata, LockedEntity, Metadata, RevisionNote},
+    narrative::{BranchPolicy, Narrative, NarrativeError, NarrativeUpdate, StoryPath},
+    relationship::{Relationship, RelationshipError, RelationshipKind},
+    revision::{RevisionColor, RevisionSet},
+    scene::{ElementError, ElementRef, ElementUpdate, Scene, SceneError, SceneVariant},
+    scene_element::{
+        CameraLocation, Dialogue, DialogueBlock, DialogueText, Parenthetical, SceneAction,
+        SceneElement, SceneHeading, SceneLocation, SceneTimeOfDay,
+    },
+    scene_graph::{GraphReport, Paths, SceneGraph, SceneGraphError, SceneGraphUpdate},
+    scene_number::{NumberedScene, SceneNumber, ScriptScene},
+    storyboard::{ChangeError, StoryTemplate, Storyboard, StoryboardChange},
+    summary::Summary,
+    title::Title,
 };
 
 use serde::{Deserialize, Serialize};
@@ -41,6 +64,12 @@ impl<T> Id<T> {
     }
 }
 
+impl<T> Default for Id<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<T> fmt::Display for Id<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.value)
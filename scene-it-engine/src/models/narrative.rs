@@ -3,10 +3,17 @@ use std::collections::{HashMap, HashSet};
 use serde::{Deserialize, Serialize};
 
 use crate::models::{
-    HasMetadata, Id, Scene, SceneVariant,
-    scene_graph::{SceneGraph, SceneGraphError, SceneGraphUpdate},
+    Author, Character, Dialogue, ElementError, ElementUpdate, HasMetadata, Id, LockedEntity,
+    Metadata, RevisionColor, RevisionNote, Scene, SceneElement, SceneError, SceneVariant,
+    metadata::Unlock,
+    scene_graph::{GraphReport, SceneGraph, SceneGraphError, SceneGraphUpdate},
+    scene_number::{NumberedScene, SceneNumber, ScriptScene},
 };
 
+/// A full story through the narrative, as each scene paired with the variant
+/// of it that the story visits, in order.
+pub type StoryPath = Vec<(Id<Scene>, Id<SceneVariant>)>;
+
 /// Errors that can occur while mutating or querying a [`Narrative`].
 #[derive(Debug, Serialize, PartialEq)]
 pub enum NarrativeError {
@@ -30,6 +37,50 @@ pub enum NarrativeError {
     RootAlreadyExists(Id<SceneVariant>),
     /// The scene variant is already removed as a root entry point.
     RootAlreadyRemoved(Id<SceneVariant>),
+    /// The scene variant is in the graph but belongs to no scene.
+    MissingVariant(Id<SceneVariant>),
+    /// `variant` has several successors and the [`BranchPolicy`] could not
+    /// pick one of them.
+    AmbiguousBranch {
+        variant: Id<SceneVariant>,
+        successors: Vec<Id<SceneVariant>>,
+    },
+    /// The scene has a variant reachable from a root, so it is not an orphan.
+    SceneAlreadyPlaced(Id<Scene>),
+    /// None of the scene's variants has an edge or is a root.
+    SceneAlreadyParked(Id<Scene>),
+    /// An explicit branch choice names a variant that does not follow `variant`.
+    InvalidChoice {
+        variant: Id<SceneVariant>,
+        choice: Id<SceneVariant>,
+    },
+    /// A lower-level [`SceneError`] occurred while changing a scene's variants.
+    Scene(SceneError),
+    /// A lower-level [`ElementError`] occurred while editing a variant's content.
+    Element(ElementError),
+    /// The scene variant is already its scene's active variant.
+    VariantAlreadyActive(Id<SceneVariant>),
+    /// The change would alter something that is locked.
+    Locked(LockedEntity),
+    /// The author may not override locks, or is not one of the storyboard's
+    /// authors.
+    NotAdmin(Id<Author>),
+    /// The author is an admin, or would become one, and only an admin can
+    /// change that once the storyboard has one.
+    AdminRequired(Id<Author>),
+    /// The referenced author is not one of the storyboard's authors.
+    UnknownAuthor(Id<Author>),
+    /// The referenced dialogue is in none of the narrative's variants.
+    UnknownDialogue(Id<Dialogue>),
+    /// A revision set was opened on `found`, out of turn; the next set goes
+    /// out on `expected`.
+    RevisionOutOfOrder {
+        expected: RevisionColor,
+        found: RevisionColor,
+    },
+    /// A revision set was opened on `found` after the last color,
+    /// [`RevisionColor::DoubleCherry`], had been issued.
+    RevisionColorsExhausted { found: RevisionColor },
 }
 
 impl From<SceneGraphError> for NarrativeError {
@@ -38,11 +89,94 @@ impl From<SceneGraphError> for NarrativeError {
     }
 }
 
+impl From<SceneError> for NarrativeError {
+    fn from(value: SceneError) -> Self {
+        NarrativeError::Scene(value)
+    }
+}
+
+impl From<ElementError> for NarrativeError {
+    fn from(value: ElementError) -> Self {
+        NarrativeError::Element(value)
+    }
+}
+
 /// A structural change to a [`Narrative`], emitted as the result of a mutating operation.
-#[derive(Clone, Debug, PartialEq, Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum NarrativeUpdate {
     /// A change to the underlying scene graph.
     Graph(SceneGraphUpdate),
+    /// A scene was added to the scene bank. Its variants are added to the
+    /// graph by the updates that follow.
+    SceneAdded(Box<Scene>),
+    /// A scene was removed from the scene bank, after the updates removing
+    /// its variants from the graph.
+    SceneRemoved(Box<Scene>),
+    /// A variant was added to a scene. It is added to the graph by the
+    /// update that follows.
+    VariantAdded {
+        scene: Id<Scene>,
+        variant: Box<SceneVariant>,
+    },
+    /// A variant was removed from a scene, after the updates removing it
+    /// from the graph.
+    VariantRemoved {
+        scene: Id<Scene>,
+        variant: Box<SceneVariant>,
+    },
+    /// A scene's active variant changed from `from` to `to`.
+    ActiveVariantChanged {
+        scene: Id<Scene>,
+        from: Id<SceneVariant>,
+        to: Id<SceneVariant>,
+    },
+    /// The scene numbers changed from `from` to `to`.
+    ScenesNumbered {
+        from: Vec<NumberedScene>,
+        to: Vec<NumberedScene>,
+    },
+    /// The heading, summary, or elements of `variant` changed.
+    Elements {
+        variant: Id<SceneVariant>,
+        update: Box<ElementUpdate>,
+    },
+}
+
+impl NarrativeUpdate {
+    /// Returns the update that reverses this one.
+    ///
+    /// To reverse the updates returned by one operation, apply the inverse
+    /// of each in reverse order.
+    pub fn inverse(&self) -> NarrativeUpdate {
+        match self {
+            NarrativeUpdate::Graph(update) => NarrativeUpdate::Graph(update.inverse()),
+            NarrativeUpdate::SceneAdded(scene) => NarrativeUpdate::SceneRemoved(scene.clone()),
+            NarrativeUpdate::SceneRemoved(scene) => NarrativeUpdate::SceneAdded(scene.clone()),
+            NarrativeUpdate::VariantAdded { scene, variant } => NarrativeUpdate::VariantRemoved {
+                scene: *scene,
+                variant: variant.clone(),
+            },
+            NarrativeUpdate::VariantRemoved { scene, variant } => NarrativeUpdate::VariantAdded {
+                scene: *scene,
+                variant: variant.clone(),
+            },
+            NarrativeUpdate::ActiveVariantChanged { scene, from, to } => {
+                NarrativeUpdate::ActiveVariantChanged {
+                    scene: *scene,
+                    from: *to,
+                    to: *from,
+                }
+            }
+            NarrativeUpdate::ScenesNumbered { from, to } => NarrativeUpdate::ScenesNumbered {
+                from: to.clone(),
+                to: from.clone(),
+            },
+            NarrativeUpdate::Elements { variant, update } => NarrativeUpdate::Elements {
+                variant: *variant,
+                update: Box::new(update.inverse()),
+            },
+        }
+    }
 }
 
 impl From<SceneGraphUpdate> for NarrativeUpdate {
@@ -51,22 +185,146 @@ impl From<SceneGraphUpdate> for NarrativeUpdate {
     }
 }
 
+/// How a path through the story picks among several successors of a variant.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum BranchPolicy {
+    /// Take the successor that is its scene's active variant. This settles
+    /// the common case of alternate variants of the same scene; a branch to
+    /// several different scenes, each active, remains ambiguous.
+    #[default]
+    PreferActive,
+    /// Take the successor chosen for each branching variant, keyed by the
+    /// variant that branches. Branches without a choice fall back to
+    /// [`BranchPolicy::PreferActive`].
+    Choices(HashMap<Id<SceneVariant>, Id<SceneVariant>>),
+}
+
 /// The set of scenes and their relationships that make up a story.
 ///
 /// A `Narrative` combines scene data (the `scenes` bank) with a [`SceneGraph`]
 /// that tracks ordering, branching, and entry points, keeping the two in sync
 /// as scenes are added, removed, linked, and reordered.
-#[derive(Debug, Serialize, Deserialize, Default)]
+///
+/// Once a shooting script is numbered with [`Narrative::number_scenes`], the
+/// narrative also keeps every scene number it has given out, in script order.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct Narrative {
     graph: SceneGraph,
     scenes: HashMap<Id<Scene>, Scene>,
+    #[serde(default)]
+    scene_numbers: Vec<NumberedScene>,
 }
 
 impl Narrative {
+    /// Returns the scene graph tracking ordering, branching, and entry points.
+    pub fn graph(&self) -> &SceneGraph {
+        &self.graph
+    }
+
+    /// Returns the scene with the given ID, if it is in the narrative.
+    pub fn scene(&self, scene_id: &Id<Scene>) -> Option<&Scene> {
+        self.scenes.get(scene_id)
+    }
+
+    /// Returns an iterator over every scene in the narrative, in no particular order.
+    pub fn scenes(&self) -> impl Iterator<Item = &Scene> {
+        self.scenes.values()
+    }
+
+    /// Returns the scene that owns the given variant, if any.
+    pub fn scene_for_variant(&self, variant_id: &Id<SceneVariant>) -> Option<&Scene> {
+        self.scenes.values().find(|s| s.has_variant(variant_id))
+    }
+
+    /// Returns the given variant for editing its content, if it belongs to a
+    /// scene in the narrative.
+    ///
+    /// Editing a variant's heading, summary, or elements leaves the graph as
+    /// it is, so the variant can be changed in place. Structural changes go
+    /// through the narrative's own methods. Use [`Narrative::edit_variant`]
+    /// instead for an edit that can be undone or recorded.
+    pub fn variant_mut(&mut self, variant_id: &Id<SceneVariant>) -> Option<&mut SceneVariant> {
+        self.scenes
+            .values_mut()
+            .find_map(|s| s.variants_mut().get_mut(variant_id))
+    }
+
+    /// Returns the given dialogue for locking or unlocking, if it is in one
+    /// of the narrative's variants.
+    pub(crate) fn dialogue_mut(&mut self, dialogue_id: Id<Dialogue>) -> Option<&mut Dialogue> {
+        self.scenes
+            .values_mut()
+            .flat_map(|s| s.variants_mut().values_mut())
+            .flat_map(|v| v.dialogues_mut())
+            .find(|d| d.id() == dialogue_id)
+    }
+
+    /// Edits the content of a variant with one of its editing methods,
+    /// returning the change as a [`NarrativeUpdate`] that can be recorded in
+    /// a [`History`](crate::models::History) or an
+    /// [`EventLog`](crate::models::EventLog).
+    ///
+    /// # Errors
+    ///
+    /// - [`NarrativeError::MissingVariant`] if no scene in the narrative has
+    ///   the variant.
+    /// - [`NarrativeError::Locked`] if the variant or its scene is locked.
+    /// - [`NarrativeError::Element`] with the error `edit` returns.
+    pub fn edit_variant(
+        &mut self,
+        variant_id: Id<SceneVariant>,
+        edit: impl FnOnce(&mut SceneVariant) -> Result<ElementUpdate, ElementError>,
+    ) -> Result<NarrativeUpdate, NarrativeError> {
+        self.check_unlocked([variant_id])?;
+        let variant = self
+            .variant_mut(&variant_id)
+            .ok_or(NarrativeError::MissingVariant(variant_id))?;
+
+        Ok(NarrativeUpdate::Elements {
+            variant: variant_id,
+            update: Box::new(edit(variant)?),
+        })
+    }
+
+    /// Locks a scene, with its variants and their dialogues, recording
+    /// `note` as the reason.
+    ///
+    /// A locked scene cannot be edited, removed, or linked or unlinked in
+    /// the graph until it is unlocked.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`NarrativeError::UnknownScene`] if the scene does not exist.
+    pub fn lock_scene(
+        &mut self,
+        scene_id: Id<Scene>,
+        note: RevisionNote,
+    ) -> Result<(), NarrativeError> {
+        self.scene_mut(scene_id)?.lock(note);
+        Ok(())
+    }
+
+    /// Unlocks a scene, with its variants and their dialogues, recording
+    /// `note` as the reason. Only an admin may unlock; see
+    /// [`Storyboard::unlock_entity`](crate::models::Storyboard::unlock_entity).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`NarrativeError::UnknownScene`] if the scene does not exist.
+    pub(crate) fn unlock_scene(
+        &mut self,
+        scene_id: Id<Scene>,
+        note: RevisionNote,
+    ) -> Result<(), NarrativeError> {
+        self.scene_mut(scene_id)?.unlock(note);
+        Ok(())
+    }
+
     /// Adds a new scene to the narrative.
     ///
     /// Registers the scene in the scene bank and each of its variants in the
-    /// [`SceneGraph`], returning one update per variant that was newly added.
+    /// [`SceneGraph`], returning a [`NarrativeUpdate::SceneAdded`] followed by
+    /// one update per variant that was newly added.
     ///
     /// A new scene has no prior metadata to touch, so no updates are applied
     /// to it here.
@@ -82,14 +340,17 @@ impl Narrative {
         }
 
         // Because the scene is new, updates for added variants won't be recorded in scene metadata.
-        let updates: Vec<_> = scene
-            .variant_ids()
-            .filter_map(|v| self.graph.add_variant(*v))
-            .collect();
+        let mut updates = vec![NarrativeUpdate::SceneAdded(Box::new(scene.clone()))];
+        updates.extend(
+            scene
+                .variant_ids()
+                .filter_map(|v| self.graph.add_variant(*v))
+                .map(NarrativeUpdate::from),
+        );
 
         self.scenes.insert(scene.id(), scene);
 
-        Ok(updates.into_iter().map(NarrativeUpdate::from).collect())
+        Ok(updates)
     }
 
     /// Removes a scene from the narrative and its scene graph.
@@ -105,8 +366,16 @@ impl Narrative {
     ///
     /// # Errors
     ///
-    /// Returns [`NarrativeError::UnknownScene`] if the scene does not exist in
-    /// the narrative.
+    /// - [`NarrativeError::UnknownScene`] if the scene does not exist in the
+    ///   narrative.
+    /// - [`NarrativeError::Locked`] if the scene, or a scene linked to it, is
+    ///   locked.
+    ///
+    /// # Updates
+    ///
+    /// The graph updates for the removed edges, roots, and variants come
+    /// first, followed by a [`NarrativeUpdate::SceneRemoved`] carrying the
+    /// removed scene so the removal can be reversed.
     ///
     /// # Side Effects
     ///
@@ -118,6 +387,14 @@ impl Narrative {
         &mut self,
         scene: Id<Scene>,
     ) -> Result<Vec<NarrativeUpdate>, NarrativeError> {
+        if let Some(variants) = self
+            .scenes
+            .get(&scene)
+            .map(|s| s.variant_ids().copied().collect::<Vec<_>>())
+        {
+            self.check_unlocked_with_neighbours(variants)?;
+        }
+
         if let Some(scene) = self.scenes.remove(&scene) {
             let updates: Vec<_> = scene
                 .variant_ids()
@@ -128,24 +405,144 @@ impl Narrative {
                 .iter()
                 .for_each(|u| self.apply_scene_graph_update(u.clone()));
 
-            return Ok(updates.into_iter().map(NarrativeUpdate::from).collect());
+            let mut updates: Vec<_> = updates.into_iter().map(NarrativeUpdate::from).collect();
+            updates.push(NarrativeUpdate::SceneRemoved(Box::new(scene)));
+
+            return Ok(updates);
         }
 
         Err(NarrativeError::UnknownScene(scene))
     }
 
+    /// Forks one of a scene's variants into a new, inactive variant.
+    ///
+    /// The fork is a deep copy of the variant's heading, elements, and
+    /// summary under fresh IDs, including fresh dialogue IDs. It is
+    /// registered in the [`SceneGraph`] with no edges and is not a root, so
+    /// it stays out of every story until it is linked in.
+    ///
+    /// Returns the fork's ID along with a [`NarrativeUpdate::VariantAdded`]
+    /// followed by the graph update adding it.
+    ///
+    /// # Errors
+    ///
+    /// - [`NarrativeError::UnknownScene`] if the scene does not exist.
+    /// - [`NarrativeError::Scene`] with [`SceneError::UnknownVariant`] if the
+    ///   variant does not belong to the scene.
+    /// - [`NarrativeError::Locked`] if the scene is locked.
+    pub fn fork_variant(
+        &mut self,
+        scene_id: Id<Scene>,
+        variant_id: Id<SceneVariant>,
+    ) -> Result<(Id<SceneVariant>, Vec<NarrativeUpdate>), NarrativeError> {
+        self.check_scene_unlocked(scene_id)?;
+        let scene = self.scene_mut(scene_id)?;
+        let fork_id = scene.fork_variant(&variant_id)?;
+
+        let mut updates = vec![NarrativeUpdate::VariantAdded {
+            scene: scene_id,
+            variant: Box::new(scene.variants()[&fork_id].clone()),
+        }];
+        updates.extend(self.graph.add_variant(fork_id).map(NarrativeUpdate::from));
+
+        Ok((fork_id, updates))
+    }
+
+    /// Makes one of a scene's variants its active variant.
+    ///
+    /// The graph is unchanged; only which variant stands for the scene, for
+    /// example when a story follows [`BranchPolicy::PreferActive`], moves.
+    ///
+    /// # Errors
+    ///
+    /// - [`NarrativeError::UnknownScene`] if the scene does not exist.
+    /// - [`NarrativeError::Scene`] with [`SceneError::UnknownVariant`] if the
+    ///   variant does not belong to the scene.
+    /// - [`NarrativeError::VariantAlreadyActive`] if the variant is already
+    ///   active.
+    /// - [`NarrativeError::Locked`] if the scene is locked.
+    pub fn set_active_variant(
+        &mut self,
+        scene_id: Id<Scene>,
+        variant_id: Id<SceneVariant>,
+    ) -> Result<NarrativeUpdate, NarrativeError> {
+        self.check_scene_unlocked(scene_id)?;
+        let scene = self.scene_mut(scene_id)?;
+        if *scene.active_variant() == variant_id {
+            return Err(NarrativeError::VariantAlreadyActive(variant_id));
+        }
+        let from = scene.set_active_variant(variant_id)?;
+
+        Ok(NarrativeUpdate::ActiveVariantChanged {
+            scene: scene_id,
+            from,
+            to: variant_id,
+        })
+    }
+
+    /// Removes one of a scene's variants from the scene and the graph.
+    ///
+    /// The variant's edges and root status go with it, as for
+    /// [`Narrative::remove_scene`]. A scene always keeps at least one
+    /// variant, and the active variant must be replaced with
+    /// [`Narrative::set_active_variant`] before it can be removed.
+    ///
+    /// # Errors
+    ///
+    /// - [`NarrativeError::UnknownScene`] if the scene does not exist.
+    /// - [`NarrativeError::Scene`] with [`SceneError::UnknownVariant`],
+    ///   [`SceneError::LastVariant`], or [`SceneError::ActiveVariant`] if the
+    ///   variant cannot be removed. Nothing is changed.
+    /// - [`NarrativeError::Locked`] if the scene, the variant, or a scene
+    ///   linked to the variant is locked.
+    ///
+    /// # Updates
+    ///
+    /// The graph updates for the removed edges, root, and variant come first,
+    /// followed by a [`NarrativeUpdate::VariantRemoved`] carrying the removed
+    /// variant so the removal can be reversed.
+    pub fn remove_variant(
+        &mut self,
+        scene_id: Id<Scene>,
+        variant_id: Id<SceneVariant>,
+    ) -> Result<Vec<NarrativeUpdate>, NarrativeError> {
+        self.check_scene_unlocked(scene_id)?;
+        self.check_unlocked_with_neighbours([variant_id])?;
+        self.scenes
+            .get(&scene_id)
+            .ok_or(NarrativeError::UnknownScene(scene_id))?
+            .check_removable(&variant_id)?;
+
+        let updates = self.graph.remove_variant(variant_id);
+        updates
+            .iter()
+            .for_each(|u| self.apply_scene_graph_update(u.clone()));
+
+        let variant = self.scene_mut(scene_id)?.remove_variant(&variant_id)?;
+
+        let mut updates: Vec<_> = updates.into_iter().map(NarrativeUpdate::from).collect();
+        updates.push(NarrativeUpdate::VariantRemoved {
+            scene: scene_id,
+            variant: Box::new(variant),
+        });
+
+        Ok(updates)
+    }
+
     /// Marks a scene as a root entry point in the scene graph.
     ///
     /// Root scenes represent valid starting points for story traversal.
     ///
     /// # Errors
     ///
-    /// Returns [`NarrativeError::RootAlreadyExists`] if the scene variant is
-    /// already registered as a root.
+    /// - [`NarrativeError::RootAlreadyExists`] if the scene variant is already
+    ///   registered as a root.
+    /// - [`NarrativeError::Locked`] if the variant or its scene is locked.
     pub fn set_variant_as_root(
         &mut self,
         variant_id: Id<SceneVariant>,
     ) -> Result<NarrativeUpdate, NarrativeError> {
+        self.check_unlocked([variant_id])?;
         if let Some(update) = self.graph.add_root(variant_id)? {
             self.apply_scene_graph_update(update.clone());
             return Ok(update.into());
@@ -158,12 +555,14 @@ impl Narrative {
     ///
     /// # Errors
     ///
-    /// Returns [`NarrativeError::RootAlreadyRemoved`] if the scene variant is
-    /// not currently registered as a root.
+    /// - [`NarrativeError::RootAlreadyRemoved`] if the scene variant is not
+    ///   currently registered as a root.
+    /// - [`NarrativeError::Locked`] if the variant or its scene is locked.
     pub fn remove_variant_as_root(
         &mut self,
         variant_id: Id<SceneVariant>,
     ) -> Result<NarrativeUpdate, NarrativeError> {
+        self.check_unlocked([variant_id])?;
         if let Some(update) = self.graph.remove_root(variant_id)? {
             self.apply_scene_graph_update(update.clone());
             return Ok(update.into());
@@ -180,14 +579,19 @@ impl Narrative {
     /// # Errors
     ///
     /// Returns [`NarrativeError::Graph`] with [`SceneGraphError::UnknownVariant`]
-    /// if either `src` or `dest` does not exist in the narrative.
+    /// if either `src` or `dest` does not exist in the narrative, or with
+    /// [`SceneGraphError::CycleDetected`] if `src` follows `dest` already.
     ///
     /// Returns [`NarrativeError::VariantsAlreadyLinked`] if the edge already exists.
+    ///
+    /// Returns [`NarrativeError::Locked`] if either variant, or its scene, is
+    /// locked.
     pub fn link_variants(
         &mut self,
         src: Id<SceneVariant>,
         dest: Id<SceneVariant>,
     ) -> Result<NarrativeUpdate, NarrativeError> {
+        self.check_unlocked([src, dest])?;
         let graph_update = self.graph.add_edge(src, dest)?;
 
         if let Some(update) = graph_update {
@@ -214,6 +618,9 @@ impl Narrative {
     /// caller asked to remove an edge it believed existed, so its view has
     /// diverged from the engine.
     ///
+    /// Returns [`NarrativeError::Locked`] if either variant, or its scene, is
+    /// locked.
+    ///
     /// # Side Effects
     ///
     /// Touches metadata for both variants' scenes.
@@ -228,6 +635,7 @@ impl Narrative {
         src: Id<SceneVariant>,
         dest: Id<SceneVariant>,
     ) -> Result<NarrativeUpdate, NarrativeError> {
+        self.check_unlocked([src, dest])?;
         if let Some(graph_update) = self.graph.remove_edge(src, dest)? {
             self.apply_scene_graph_update(graph_update.clone());
             return Ok(graph_update.into());
@@ -238,7 +646,7 @@ impl Narrative {
 
     /// Returns all scenes that are unreachable from any root scene.
     ///
-    /// A standalone scene is defined as one that:
+    /// A standalone scene is defined as one where every variant:
     /// - Is not a root scene
     /// - Is not reachable from any root via directed edges
     ///
@@ -255,8 +663,188 @@ impl Narrative {
     /// - Providing UI warnings or cleanup suggestions
     /// - Helping users identify narrative dead ends
     pub fn standalone_scenes(&self) -> HashSet<Id<Scene>> {
-        self.graph.unreachable_variants();
-        todo!()
+        let unreachable = self.graph.unreachable_variants();
+
+        self.scenes
+            .values()
+            .filter(|s| s.variant_ids().all(|v| unreachable.contains(v)))
+            .map(|s| s.id())
+            .collect()
+    }
+
+    /// Returns the scenes that are only partly reachable, each with the
+    /// variants of it that no root can reach.
+    ///
+    /// These scenes are placed in the story, but some of their alternate
+    /// variants are not, so switching to one of those would drop the scene
+    /// from every path.
+    pub fn partially_reachable_scenes(&self) -> HashMap<Id<Scene>, HashSet<Id<SceneVariant>>> {
+        let unreachable = self.graph.unreachable_variants();
+
+        self.scenes
+            .values()
+            .filter_map(|scene| {
+                let orphaned: HashSet<_> = scene
+                    .variant_ids()
+                    .filter(|v| unreachable.contains(v))
+                    .copied()
+                    .collect();
+
+                (!orphaned.is_empty() && orphaned.len() < scene.variants().len())
+                    .then(|| (scene.id(), orphaned))
+            })
+            .collect()
+    }
+
+    /// Checks the narrative's graph for problems, including graph variants
+    /// that belong to no scene and scene variants missing from the graph.
+    ///
+    /// A project read from a file is not checked on the way in, so this is
+    /// worth running when one is opened. See [`SceneGraph::validate`] for the
+    /// graph-level checks.
+    pub fn validate(&self) -> GraphReport {
+        let owned: HashSet<_> = self
+            .scenes
+            .values()
+            .flat_map(|s| s.variant_ids())
+            .copied()
+            .collect();
+
+        let mut report = self.graph.validate();
+        report.unowned_variants = self
+            .graph
+            .variants()
+            .filter(|v| !owned.contains(v))
+            .collect();
+        report.unowned_variants.sort_by_key(|id| id.uuid());
+        report.ungraphed_variants = owned
+            .into_iter()
+            .filter(|v| !self.graph.contains(*v))
+            .collect();
+        report.ungraphed_variants.sort_by_key(|id| id.uuid());
+
+        report
+    }
+
+    /// Places a standalone scene in the story by linking its active variant
+    /// after `after`.
+    ///
+    /// The scene becomes a successor of `after` alongside any it already has;
+    /// no existing edges are changed.
+    ///
+    /// # Errors
+    ///
+    /// - [`NarrativeError::UnknownScene`] if the scene does not exist.
+    /// - [`NarrativeError::SceneAlreadyPlaced`] if any of the scene's variants
+    ///   is reachable from a root, since the scene is not an orphan.
+    /// - The errors of [`Narrative::link_variants`] if the link cannot be made.
+    pub fn attach_scene(
+        &mut self,
+        scene_id: Id<Scene>,
+        after: Id<SceneVariant>,
+    ) -> Result<NarrativeUpdate, NarrativeError> {
+        let variant_id = *self
+            .scenes
+            .get(&scene_id)
+            .ok_or(NarrativeError::UnknownScene(scene_id))?
+            .active_variant();
+
+        if !self.standalone_scenes().contains(&scene_id) {
+            return Err(NarrativeError::SceneAlreadyPlaced(scene_id));
+        }
+
+        self.link_variants(after, variant_id)
+    }
+
+    /// Takes a scene out of the story while keeping it and its variants in
+    /// the narrative, ready to be attached again.
+    ///
+    /// Every edge into or out of the scene's variants is removed, and so is
+    /// their root status. So that parking a scene does not strand the story
+    /// after it, each predecessor of the scene is linked to each of its
+    /// successors, and if the scene was a root its successors become roots.
+    ///
+    /// # Errors
+    ///
+    /// - [`NarrativeError::UnknownScene`] if the scene does not exist.
+    /// - [`NarrativeError::SceneAlreadyParked`] if none of the scene's
+    ///   variants has an edge or is a root.
+    /// - [`NarrativeError::Locked`] if the scene, or a scene linked to it, is
+    ///   locked.
+    ///
+    /// # Updates
+    ///
+    /// The removals come first, followed by the bridging edges and roots.
+    pub fn park_scene(
+        &mut self,
+        scene_id: Id<Scene>,
+    ) -> Result<Vec<NarrativeUpdate>, NarrativeError> {
+        let own: HashSet<_> = self
+            .scenes
+            .get(&scene_id)
+            .ok_or(NarrativeError::UnknownScene(scene_id))?
+            .variant_ids()
+            .copied()
+            .collect();
+
+        let mut outgoing = Vec::new();
+        let mut incoming = Vec::new();
+        let mut roots = Vec::new();
+        for &variant in &own {
+            outgoing.extend(self.graph.next_variants(variant).map(|s| (variant, s)));
+            incoming.extend(
+                self.graph
+                    .previous_variants(variant)
+                    .filter(|p| !own.contains(p))
+                    .map(|p| (p, variant)),
+            );
+            if self.graph.roots().contains(&variant) {
+                roots.push(variant);
+            }
+        }
+
+        if outgoing.is_empty() && incoming.is_empty() && roots.is_empty() {
+            return Err(NarrativeError::SceneAlreadyParked(scene_id));
+        }
+        self.check_unlocked_with_neighbours(own.iter().copied())?;
+
+        let sorted = |ids: HashSet<Id<SceneVariant>>| {
+            let mut ids: Vec<_> = ids.into_iter().collect();
+            ids.sort_by_key(|id| id.uuid());
+            ids
+        };
+        let predecessors = sorted(incoming.iter().map(|(p, _)| *p).collect());
+        let successors = sorted(
+            outgoing
+                .iter()
+                .map(|(_, s)| *s)
+                .filter(|s| !own.contains(s))
+                .collect(),
+        );
+
+        let mut updates = Vec::new();
+        for (src, dest) in outgoing.into_iter().chain(incoming) {
+            updates.extend(self.graph.remove_edge(src, dest)?);
+        }
+        for &variant in &roots {
+            updates.extend(self.graph.remove_root(variant)?);
+        }
+        for &src in &predecessors {
+            for &dest in &successors {
+                updates.extend(self.graph.add_edge(src, dest)?);
+            }
+        }
+        if !roots.is_empty() {
+            for &variant in &successors {
+                updates.extend(self.graph.add_root(variant)?);
+            }
+        }
+
+        updates
+            .iter()
+            .for_each(|u| self.apply_scene_graph_update(u.clone()));
+
+        Ok(updates.into_iter().map(NarrativeUpdate::from).collect())
     }
 
     /// Moves a scene variant from one parent variant to another.
@@ -268,7 +856,8 @@ impl Narrative {
     /// Returns [`NarrativeError::Graph`] wrapping the underlying
     /// [`SceneGraphError`] if any of the three variants is unknown, if
     /// `variant` is not a child of `src`, or if the move would create a cycle.
-    /// On failure the graph is left unchanged.
+    /// Returns [`NarrativeError::Locked`] if any of the three variants, or
+    /// its scene, is locked. On failure the graph is left unchanged.
     ///
     /// # Side Effects
     ///
@@ -279,42 +868,610 @@ impl Narrative {
         src: Id<SceneVariant>,
         dest: Id<SceneVariant>,
     ) -> Result<NarrativeUpdate, NarrativeError> {
+        self.check_unlocked([variant, src, dest])?;
         let graph_update = self.graph.move_variant(variant, src, dest)?;
         self.apply_scene_graph_update(graph_update.clone());
         Ok(graph_update.into())
     }
 
-    /// Returns an iterator over the scenes reachable from `root`, in traversal order.
+    /// Returns a lazy iterator over every full story the graph allows, from
+    /// each root to each leaf.
+    ///
+    /// Stories come in the stable order of [`SceneGraph::paths`]. Graph
+    /// variants that belong to no scene (see [`Narrative::validate`]) are
+    /// left out of the stories they appear in.
+    ///
+    /// The number of stories can grow exponentially with the number of
+    /// branches, so callers should size them up with
+    /// [`Narrative::count_story_paths`] or cap them with
+    /// [`Narrative::story_paths_limited`].
+    pub fn story_paths(&self) -> impl Iterator<Item = StoryPath> + '_ {
+        let owners = self.variant_owners();
+        self.graph
+            .paths()
+            .map(move |path| Self::owned_path(&owners, path))
+    }
+
+    /// Returns at most `limit` stories, in the order of
+    /// [`Narrative::story_paths`].
+    pub fn story_paths_limited(&self, limit: usize) -> Vec<StoryPath> {
+        self.story_paths().take(limit).collect()
+    }
+
+    /// Returns the number of distinct stories, without listing them.
+    ///
+    /// See [`SceneGraph::count_paths`].
+    pub fn count_story_paths(&self) -> u64 {
+        self.graph.count_paths()
+    }
+
+    /// Returns the story with the greatest total `weight`, or `None` if the
+    /// narrative has no roots.
+    ///
+    /// `weight` measures a single scene variant: `|_| 1` measures stories by
+    /// scene count, and [`estimated_eighths`](crate::layout::estimated_eighths)
+    /// by page length. Variants that belong to no scene weigh nothing.
+    pub fn longest_story_path(&self, weight: impl Fn(&SceneVariant) -> usize) -> Option<StoryPath> {
+        let owners = self.variant_owners();
+        self.graph
+            .longest_path(|v| self.variant_weight(&owners, v, &weight))
+            .map(|path| Self::owned_path(&owners, path))
+    }
+
+    /// Returns the story with the least total `weight`, or `None` if the
+    /// narrative has no roots.
+    ///
+    /// See [`Narrative::longest_story_path`] for how stories are measured.
+    pub fn shortest_story_path(
+        &self,
+        weight: impl Fn(&SceneVariant) -> usize,
+    ) -> Option<StoryPath> {
+        let owners = self.variant_owners();
+        self.graph
+            .shortest_path(|v| self.variant_weight(&owners, v, &weight))
+            .map(|path| Self::owned_path(&owners, path))
+    }
+
+    /// Maps each scene variant to the scene that owns it.
+    fn variant_owners(&self) -> HashMap<Id<SceneVariant>, Id<Scene>> {
+        self.scenes
+            .values()
+            .flat_map(|scene| scene.variant_ids().map(move |v| (*v, scene.id())))
+            .collect()
+    }
+
+    /// Pairs each variant on a graph path with its scene, dropping variants
+    /// that belong to none.
+    fn owned_path(
+        owners: &HashMap<Id<SceneVariant>, Id<Scene>>,
+        path: Vec<Id<SceneVariant>>,
+    ) -> StoryPath {
+        path.into_iter()
+            .filter_map(|v| owners.get(&v).map(|scene| (*scene, v)))
+            .collect()
+    }
+
+    fn variant_weight(
+        &self,
+        owners: &HashMap<Id<SceneVariant>, Id<Scene>>,
+        variant_id: Id<SceneVariant>,
+        weight: impl Fn(&SceneVariant) -> usize,
+    ) -> usize {
+        owners
+            .get(&variant_id)
+            .and_then(|scene| self.scenes.get(scene))
+            .and_then(|scene| scene.variants().get(&variant_id))
+            .map_or(0, weight)
+    }
+
+    /// Returns the path through the story that starts at `root`, as each
+    /// scene paired with the variant of it that the path visits.
+    ///
+    /// The path follows the [`SceneGraph`]'s edges. Where a variant has a
+    /// single successor the path takes it; where it has several, `policy`
+    /// picks one. The path ends at a variant with no successors, or at the
+    /// first variant it would visit twice.
     ///
-    /// Starting at `root`, this follows each scene variant's `next` link until it
-    /// reaches a variant with no successor or revisits an already-visited variant
-    /// (at which point traversal stops to avoid looping). If a variant referenced
-    /// by the graph cannot be found in the scene bank, a warning is printed to
-    /// stderr and traversal stops.
-    pub fn linearize_from<'a>(&'a self, root: Id<SceneVariant>) -> impl Iterator<Item = &'a Scene> {
+    /// # Errors
+    ///
+    /// - [`NarrativeError::Graph`] with [`SceneGraphError::UnknownVariant`] if
+    ///   `root` is not in the graph.
+    /// - [`NarrativeError::MissingVariant`] if the path reaches a variant that
+    ///   belongs to no scene.
+    /// - [`NarrativeError::AmbiguousBranch`] if the policy cannot pick a
+    ///   single successor.
+    /// - [`NarrativeError::InvalidChoice`] if an explicit choice names a
+    ///   variant that is not a successor.
+    pub fn linearize_from(
+        &self,
+        root: Id<SceneVariant>,
+        policy: &BranchPolicy,
+    ) -> Result<Vec<(&Scene, &SceneVariant)>, NarrativeError> {
+        if !self.graph.contains(root) {
+            return Err(SceneGraphError::UnknownVariant(root).into());
+        }
+
+        let scenes: HashMap<_, _> = self
+            .scenes
+            .values()
+            .flat_map(|scene| scene.variant_ids().map(move |v| (*v, scene)))
+            .collect();
         let mut current = Some(root);
         let mut visited = HashSet::new();
-        let mut order = Vec::new();
+        let mut path = Vec::new();
 
         while let Some(variant_id) = current {
             if !visited.insert(variant_id) {
                 break;
             }
 
-            if let Some(scene) = self.scenes.values().find(|s| s.has_variant(&variant_id)) {
-                order.push(scene);
+            let scene = *scenes
+                .get(&variant_id)
+                .ok_or(NarrativeError::MissingVariant(variant_id))?;
+            path.push((scene, &scene.variants()[&variant_id]));
+
+            current = self.choose_successor(variant_id, policy, &scenes)?;
+        }
+
+        Ok(path)
+    }
+
+    /// Picks the variant that follows `variant_id` on a path, if any.
+    ///
+    /// `scenes` maps each variant to the scene that owns it.
+    fn choose_successor(
+        &self,
+        variant_id: Id<SceneVariant>,
+        policy: &BranchPolicy,
+        scenes: &HashMap<Id<SceneVariant>, &Scene>,
+    ) -> Result<Option<Id<SceneVariant>>, NarrativeError> {
+        let mut successors: Vec<_> = self.graph.next_variants(variant_id).collect();
+        successors.sort_by_key(|id| id.uuid());
 
-                current = scene
-                    .variants()
-                    .get(&variant_id)
-                    .and_then(|v| v.next())
-                    .copied();
-            } else {
-                eprintln!("Warning: variant ID {variant_id} found in graph but not in any scene");
+        if let BranchPolicy::Choices(choices) = policy
+            && let Some(choice) = choices.get(&variant_id)
+        {
+            return match successors.contains(choice) {
+                true => Ok(Some(*choice)),
+                false => Err(NarrativeError::InvalidChoice {
+                    variant: variant_id,
+                    choice: *choice,
+                }),
+            };
+        }
+
+        if successors.len() <= 1 {
+            return Ok(successors.first().copied());
+        }
+
+        let active: Vec<_> = successors
+            .iter()
+            .filter(|id| {
+                scenes
+                    .get(*id)
+                    .is_some_and(|scene| scene.active_variant() == *id)
+            })
+            .collect();
+
+        match active.as_slice() {
+            [only] => Ok(Some(**only)),
+            _ => Err(NarrativeError::AmbiguousBranch {
+                variant: variant_id,
+                successors,
+            }),
+        }
+    }
+
+    /// Returns every scene number given out, in script order, including the
+    /// numbers of removed scenes. Empty until the scenes are numbered.
+    pub fn scene_numbers(&self) -> &[NumberedScene] {
+        &self.scene_numbers
+    }
+
+    /// Returns the number of the given scene, if it has one.
+    pub fn scene_number(&self, scene_id: &Id<Scene>) -> Option<&SceneNumber> {
+        self.scene_numbers
+            .iter()
+            .find(|n| n.scene() == Some(*scene_id))
+            .map(NumberedScene::number)
+    }
+
+    /// Numbers the scenes on the path that starts at `root`, and freezes
+    /// the numbers.
+    ///
+    /// The first time, the scenes are numbered `1`, `2`, `3`, and so on in
+    /// path order. From then on every number stays with its scene, even if
+    /// the scene moves or is removed, and numbering again only gives scenes
+    /// new to the path insert numbers after the numbered scene before them:
+    /// a scene between 12 and 13 becomes `12A`, one between 12 and an
+    /// existing 12A becomes `A12A`, and one before the first scene becomes
+    /// `A1`. The path is worked out as in
+    /// [`Narrative::linearize_from`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the error from [`Narrative::linearize_from`] if the path
+    /// cannot be worked out. Nothing is changed.
+    ///
+    /// # Updates
+    ///
+    /// A [`NarrativeUpdate::ScenesNumbered`], or none if every scene on the
+    /// path was already numbered.
+    pub fn number_scenes(
+        &mut self,
+        root: Id<SceneVariant>,
+        policy: &BranchPolicy,
+    ) -> Result<Vec<NarrativeUpdate>, NarrativeError> {
+        let path: Vec<_> = self
+            .linearize_from(root, policy)?
+            .into_iter()
+            .map(|(scene, _)| scene.id())
+            .collect();
+
+        let mut numbers = self.scene_numbers.clone();
+        if numbers.is_empty() {
+            numbers = path
+                .iter()
+                .enumerate()
+                .map(|(index, id)| NumberedScene::new(SceneNumber::nth(index + 1), Some(*id)))
+                .collect();
+        } else {
+            let mut previous = None;
+            for scene_id in path {
+                if let Some(index) = numbers.iter().position(|n| n.scene() == Some(scene_id)) {
+                    previous = Some(index);
+                    continue;
+                }
+
+                let index = match previous {
+                    // Inserts go after any numbers omitted since the previous scene.
+                    Some(previous) => (previous + 1..numbers.len())
+                        .find(|&i| !self.is_omitted(&numbers[i]))
+                        .unwrap_or(numbers.len()),
+                    None => 0,
+                };
+                let number = match previous {
+                    Some(previous) => insert_number(&numbers, previous, index),
+                    None => free_number(&numbers, &numbers[0], |n| n.next_before()),
+                };
+
+                numbers.insert(index, NumberedScene::new(number, Some(scene_id)));
+                previous = Some(index);
             }
         }
 
-        order.into_iter()
+        if numbers == self.scene_numbers {
+            return Ok(Vec::new());
+        }
+
+        let from = std::mem::replace(&mut self.scene_numbers, numbers);
+        Ok(vec![NarrativeUpdate::ScenesNumbered {
+            from,
+            to: self.scene_numbers.clone(),
+        }])
+    }
+
+    /// Drops every scene number, omitted ones included, so the script can
+    /// be numbered afresh.
+    ///
+    /// # Updates
+    ///
+    /// A [`NarrativeUpdate::ScenesNumbered`], or none if the scenes were not
+    /// numbered.
+    pub fn clear_scene_numbers(&mut self) -> Vec<NarrativeUpdate> {
+        if self.scene_numbers.is_empty() {
+            return Vec::new();
+        }
+
+        vec![NarrativeUpdate::ScenesNumbered {
+            from: std::mem::take(&mut self.scene_numbers),
+            to: Vec::new(),
+        }]
+    }
+
+    /// Returns the path through the story that starts at `root` as a
+    /// shooting script: each scene with its number, and an
+    /// [`ScriptScene::Omitted`] entry for each removed scene's number where
+    /// it falls between them.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error from [`Narrative::linearize_from`] if the path
+    /// cannot be worked out.
+    pub fn linearize_numbered(
+        &self,
+        root: Id<SceneVariant>,
+        policy: &BranchPolicy,
+    ) -> Result<Vec<ScriptScene<'_>>, NarrativeError> {
+        let omitted = |range: std::ops::Range<usize>| {
+            self.scene_numbers[range]
+                .iter()
+                .filter(|n| self.is_omitted(n))
+                .map(|n| ScriptScene::Omitted(n.number()))
+                .collect::<Vec<_>>()
+        };
+
+        let mut script = Vec::new();
+        // The first number not yet passed on the way through the path.
+        let mut next = 0;
+        for (scene, variant) in self.linearize_from(root, policy)? {
+            let index = self
+                .scene_numbers
+                .iter()
+                .position(|n| n.scene() == Some(scene.id()));
+            if let Some(index) = index {
+                script.extend(omitted(next.min(index)..index));
+                next = next.max(index + 1);
+            }
+
+            script.push(ScriptScene::Scene {
+                number: index.map(|i| self.scene_numbers[i].number()),
+                scene,
+                variant,
+            });
+        }
+        script.extend(omitted(next..self.scene_numbers.len()));
+
+        Ok(script)
+    }
+
+    /// Replaces the scene numbers, as read from an imported script.
+    pub(crate) fn set_scene_numbers(&mut self, numbers: Vec<NumberedScene>) {
+        self.scene_numbers = numbers;
+    }
+
+    /// Returns `true` if the number's scene is no longer in the narrative.
+    fn is_omitted(&self, number: &NumberedScene) -> bool {
+        number
+            .scene()
+            .is_none_or(|scene_id| !self.scenes.contains_key(&scene_id))
+    }
+
+    /// Makes exactly the change described by `update`.
+    ///
+    /// This replays an update previously emitted by the narrative, or the
+    /// [inverse](NarrativeUpdate::inverse) of one, and is what undo and redo
+    /// are built on. Graph updates are applied with [`SceneGraph::apply`] and
+    /// touch the affected scenes' metadata as the original operations do.
+    ///
+    /// # Errors
+    ///
+    /// - [`NarrativeError::Graph`] if a graph update cannot be applied.
+    /// - [`NarrativeError::SceneAlreadyExists`] when adding a scene that is
+    ///   already in the narrative.
+    /// - [`NarrativeError::UnknownScene`] when removing a scene that is not,
+    ///   or when changing the variants of a scene that is not.
+    /// - [`NarrativeError::Scene`] if a scene's variants cannot be changed as
+    ///   described.
+    /// - [`NarrativeError::MissingVariant`] or [`NarrativeError::Element`]
+    ///   if a variant's content cannot be changed as described.
+    /// - [`NarrativeError::Locked`] if the update would change a locked scene
+    ///   or variant, so a locked scene cannot be changed by undoing an edit.
+    ///
+    /// On failure the narrative is left unchanged.
+    pub fn apply_update(&mut self, update: &NarrativeUpdate) -> Result<(), NarrativeError> {
+        match update {
+            NarrativeUpdate::Graph(update) => self.check_unlocked(affected_variants(update))?,
+            NarrativeUpdate::SceneAdded(_) => {}
+            NarrativeUpdate::SceneRemoved(scene) => self.check_scene_unlocked(scene.id())?,
+            NarrativeUpdate::VariantAdded { scene, .. }
+            | NarrativeUpdate::VariantRemoved { scene, .. }
+            | NarrativeUpdate::ActiveVariantChanged { scene, .. } => {
+                self.check_scene_unlocked(*scene)?
+            }
+            NarrativeUpdate::ScenesNumbered { .. } => {}
+            NarrativeUpdate::Elements { variant, .. } => self.check_unlocked([*variant])?,
+        }
+
+        match update {
+            NarrativeUpdate::Graph(update) => {
+                self.graph.apply(update)?;
+                self.apply_scene_graph_update(update.clone());
+            }
+            NarrativeUpdate::SceneAdded(scene) => {
+                if self.scenes.contains_key(&scene.id()) {
+                    return Err(NarrativeError::SceneAlreadyExists(scene.id()));
+                }
+                self.scenes.insert(scene.id(), scene.as_ref().clone());
+            }
+            NarrativeUpdate::SceneRemoved(scene) => {
+                if self.scenes.remove(&scene.id()).is_none() {
+                    return Err(NarrativeError::UnknownScene(scene.id()));
+                }
+            }
+            NarrativeUpdate::VariantAdded { scene, variant } => {
+                self.scene_mut(*scene)?
+                    .insert_variant(variant.as_ref().clone())?;
+            }
+            NarrativeUpdate::VariantRemoved { scene, variant } => {
+                self.scene_mut(*scene)?.remove_variant(&variant.id())?;
+            }
+            NarrativeUpdate::ActiveVariantChanged { scene, to, .. } => {
+                self.scene_mut(*scene)?.set_active_variant(*to)?;
+            }
+            NarrativeUpdate::ScenesNumbered { to, .. } => self.scene_numbers = to.clone(),
+            NarrativeUpdate::Elements { variant, update } => {
+                self.variant_mut(variant)
+                    .ok_or(NarrativeError::MissingVariant(*variant))?
+                    .apply_update(update)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Applies a sequence of updates in order, as a unit.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error from the first update that cannot be applied. The
+    /// updates applied before it are reversed, so on failure the narrative's
+    /// structure is left as it was.
+    pub fn apply_updates(&mut self, updates: &[NarrativeUpdate]) -> Result<(), NarrativeError> {
+        for (index, update) in updates.iter().enumerate() {
+            if let Err(error) = self.apply_update(update) {
+                for applied in updates[..index].iter().rev() {
+                    // Reversing updates that were just applied cannot fail.
+                    let _ = self.apply_update(&applied.inverse());
+                }
+                return Err(error);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Removes every line spoken by a character `keep` returns `false` for,
+    /// past any lock.
+    pub(crate) fn remove_lines_unless(&mut self, keep: impl Fn(Id<Character>) -> bool) {
+        for variant in self
+            .scenes
+            .values_mut()
+            .flat_map(|scene| scene.variants_mut().values_mut())
+        {
+            variant.retain_elements(|element| match element {
+                SceneElement::Dialogue(dialogue) => keep(dialogue.speaker()),
+                SceneElement::Action(_) => true,
+            });
+        }
+    }
+
+    /// Clears the lock on every scene, variant, and dialogue, returning what
+    /// was locked.
+    pub(crate) fn suspend_locks(&mut self) -> HashSet<LockedEntity> {
+        let mut locked = HashSet::new();
+        self.visit_metadata(|entity, metadata| {
+            if std::mem::take(&mut metadata.locked) {
+                locked.insert(entity);
+            }
+        });
+
+        locked
+    }
+
+    /// Puts back the locks [`Narrative::suspend_locks`] cleared, on whatever
+    /// is still there.
+    pub(crate) fn restore_locks(&mut self, locked: &HashSet<LockedEntity>) {
+        self.visit_metadata(|entity, metadata| metadata.locked |= locked.contains(&entity));
+    }
+
+    /// Calls `visit` with the metadata of every scene, variant, and dialogue.
+    fn visit_metadata(&mut self, mut visit: impl FnMut(LockedEntity, &mut Metadata)) {
+        for scene in self.scenes.values_mut() {
+            visit(LockedEntity::Scene(scene.id()), scene.metadata_mut());
+            for variant in scene.variants_mut().values_mut() {
+                visit(LockedEntity::Variant(variant.id()), variant.metadata_mut());
+                for dialogue in variant.dialogues_mut() {
+                    visit(
+                        LockedEntity::Dialogue(dialogue.id()),
+                        dialogue.metadata_mut(),
+                    );
+                }
+            }
+        }
+    }
+
+    /// Returns [`NarrativeError::Locked`] if the scene is locked. A scene
+    /// not in the narrative is left for the caller to report.
+    fn check_scene_unlocked(&self, scene_id: Id<Scene>) -> Result<(), NarrativeError> {
+        match self.scenes.get(&scene_id) {
+            Some(scene) if scene.is_locked() => {
+                Err(NarrativeError::Locked(LockedEntity::Scene(scene_id)))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns [`NarrativeError::Locked`] if any of the variants, or the
+    /// scene that owns it, is locked.
+    fn check_unlocked(
+        &self,
+        variants: impl IntoIterator<Item = Id<SceneVariant>>,
+    ) -> Result<(), NarrativeError> {
+        for variant_id in variants {
+            let Some(scene) = self.scene_for_variant(&variant_id) else {
+                continue;
+            };
+            if scene.is_locked() {
+                return Err(NarrativeError::Locked(LockedEntity::Scene(scene.id())));
+            }
+            if scene.variants()[&variant_id].is_locked() {
+                return Err(NarrativeError::Locked(LockedEntity::Variant(variant_id)));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// As [`Narrative::check_unlocked`], for the variants and every variant
+    /// linked to them, whose edges would change if the variants were taken
+    /// out of the graph.
+    fn check_unlocked_with_neighbours(
+        &self,
+        variants: impl IntoIterator<Item = Id<SceneVariant>>,
+    ) -> Result<(), NarrativeError> {
+        let variants: Vec<_> = variants.into_iter().collect();
+        let neighbours: Vec<_> = variants
+            .iter()
+            .flat_map(|v| {
+                self.graph
+                    .next_variants(*v)
+                    .chain(self.graph.previous_variants(*v))
+            })
+            .collect();
+
+        self.check_unlocked(variants.into_iter().chain(neighbours))
+    }
+
+    /// Gives every line `from` speaks, in any variant, to `to`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`NarrativeError::Locked`] if any of the dialogues, or the
+    /// variant or scene holding it, is locked. Nothing is changed.
+    pub(crate) fn reassign_speaker(
+        &mut self,
+        from: Id<Character>,
+        to: Id<Character>,
+    ) -> Result<(), NarrativeError> {
+        for scene in self.scenes.values() {
+            for variant in scene.variants().values() {
+                for element in variant.elements() {
+                    let SceneElement::Dialogue(dialogue) = element else {
+                        continue;
+                    };
+                    if dialogue.speaker() != from {
+                        continue;
+                    }
+                    if scene.is_locked() {
+                        return Err(NarrativeError::Locked(LockedEntity::Scene(scene.id())));
+                    }
+                    if variant.is_locked() {
+                        return Err(NarrativeError::Locked(LockedEntity::Variant(variant.id())));
+                    }
+                    if dialogue.is_locked() {
+                        return Err(NarrativeError::Locked(LockedEntity::Dialogue(
+                            dialogue.id(),
+                        )));
+                    }
+                }
+            }
+        }
+
+        self.scenes
+            .values_mut()
+            .flat_map(|scene| scene.variants_mut().values_mut())
+            .flat_map(|variant| variant.dialogues_mut())
+            .filter(|dialogue| dialogue.speaker() == from)
+            .for_each(|dialogue| dialogue.set_speaker(to));
+
+        Ok(())
+    }
+
+    fn scene_mut(&mut self, scene_id: Id<Scene>) -> Result<&mut Scene, NarrativeError> {
+        self.scenes
+            .get_mut(&scene_id)
+            .ok_or(NarrativeError::UnknownScene(scene_id))
     }
 
     /// Applies a structural update emitted by the scene graph.
@@ -357,3 +1514,555 @@ impl Narrative {
         }
     }
 }
+
+/// Returns the variants whose scenes a graph update changes.
+fn affected_variants(update: &SceneGraphUpdate) -> Vec<Id<SceneVariant>> {
+    match *update {
+        SceneGraphUpdate::Move { variant, src, dest } => vec![variant, src, dest],
+        SceneGraphUpdate::SceneVariantAdded(variant)
+        | SceneGraphUpdate::SceneVariantRemoved(variant)
+        | SceneGraphUpdate::SceneVariantSetAsRoot(variant)
+        | SceneGraphUpdate::SceneVariantRemovedAsRoot(variant) => vec![variant],
+        SceneGraphUpdate::EdgeAdded { src, dest } | SceneGraphUpdate::EdgeRemoved { src, dest } => {
+            vec![src, dest]
+        }
+    }
+}
+
+/// Returns the number for a scene inserted at `index`, after the numbered
+/// scene at `previous`.
+///
+/// The insert takes the first free number after the previous scene's,
+/// stepping past numbers omitted in between: after 12 comes 12A, or 12B if
+/// 12A was omitted. If that number is held further on, as when 12A is the
+/// next scene, any letter after 12 would print out of order, so the insert
+/// is numbered before the scene that follows instead: `A12A`.
+fn insert_number(numbers: &[NumberedScene], previous: usize, index: usize) -> SceneNumber {
+    let mut number = numbers[previous].number().next_after();
+    while let Some(holder) = numbers.iter().position(|n| n.number() == &number) {
+        if holder >= index {
+            return free_number(numbers, &numbers[index], |n| n.next_before());
+        }
+        number = number.next_after();
+    }
+
+    number
+}
+
+/// Returns the first number from `start`, stepping with `next`, that no scene
+/// has been given yet.
+fn free_number(
+    numbers: &[NumberedScene],
+    start: &NumberedScene,
+    next: impl Fn(&SceneNumber) -> SceneNumber,
+) -> SceneNumber {
+    let mut number = next(start.number());
+    while numbers.iter().any(|n| n.number() == &number) {
+        number = next(&number);
+    }
+
+    number
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashMap, HashSet};
+
+    use crate::models::{
+        BranchPolicy, Id, LockedEntity, Narrative, NarrativeError, NarrativeUpdate, RevisionNote,
+        Scene, SceneAction, SceneElement, SceneError, SceneGraphError, SceneGraphUpdate,
+        SceneVariant, ScriptScene, StoryPath,
+    };
+
+    /// Three scenes linked in a chain from a root through the graph API.
+    fn generate_test_components() -> (Narrative, Vec<Id<SceneVariant>>) {
+        let mut narrative = Narrative::default();
+        let mut variants = Vec::new();
+        for _ in 0..3 {
+            let scene = Scene::new();
+            variants.push(*scene.active_variant());
+            narrative.add_scene(scene).unwrap();
+        }
+        narrative.set_variant_as_root(variants[0]).unwrap();
+        narrative.link_variants(variants[0], variants[1]).unwrap();
+        narrative.link_variants(variants[1], variants[2]).unwrap();
+
+        (narrative, variants)
+    }
+
+    /// Adds a second, inactive variant to the scene that owns `variant_id`
+    /// and links it after `src`.
+    fn add_alternate(
+        narrative: &mut Narrative,
+        variant_id: Id<SceneVariant>,
+        src: Id<SceneVariant>,
+    ) -> Id<SceneVariant> {
+        let scene_id = narrative.scene_for_variant(&variant_id).unwrap().id();
+        let mut scene = narrative.scene(&scene_id).unwrap().clone();
+        let alternate = SceneVariant::new();
+        let alternate_id = alternate.id();
+        scene.variants_mut().insert(alternate_id, alternate);
+
+        narrative.remove_scene(scene_id).unwrap();
+        narrative.add_scene(scene).unwrap();
+        narrative.link_variants(src, alternate_id).unwrap();
+        alternate_id
+    }
+
+    fn path_ids(
+        narrative: &Narrative,
+        root: Id<SceneVariant>,
+        policy: &BranchPolicy,
+    ) -> Result<Vec<Id<SceneVariant>>, NarrativeError> {
+        Ok(narrative
+            .linearize_from(root, policy)?
+            .into_iter()
+            .map(|(_, variant)| variant.id())
+            .collect())
+    }
+
+    #[test]
+    fn test_linearizing_follows_graph_edges() {
+        // ARRANGE
+        let (narrative, variants) = generate_test_components();
+        // ACT
+        let response = path_ids(&narrative, variants[0], &BranchPolicy::default());
+        // ASSERT
+        assert_eq!(response, Ok(variants));
+    }
+
+    #[test]
+    fn test_linearizing_prefers_the_active_variant_at_a_branch() {
+        // ARRANGE
+        let (mut narrative, variants) = generate_test_components();
+        add_alternate(&mut narrative, variants[1], variants[0]);
+        narrative.link_variants(variants[0], variants[1]).unwrap();
+        narrative.link_variants(variants[1], variants[2]).unwrap();
+        // ACT
+        let response = path_ids(&narrative, variants[0], &BranchPolicy::PreferActive);
+        // ASSERT
+        assert_eq!(response, Ok(variants));
+    }
+
+    #[test]
+    fn test_linearizing_follows_explicit_choices() {
+        // ARRANGE
+        let (mut narrative, variants) = generate_test_components();
+        narrative.unlink_variants(variants[0], variants[1]).unwrap();
+        narrative.link_variants(variants[0], variants[2]).unwrap();
+        narrative.link_variants(variants[0], variants[1]).unwrap();
+        let policy = BranchPolicy::Choices(HashMap::from([(variants[0], variants[2])]));
+        // ACT
+        let response = path_ids(&narrative, variants[0], &policy);
+        // ASSERT
+        assert_eq!(response, Ok(vec![variants[0], variants[2]]));
+    }
+
+    #[test]
+    fn test_branching_to_different_scenes_is_ambiguous() {
+        // ARRANGE
+        let (mut narrative, variants) = generate_test_components();
+        narrative.link_variants(variants[0], variants[2]).unwrap();
+        let mut successors = vec![variants[1], variants[2]];
+        successors.sort_by_key(|id| id.uuid());
+        // ACT
+        let response = path_ids(&narrative, variants[0], &BranchPolicy::PreferActive);
+        // ASSERT
+        assert_eq!(
+            response,
+            Err(NarrativeError::AmbiguousBranch {
+                variant: variants[0],
+                successors
+            })
+        );
+    }
+
+    #[test]
+    fn test_choosing_a_variant_that_does_not_follow_is_an_error() {
+        // ARRANGE
+        let (narrative, variants) = generate_test_components();
+        let policy = BranchPolicy::Choices(HashMap::from([(variants[0], variants[2])]));
+        // ACT
+        let response = path_ids(&narrative, variants[0], &policy);
+        // ASSERT
+        assert_eq!(
+            response,
+            Err(NarrativeError::InvalidChoice {
+                variant: variants[0],
+                choice: variants[2]
+            })
+        );
+    }
+
+    #[test]
+    fn test_linearizing_from_an_unknown_root_is_an_error() {
+        // ARRANGE
+        let (narrative, _) = generate_test_components();
+        let random_id = Id::new();
+        // ACT
+        let response = path_ids(&narrative, random_id, &BranchPolicy::default());
+        // ASSERT
+        assert_eq!(
+            response,
+            Err(NarrativeError::Graph(SceneGraphError::UnknownVariant(
+                random_id
+            )))
+        );
+    }
+
+    #[test]
+    fn test_unlinked_scene_is_standalone() {
+        // ARRANGE
+        let (mut narrative, _) = generate_test_components();
+        let scene = Scene::new();
+        let scene_id = scene.id();
+        narrative.add_scene(scene).unwrap();
+        // ACT
+        let standalone = narrative.standalone_scenes();
+        // ASSERT
+        assert_eq!(standalone, HashSet::from([scene_id]));
+    }
+
+    #[test]
+    fn test_scene_with_an_orphaned_variant_is_partly_reachable() {
+        // ARRANGE
+        let (mut narrative, variants) = generate_test_components();
+        let scene_id = narrative.scene_for_variant(&variants[1]).unwrap().id();
+        let alternate = add_alternate(&mut narrative, variants[1], variants[0]);
+        narrative.unlink_variants(variants[0], alternate).unwrap();
+        narrative.link_variants(variants[0], variants[1]).unwrap();
+        narrative.link_variants(variants[1], variants[2]).unwrap();
+        // ACT
+        let partial = narrative.partially_reachable_scenes();
+        // ASSERT
+        assert_eq!(
+            partial,
+            HashMap::from([(scene_id, HashSet::from([alternate]))])
+        );
+        assert!(narrative.standalone_scenes().is_empty());
+    }
+
+    #[test]
+    fn test_attaching_an_orphan_places_it_in_the_story() {
+        // ARRANGE
+        let (mut narrative, variants) = generate_test_components();
+        let scene = Scene::new();
+        let (scene_id, variant_id) = (scene.id(), *scene.active_variant());
+        narrative.add_scene(scene).unwrap();
+        // ACT
+        narrative.attach_scene(scene_id, variants[2]).unwrap();
+        // ASSERT
+        assert!(narrative.standalone_scenes().is_empty());
+        let path = path_ids(&narrative, variants[0], &BranchPolicy::default()).unwrap();
+        assert_eq!(path.last(), Some(&variant_id));
+    }
+
+    #[test]
+    fn test_attaching_a_placed_scene_is_an_error() {
+        // ARRANGE
+        let (mut narrative, variants) = generate_test_components();
+        let scene_id = narrative.scene_for_variant(&variants[2]).unwrap().id();
+        // ACT
+        let response = narrative.attach_scene(scene_id, variants[0]);
+        // ASSERT
+        assert_eq!(response, Err(NarrativeError::SceneAlreadyPlaced(scene_id)));
+    }
+
+    #[test]
+    fn test_parking_a_scene_bridges_the_story_around_it() {
+        // ARRANGE
+        let (mut narrative, variants) = generate_test_components();
+        let scene_id = narrative.scene_for_variant(&variants[1]).unwrap().id();
+        // ACT
+        narrative.park_scene(scene_id).unwrap();
+        // ASSERT
+        assert_eq!(narrative.standalone_scenes(), HashSet::from([scene_id]));
+        assert!(narrative.graph().contains(variants[1]));
+        let path = path_ids(&narrative, variants[0], &BranchPolicy::default()).unwrap();
+        assert_eq!(path, vec![variants[0], variants[2]]);
+    }
+
+    #[test]
+    fn test_parking_the_root_scene_promotes_its_successor() {
+        // ARRANGE
+        let (mut narrative, variants) = generate_test_components();
+        let scene_id = narrative.scene_for_variant(&variants[0]).unwrap().id();
+        // ACT
+        narrative.park_scene(scene_id).unwrap();
+        // ASSERT
+        assert_eq!(narrative.graph().roots(), &HashSet::from([variants[1]]));
+        let path = path_ids(&narrative, variants[1], &BranchPolicy::default()).unwrap();
+        assert_eq!(path, vec![variants[1], variants[2]]);
+    }
+
+    #[test]
+    fn test_parking_can_be_undone() {
+        // ARRANGE
+        let (mut narrative, variants) = generate_test_components();
+        let before = narrative.graph().clone();
+        let scene_id = narrative.scene_for_variant(&variants[1]).unwrap().id();
+        let updates = narrative.park_scene(scene_id).unwrap();
+        // ACT
+        let inverse: Vec<_> = updates.iter().rev().map(|u| u.inverse()).collect();
+        narrative.apply_updates(&inverse).unwrap();
+        // ASSERT
+        assert_eq!(narrative.graph(), &before);
+    }
+
+    #[test]
+    fn test_parking_a_parked_scene_is_an_error() {
+        // ARRANGE
+        let (mut narrative, variants) = generate_test_components();
+        let scene_id = narrative.scene_for_variant(&variants[1]).unwrap().id();
+        narrative.park_scene(scene_id).unwrap();
+        // ACT
+        let response = narrative.park_scene(scene_id);
+        // ASSERT
+        assert_eq!(response, Err(NarrativeError::SceneAlreadyParked(scene_id)));
+    }
+
+    #[test]
+    fn test_validating_reports_graph_variants_without_a_scene() {
+        // ARRANGE
+        let (mut narrative, _) = generate_test_components();
+        let stray = Id::new();
+        narrative.graph.add_variant(stray);
+        // ACT
+        let report = narrative.validate();
+        // ASSERT
+        assert_eq!(report.unowned_variants, vec![stray]);
+        assert!(report.cycles.is_empty());
+    }
+
+    #[test]
+    fn test_validating_reports_scene_variants_missing_from_the_graph() {
+        // ARRANGE
+        let (mut narrative, variants) = generate_test_components();
+        let scene_id = narrative.scene_for_variant(&variants[1]).unwrap().id();
+        let missing = SceneVariant::new();
+        let missing_id = missing.id();
+        narrative
+            .scene_mut(scene_id)
+            .unwrap()
+            .variants_mut()
+            .insert(missing_id, missing);
+        // ACT
+        let report = narrative.validate();
+        // ASSERT
+        assert_eq!(report.ungraphed_variants, vec![missing_id]);
+        assert!(report.unowned_variants.is_empty());
+        assert!(!report.is_valid());
+    }
+
+    #[test]
+    fn test_story_paths_pair_each_variant_with_its_scene() {
+        // ARRANGE
+        let (mut narrative, variants) = generate_test_components();
+        narrative.link_variants(variants[0], variants[2]).unwrap();
+        let scene_of = |v| narrative.scene_for_variant(&v).unwrap().id();
+        let expected_long: Vec<_> = variants.iter().map(|v| (scene_of(*v), *v)).collect();
+        // ACT
+        let paths = narrative.story_paths_limited(10);
+        // ASSERT
+        assert_eq!(paths.len(), 2);
+        assert!(paths.contains(&expected_long));
+        assert_eq!(narrative.count_story_paths(), 2);
+        assert_eq!(narrative.story_paths_limited(1).len(), 1);
+    }
+
+    #[test]
+    fn test_longest_and_shortest_story_paths_by_scene_count() {
+        // ARRANGE
+        let (mut narrative, variants) = generate_test_components();
+        narrative.link_variants(variants[0], variants[2]).unwrap();
+        // ACT
+        let longest = narrative.longest_story_path(|_| 1).unwrap();
+        let shortest = narrative.shortest_story_path(|_| 1).unwrap();
+        // ASSERT
+        let ids = |path: StoryPath| path.into_iter().map(|(_, v)| v).collect::<Vec<_>>();
+        assert_eq!(ids(longest), variants);
+        assert_eq!(ids(shortest), vec![variants[0], variants[2]]);
+    }
+
+    #[test]
+    fn test_forking_registers_the_fork_in_the_graph() {
+        // ARRANGE
+        let (mut narrative, variants) = generate_test_components();
+        let scene_id = narrative.scene_for_variant(&variants[1]).unwrap().id();
+        // ACT
+        let (fork, updates) = narrative.fork_variant(scene_id, variants[1]).unwrap();
+        // ASSERT
+        assert!(narrative.graph().contains(fork));
+        assert!(narrative.scene(&scene_id).unwrap().has_variant(&fork));
+        assert_eq!(updates.len(), 2);
+        assert_eq!(
+            updates[1],
+            NarrativeUpdate::Graph(SceneGraphUpdate::SceneVariantAdded(fork))
+        );
+    }
+
+    #[test]
+    fn test_activating_a_variant_twice_fails() {
+        // ARRANGE
+        let (mut narrative, variants) = generate_test_components();
+        let scene_id = narrative.scene_for_variant(&variants[1]).unwrap().id();
+        let (fork, _) = narrative.fork_variant(scene_id, variants[1]).unwrap();
+        // ACT
+        let first = narrative.set_active_variant(scene_id, fork);
+        let second = narrative.set_active_variant(scene_id, fork);
+        // ASSERT
+        assert_eq!(
+            first,
+            Ok(NarrativeUpdate::ActiveVariantChanged {
+                scene: scene_id,
+                from: variants[1],
+                to: fork,
+            })
+        );
+        assert_eq!(second, Err(NarrativeError::VariantAlreadyActive(fork)));
+    }
+
+    #[test]
+    fn test_removing_a_variant_unlinks_it_and_can_be_undone() {
+        // ARRANGE
+        let (mut narrative, variants) = generate_test_components();
+        let scene_id = narrative.scene_for_variant(&variants[1]).unwrap().id();
+        let (fork, _) = narrative.fork_variant(scene_id, variants[1]).unwrap();
+        narrative.link_variants(variants[0], fork).unwrap();
+        // ACT
+        let updates = narrative.remove_variant(scene_id, fork).unwrap();
+        let removed = !narrative.graph().contains(fork)
+            && narrative.graph().next_variants(variants[0]).count() == 1;
+        let inverse: Vec<_> = updates.iter().rev().map(|u| u.inverse()).collect();
+        narrative.apply_updates(&inverse).unwrap();
+        // ASSERT
+        assert!(removed);
+        assert!(narrative.scene(&scene_id).unwrap().has_variant(&fork));
+        assert!(
+            narrative
+                .graph()
+                .next_variants(variants[0])
+                .any(|v| v == fork)
+        );
+    }
+
+    #[test]
+    fn test_removing_the_active_variant_fails_without_changes() {
+        // ARRANGE
+        let (mut narrative, variants) = generate_test_components();
+        let scene_id = narrative.scene_for_variant(&variants[1]).unwrap().id();
+        narrative.fork_variant(scene_id, variants[1]).unwrap();
+        // ACT
+        let response = narrative.remove_variant(scene_id, variants[1]);
+        // ASSERT
+        assert_eq!(
+            response,
+            Err(NarrativeError::Scene(SceneError::ActiveVariant(
+                variants[1]
+            )))
+        );
+        assert!(
+            narrative
+                .graph()
+                .next_variants(variants[1])
+                .any(|v| v == variants[2])
+        );
+    }
+
+    #[test]
+    fn test_variants_can_be_edited_in_place() {
+        // ARRANGE
+        let (mut narrative, variants) = generate_test_components();
+        let action = SceneElement::Action(SceneAction::new("Rain.").unwrap());
+        // ACT
+        narrative
+            .variant_mut(&variants[1])
+            .unwrap()
+            .insert_element(0, action.clone())
+            .unwrap();
+        // ASSERT
+        let scene = narrative.scene_for_variant(&variants[1]).unwrap();
+        assert_eq!(scene.variants()[&variants[1]].elements(), &[action]);
+        assert!(narrative.variant_mut(&Id::new()).is_none());
+    }
+
+    #[test]
+    fn test_a_locked_scene_cannot_be_unlinked_removed_or_changed_by_undo() {
+        // ARRANGE
+        let (mut narrative, variants) = generate_test_components();
+        let scene_id = narrative.scene_for_variant(&variants[1]).unwrap().id();
+        let unlinked = narrative.unlink_variants(variants[1], variants[2]).unwrap();
+        let note = |text: &str| RevisionNote::new(text).unwrap();
+        narrative
+            .lock_scene(scene_id, note("Locked for the table read."))
+            .unwrap();
+        let locked = || NarrativeError::Locked(LockedEntity::Scene(scene_id));
+        // ACT & ASSERT
+        assert_eq!(
+            narrative.unlink_variants(variants[0], variants[1]),
+            Err(locked())
+        );
+        assert_eq!(narrative.remove_scene(scene_id), Err(locked()));
+        assert_eq!(narrative.apply_update(&unlinked.inverse()), Err(locked()));
+        assert_eq!(narrative.graph().next_variants(variants[0]).count(), 1);
+        assert!(
+            narrative
+                .variant_mut(&variants[1])
+                .unwrap()
+                .set_summary(Default::default())
+                .is_err()
+        );
+
+        narrative
+            .unlock_scene(scene_id, note("Table read done."))
+            .unwrap();
+        assert!(narrative.unlink_variants(variants[0], variants[1]).is_ok());
+    }
+
+    #[test]
+    fn test_frozen_numbers_give_inserts_letters_and_keep_omitted_scenes() {
+        // ARRANGE
+        let (mut narrative, variants) = generate_test_components();
+        let policy = BranchPolicy::default();
+        narrative.number_scenes(variants[0], &policy).unwrap();
+        let inserted = Scene::new();
+        let (inserted_id, inserted_variant) = (inserted.id(), *inserted.active_variant());
+        narrative.add_scene(inserted).unwrap();
+        narrative.unlink_variants(variants[0], variants[1]).unwrap();
+        narrative
+            .link_variants(variants[0], inserted_variant)
+            .unwrap();
+        narrative
+            .link_variants(inserted_variant, variants[1])
+            .unwrap();
+        let last = narrative.scene_for_variant(&variants[2]).unwrap().id();
+        let removal = narrative.remove_scene(last).unwrap();
+        // ACT
+        let updates = narrative.number_scenes(variants[0], &policy).unwrap();
+        let script: Vec<_> = narrative
+            .linearize_numbered(variants[0], &policy)
+            .unwrap()
+            .into_iter()
+            .map(|entry| match entry {
+                ScriptScene::Scene { number, .. } => number.unwrap().to_string(),
+                ScriptScene::Omitted(number) => format!("{number} OMITTED"),
+            })
+            .collect();
+        // ASSERT
+        assert_eq!(updates.len(), 1);
+        assert_eq!(script, ["1", "1A", "2", "3 OMITTED"]);
+        assert_eq!(narrative.scene_number(&inserted_id).unwrap().as_str(), "1A");
+        assert!(
+            narrative
+                .number_scenes(variants[0], &policy)
+                .unwrap()
+                .is_empty()
+        );
+        for update in removal.iter().rev() {
+            narrative.apply_update(&update.inverse()).unwrap();
+        }
+        assert_eq!(narrative.scene_number(&last).unwrap().as_str(), "3");
+        narrative.apply_update(&updates[0].inverse()).unwrap();
+        assert!(narrative.scene_number(&inserted_id).is_none());
+    }
+}
@@ -0,0 +1,240 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::models::{
+    Id,
+    character::Character,
+    metadata::Metadata,
+    narrative::NarrativeError,
+    scene::{Scene, SceneVariant},
+    summary::Summary,
+};
+
+/// Errors that can occur while working with the relationships of a
+/// [`Storyboard`](crate::models::Storyboard).
+#[derive(Debug, Serialize, PartialEq)]
+pub enum RelationshipError {
+    /// The referenced relationship does not exist in the storyboard.
+    UnknownRelationship(Id<Relationship>),
+    /// A character the relationship names is not in the storyboard.
+    UnknownCharacter(Id<Character>),
+    /// A scene bounding the relationship is not in the narrative, or the
+    /// scene asked about is not on the path.
+    UnknownScene(Id<Scene>),
+    /// A character cannot be related to themselves.
+    SelfRelationship(Id<Character>),
+    /// The path could not be worked out, or the storyboard is locked.
+    Narrative(NarrativeError),
+}
+
+impl From<NarrativeError> for RelationshipError {
+    fn from(value: NarrativeError) -> Self {
+        RelationshipError::Narrative(value)
+    }
+}
+
+/// How one character stands to another.
+///
+/// Kinds read from the first character to the second: a `Parent`
+/// relationship from A to B means A is B's parent.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum RelationshipKind {
+    Parent,
+    Sibling,
+    Spouse,
+    Romantic,
+    Friend,
+    Ally,
+    Rival,
+    Enemy,
+    Mentor,
+    Employer,
+    /// Any other relationship, described by the relationship's note.
+    Other,
+}
+
+impl fmt::Display for RelationshipKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            RelationshipKind::Parent => "parent of",
+            RelationshipKind::Sibling => "sibling of",
+            RelationshipKind::Spouse => "spouse of",
+            RelationshipKind::Romantic => "romantically involved with",
+            RelationshipKind::Friend => "friend of",
+            RelationshipKind::Ally => "ally of",
+            RelationshipKind::Rival => "rival of",
+            RelationshipKind::Enemy => "enemy of",
+            RelationshipKind::Mentor => "mentor of",
+            RelationshipKind::Employer => "employer of",
+            RelationshipKind::Other => "related to",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// A directed relationship from one character to another.
+///
+/// A relationship holds for the whole story unless it is scoped to a span
+/// of scenes: it then begins at `starts` and holds until `ends`, which is
+/// the first scene it no longer holds in. Either bound may be left open.
+/// Where a relationship stands at a scene depends on the path taken through
+/// the story; see [`Storyboard::relationships_as_of`](crate::models::Storyboard::relationships_as_of).
+/// Removing a bounding scene breaks the relationship; see
+/// [`Storyboard::relationships_broken_by_removing`](crate::models::Storyboard::relationships_broken_by_removing).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Relationship {
+    id: Id<Self>,
+    from: Id<Character>,
+    to: Id<Character>,
+    kind: RelationshipKind,
+    #[serde(default)]
+    note: Summary,
+    #[serde(default)]
+    starts: Option<Id<Scene>>,
+    #[serde(default)]
+    ends: Option<Id<Scene>>,
+    metadata: Metadata,
+}
+
+impl Relationship {
+    pub fn new(from: Id<Character>, to: Id<Character>, kind: RelationshipKind) -> Self {
+        Self {
+            id: Id::new(),
+            from,
+            to,
+            kind,
+            note: Summary::default(),
+            starts: None,
+            ends: None,
+            metadata: Metadata::new(),
+        }
+    }
+
+    pub fn id(&self) -> Id<Self> {
+        self.id
+    }
+
+    pub fn from(&self) -> Id<Character> {
+        self.from
+    }
+
+    pub fn to(&self) -> Id<Character> {
+        self.to
+    }
+
+    pub fn kind(&self) -> RelationshipKind {
+        self.kind
+    }
+
+    pub fn note(&self) -> &Summary {
+        &self.note
+    }
+
+    pub fn set_note(&mut self, note: Summary) {
+        self.note = note;
+    }
+
+    /// Returns the scene the relationship begins at, if it is scoped.
+    pub fn starts(&self) -> Option<Id<Scene>> {
+        self.starts
+    }
+
+    /// Returns the first scene the relationship no longer holds in, if it is scoped.
+    pub fn ends(&self) -> Option<Id<Scene>> {
+        self.ends
+    }
+
+    /// Scopes the relationship to the scenes from `starts` up to, but not
+    /// including, `ends`. `None` leaves that side open.
+    pub fn set_span(&mut self, starts: Option<Id<Scene>>, ends: Option<Id<Scene>>) {
+        self.starts = starts;
+        self.ends = ends;
+    }
+
+    /// Returns `true` if `character` is either side of the relationship.
+    pub fn involves(&self, character: Id<Character>) -> bool {
+        self.from == character || self.to == character
+    }
+
+    /// Returns `true` if the relationship holds at position `at` of `path`.
+    ///
+    /// A start not on the path is never reached, so the relationship does
+    /// not hold; an end not on the path is never reached either, so it does
+    /// not end.
+    pub(crate) fn holds_at(&self, path: &[(&Scene, &SceneVariant)], at: usize) -> bool {
+        let position = |scene: Id<Scene>| path.iter().position(|(s, _)| s.id() == scene);
+        let started = self
+            .starts
+            .is_none_or(|starts| position(starts).is_some_and(|i| i <= at));
+        let ended = self.ends.and_then(position).is_some_and(|i| i <= at);
+
+        started && !ended
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::{Id, Relationship, RelationshipKind, Scene, SceneVariant};
+
+    fn generate_test_components() -> Vec<(Scene, SceneVariant)> {
+        (0..3)
+            .map(|_| {
+                let variant = SceneVariant::new();
+                let mut scene = Scene::new();
+                scene.insert_variant(variant.clone()).unwrap();
+                (scene, variant)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_scoped_relationships_hold_from_their_start_until_their_end() {
+        // ARRANGE
+        let scenes = generate_test_components();
+        let path: Vec<_> = scenes.iter().map(|(s, v)| (s, v)).collect();
+        let mut rivals = Relationship::new(Id::new(), Id::new(), RelationshipKind::Rival);
+        let mut elsewhere = rivals.clone();
+        // ACT
+        rivals.set_span(Some(scenes[1].0.id()), Some(scenes[2].0.id()));
+        elsewhere.set_span(Some(Scene::new().id()), None);
+        // ASSERT
+        let holds: Vec<_> = (0..3).map(|i| rivals.holds_at(&path, i)).collect();
+        assert_eq!(holds, [false, true, false]);
+        assert!(!elsewhere.holds_at(&path, 2));
+    }
+
+    #[test]
+    fn test_open_bounds_hold_to_the_edge_of_the_story() {
+        // ARRANGE
+        let scenes = generate_test_components();
+        let path: Vec<_> = scenes.iter().map(|(s, v)| (s, v)).collect();
+        let mut until = Relationship::new(Id::new(), Id::new(), RelationshipKind::Friend);
+        let mut from = until.clone();
+        // ACT
+        until.set_span(None, Some(scenes[1].0.id()));
+        from.set_span(Some(scenes[1].0.id()), None);
+        // ASSERT
+        let holds = |r: &Relationship| (0..3).map(|i| r.holds_at(&path, i)).collect::<Vec<_>>();
+        assert_eq!(holds(&until), [true, false, false]);
+        assert_eq!(holds(&from), [false, true, true]);
+    }
+
+    #[test]
+    fn test_bounds_on_another_branch_are_never_reached() {
+        // ARRANGE
+        let scenes = generate_test_components();
+        // The path skips the middle scene, as if it were on another branch.
+        let path: Vec<_> = [&scenes[0], &scenes[2]]
+            .into_iter()
+            .map(|(s, v)| (s, v))
+            .collect();
+        let mut starts = Relationship::new(Id::new(), Id::new(), RelationshipKind::Enemy);
+        let mut ends = starts.clone();
+        // ACT
+        starts.set_span(Some(scenes[1].0.id()), None);
+        ends.set_span(None, Some(scenes[1].0.id()));
+        // ASSERT
+        assert!(!starts.holds_at(&path, 0) && !starts.holds_at(&path, 1));
+        assert!(ends.holds_at(&path, 0) && ends.holds_at(&path, 1));
+    }
+}
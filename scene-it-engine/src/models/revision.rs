@@ -0,0 +1,412 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+};
+use time::OffsetDateTime;
+
+use crate::{
+    models::{
+        Id, metadata::RevisionNote, narrative::Narrative, scene::SceneVariant,
+        scene_element::SceneElement,
+    },
+    utils::fnv1a,
+};
+
+/// The paper colors of production revisions, in the order they are issued.
+///
+/// The first locked draft is printed on white; each revision set after it
+/// goes out on the next color. After Cherry the colors are issued again as
+/// doubles, from Double White to Double Cherry.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum RevisionColor {
+    White,
+    Blue,
+    Pink,
+    Yellow,
+    Green,
+    Goldenrod,
+    Buff,
+    Salmon,
+    Cherry,
+    DoubleWhite,
+    DoubleBlue,
+    DoublePink,
+    DoubleYellow,
+    DoubleGreen,
+    DoubleGoldenrod,
+    DoubleBuff,
+    DoubleSalmon,
+    DoubleCherry,
+}
+
+impl RevisionColor {
+    /// Returns the color the revision set after this one is issued on, or
+    /// `None` after Double Cherry, the last color.
+    pub fn next(self) -> Option<Self> {
+        let next = match self {
+            RevisionColor::White => RevisionColor::Blue,
+            RevisionColor::Blue => RevisionColor::Pink,
+            RevisionColor::Pink => RevisionColor::Yellow,
+            RevisionColor::Yellow => RevisionColor::Green,
+            RevisionColor::Green => RevisionColor::Goldenrod,
+            RevisionColor::Goldenrod => RevisionColor::Buff,
+            RevisionColor::Buff => RevisionColor::Salmon,
+            RevisionColor::Salmon => RevisionColor::Cherry,
+            RevisionColor::Cherry => RevisionColor::DoubleWhite,
+            RevisionColor::DoubleWhite => RevisionColor::DoubleBlue,
+            RevisionColor::DoubleBlue => RevisionColor::DoublePink,
+            RevisionColor::DoublePink => RevisionColor::DoubleYellow,
+            RevisionColor::DoubleYellow => RevisionColor::DoubleGreen,
+            RevisionColor::DoubleGreen => RevisionColor::DoubleGoldenrod,
+            RevisionColor::DoubleGoldenrod => RevisionColor::DoubleBuff,
+            RevisionColor::DoubleBuff => RevisionColor::DoubleSalmon,
+            RevisionColor::DoubleSalmon => RevisionColor::DoubleCherry,
+            RevisionColor::DoubleCherry => return None,
+        };
+        Some(next)
+    }
+}
+
+impl fmt::Display for RevisionColor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            RevisionColor::White => "White",
+            RevisionColor::Blue => "Blue",
+            RevisionColor::Pink => "Pink",
+            RevisionColor::Yellow => "Yellow",
+            RevisionColor::Green => "Green",
+            RevisionColor::Goldenrod => "Goldenrod",
+            RevisionColor::Buff => "Buff",
+            RevisionColor::Salmon => "Salmon",
+            RevisionColor::Cherry => "Cherry",
+            RevisionColor::DoubleWhite => "Double White",
+            RevisionColor::DoubleBlue => "Double Blue",
+            RevisionColor::DoublePink => "Double Pink",
+            RevisionColor::DoubleYellow => "Double Yellow",
+            RevisionColor::DoubleGreen => "Double Green",
+            RevisionColor::DoubleGoldenrod => "Double Goldenrod",
+            RevisionColor::DoubleBuff => "Double Buff",
+            RevisionColor::DoubleSalmon => "Double Salmon",
+            RevisionColor::DoubleCherry => "Double Cherry",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// A stable 64-bit FNV-1a hash of how something reads on the page.
+///
+/// Fingerprints are kept in saved projects, so they are taken over the
+/// serialized text rather than with [`std::hash::Hash`], whose output may
+/// change between Rust releases.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+struct Fingerprint(u64);
+
+impl Fingerprint {
+    fn of(text: &impl Serialize) -> Self {
+        let bytes = serde_json::to_vec(text).expect("Page text always serializes");
+        Self(fnv1a(&bytes))
+    }
+
+    /// Fingerprints the text of an element. Dialogue metadata is left out,
+    /// so touching a dialogue does not revise it.
+    fn of_element(element: &SceneElement) -> Self {
+        match element {
+            SceneElement::Action(action) => Self::of(&("Action", action.as_str())),
+            SceneElement::Dialogue(dialogue) => {
+                Self::of(&("Dialogue", dialogue.speaker(), dialogue.content()))
+            }
+        }
+    }
+
+    fn of_heading(variant: &SceneVariant) -> Self {
+        Self::of(&variant.heading())
+    }
+}
+
+/// Fingerprints of a variant's heading and elements as they stood when a
+/// revision set was opened.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+struct Baseline {
+    heading: Fingerprint,
+    elements: HashMap<Id<SceneElement>, Fingerprint>,
+    /// The elements in the order they stood in.
+    #[serde(default)]
+    order: Vec<Id<SceneElement>>,
+}
+
+impl Baseline {
+    /// Returns the elements of `variant` that have been moved since the
+    /// baseline was taken.
+    ///
+    /// The longest run of elements still in their baseline order stays put;
+    /// every other element that was already there has moved.
+    fn moved(&self, variant: &SceneVariant) -> HashSet<Id<SceneElement>> {
+        let positions: HashMap<_, _> = self
+            .order
+            .iter()
+            .enumerate()
+            .map(|(i, id)| (*id, i))
+            .collect();
+        let kept: Vec<_> = variant
+            .elements()
+            .iter()
+            .filter_map(|element| positions.get(&element.id()).map(|&p| (element.id(), p)))
+            .collect();
+
+        // Longest increasing run of baseline positions, by patience sorting.
+        let mut tails: Vec<usize> = Vec::new();
+        let mut previous = vec![None; kept.len()];
+        for (index, &(_, position)) in kept.iter().enumerate() {
+            let length = tails.partition_point(|&tail| kept[tail].1 < position);
+            previous[index] = length.checked_sub(1).map(|length| tails[length]);
+            match tails.get_mut(length) {
+                Some(tail) => *tail = index,
+                None => tails.push(index),
+            }
+        }
+
+        let mut in_order = HashSet::new();
+        let mut next = tails.last().copied();
+        while let Some(index) = next {
+            in_order.insert(kept[index].0);
+            next = previous[index];
+        }
+
+        kept.into_iter()
+            .map(|(id, _)| id)
+            .filter(|id| !in_order.contains(id))
+            .collect()
+    }
+}
+
+/// A production revision: everything changed in the script since the set
+/// was opened is issued together on pages of its color.
+///
+/// The set keeps a fingerprint of every variant's heading and of each
+/// element's text as they stood when it was opened, along with the order of
+/// the elements. An element counts as revised if it is new since then, its
+/// text has changed, or it has been moved. Variants added after the set was
+/// opened are revised throughout.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct RevisionSet {
+    color: RevisionColor,
+    note: RevisionNote,
+    #[serde(with = "time::serde::iso8601")]
+    opened_at: OffsetDateTime,
+    /// The storyboard's metadata version once the set was opened.
+    version: u32,
+    baselines: HashMap<Id<SceneVariant>, Baseline>,
+}
+
+impl RevisionSet {
+    /// Opens a set against the narrative as it now stands.
+    pub(crate) fn open(
+        color: RevisionColor,
+        note: RevisionNote,
+        version: u32,
+        narrative: &Narrative,
+    ) -> Self {
+        let baselines = narrative
+            .scenes()
+            .flat_map(|scene| scene.variants().values())
+            .map(|variant| {
+                let baseline = Baseline {
+                    heading: Fingerprint::of_heading(variant),
+                    elements: variant
+                        .elements()
+                        .iter()
+                        .map(|element| (element.id(), Fingerprint::of_element(element)))
+                        .collect(),
+                    order: variant.elements().iter().map(SceneElement::id).collect(),
+                };
+                (variant.id(), baseline)
+            })
+            .collect();
+
+        Self {
+            color,
+            note,
+            opened_at: OffsetDateTime::now_utc(),
+            version,
+            baselines,
+        }
+    }
+
+    pub fn color(&self) -> RevisionColor {
+        self.color
+    }
+
+    pub fn note(&self) -> &RevisionNote {
+        &self.note
+    }
+
+    pub fn opened_at(&self) -> OffsetDateTime {
+        self.opened_at
+    }
+
+    /// Returns the storyboard's metadata version once the set was opened.
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// Returns `true` if `variant`'s heading has changed since the set was opened.
+    pub fn is_heading_revised(&self, variant: &SceneVariant) -> bool {
+        self.baselines
+            .get(&variant.id())
+            .is_none_or(|baseline| baseline.heading != Fingerprint::of_heading(variant))
+    }
+
+    /// Returns `true` if `element` of `variant` is new since the set was
+    /// opened, its text has changed, or it has been moved.
+    pub fn is_element_revised(&self, variant: &SceneVariant, element: &SceneElement) -> bool {
+        let Some(baseline) = self.baselines.get(&variant.id()) else {
+            return true;
+        };
+
+        baseline
+            .elements
+            .get(&element.id())
+            .is_none_or(|old| *old != Fingerprint::of_element(element))
+            || baseline.moved(variant).contains(&element.id())
+    }
+
+    /// Returns `true` if anything in `variant` has changed since the set was
+    /// opened: its heading, any of its elements or their order, or elements
+    /// removed from it.
+    pub fn is_variant_revised(&self, variant: &SceneVariant) -> bool {
+        let Some(baseline) = self.baselines.get(&variant.id()) else {
+            return true;
+        };
+
+        self.is_heading_revised(variant)
+            || baseline.elements.len() != variant.elements().len()
+            || variant
+                .elements()
+                .iter()
+                .any(|element| self.is_element_revised(variant, element))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::{
+        RevisionColor, RevisionNote, RevisionSet, Scene, SceneAction, SceneElement, SceneVariant,
+        narrative::Narrative,
+    };
+
+    fn generate_test_components() -> (Narrative, SceneVariant, SceneElement) {
+        let mut variant = SceneVariant::new();
+        let action = SceneElement::Action(SceneAction::new("The door creaks.").unwrap());
+        variant.add_element(action.clone()).unwrap();
+
+        let mut scene = Scene::new();
+        scene.insert_variant(variant.clone()).unwrap();
+        let mut narrative = Narrative::default();
+        narrative.add_scene(scene).unwrap();
+
+        (narrative, variant, action)
+    }
+
+    #[test]
+    fn test_only_changed_elements_are_revised() {
+        // ARRANGE
+        let (narrative, mut variant, action) = generate_test_components();
+        let set = RevisionSet::open(
+            RevisionColor::Blue,
+            RevisionNote::new("Blue pages").unwrap(),
+            2,
+            &narrative,
+        );
+        let added = SceneElement::Action(SceneAction::new("It slams shut.").unwrap());
+        // ACT
+        variant.add_element(added.clone()).unwrap();
+        // ASSERT
+        assert!(!set.is_element_revised(&variant, &action));
+        assert!(set.is_element_revised(&variant, &added));
+        assert!(set.is_variant_revised(&variant));
+        assert!(!set.is_heading_revised(&variant));
+        assert!(set.is_variant_revised(&SceneVariant::new()));
+    }
+
+    #[test]
+    fn test_moved_elements_are_revised() {
+        // ARRANGE
+        let texts = ["The door creaks.", "It slams shut.", "A dog barks."];
+        let [creak, slam, bark] = texts.map(|t| SceneElement::Action(SceneAction::new(t).unwrap()));
+        let mut variant = SceneVariant::new();
+        for element in [&creak, &slam, &bark] {
+            variant.add_element(element.clone()).unwrap();
+        }
+        let mut scene = Scene::new();
+        scene.insert_variant(variant.clone()).unwrap();
+        let mut narrative = Narrative::default();
+        narrative.add_scene(scene).unwrap();
+        let set = RevisionSet::open(
+            RevisionColor::Blue,
+            RevisionNote::new("Blue pages").unwrap(),
+            2,
+            &narrative,
+        );
+        // ACT
+        variant.move_element(2, 0).unwrap();
+        // ASSERT
+        assert!(set.is_element_revised(&variant, &bark));
+        assert!(!set.is_element_revised(&variant, &creak));
+        assert!(!set.is_element_revised(&variant, &slam));
+        assert!(set.is_variant_revised(&variant));
+    }
+
+    #[test]
+    fn test_a_reloaded_set_still_knows_unchanged_text() {
+        // ARRANGE
+        let (narrative, mut variant, action) = generate_test_components();
+        let unchanged = variant.clone();
+        let set = RevisionSet::open(
+            RevisionColor::Blue,
+            RevisionNote::new("Blue pages").unwrap(),
+            2,
+            &narrative,
+        );
+        // ACT
+        let reloaded: RevisionSet =
+            serde_json::from_str(&serde_json::to_string(&set).unwrap()).unwrap();
+        variant.edit_action(action.id(), "The door slams.").unwrap();
+        // ASSERT
+        assert_eq!(reloaded, set);
+        assert!(!reloaded.is_variant_revised(&unchanged));
+        assert!(reloaded.is_element_revised(&variant, &variant.elements()[0]));
+    }
+
+    #[test]
+    fn test_colors_follow_the_industry_order() {
+        // ARRANGE & ACT
+        let colors: Vec<_> = std::iter::successors(Some(RevisionColor::White), |c| c.next())
+            .map(|c| c.to_string())
+            .collect();
+        // ASSERT
+        assert_eq!(
+            colors,
+            [
+                "White",
+                "Blue",
+                "Pink",
+                "Yellow",
+                "Green",
+                "Goldenrod",
+                "Buff",
+                "Salmon",
+                "Cherry",
+                "Double White",
+                "Double Blue",
+                "Double Pink",
+                "Double Yellow",
+                "Double Green",
+                "Double Goldenrod",
+                "Double Buff",
+                "Double Salmon",
+                "Double Cherry"
+            ]
+        );
+    }
+}
@@ -1,20 +1,250 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-use crate::models::{
-    Id,
-    metadata::{HasMetadata, Metadata},
-    scene_element::{SceneElement, SceneHeading},
-    summary::Summary,
+use crate::{
+    models::{
+        Id,
+        metadata::{HasMetadata, LockedEntity, Metadata, RevisionNote, Unlock},
+        scene_element::{Dialogue, DialogueBlock, SceneAction, SceneElement, SceneHeading},
+        summary::Summary,
+    },
+    utils::InputError,
 };
 
+/// Errors that can occur while managing the variants of a [`Scene`].
+#[derive(Debug, Serialize, PartialEq)]
+pub enum SceneError {
+    /// The referenced variant does not belong to the scene.
+    UnknownVariant(Id<SceneVariant>),
+    /// The variant is the scene's only one; a scene always keeps at least one.
+    LastVariant(Id<SceneVariant>),
+    /// The variant is the scene's active variant, so another must be made
+    /// active before it can be removed.
+    ActiveVariant(Id<SceneVariant>),
+    /// A variant with this ID already belongs to the scene.
+    VariantAlreadyExists(Id<SceneVariant>),
+    /// The scene, or the variant to be removed, is locked.
+    Locked(LockedEntity),
+}
+
+/// Addresses an element of a [`SceneVariant`] by its position or by its
+/// stable ID.
+///
+/// Both convert into an `ElementRef`, so the editing methods accept either
+/// an index or an element ID directly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ElementRef {
+    Index(usize),
+    Id(Id<SceneElement>),
+}
+
+impl From<usize> for ElementRef {
+    fn from(value: usize) -> Self {
+        ElementRef::Index(value)
+    }
+}
+
+impl From<Id<SceneElement>> for ElementRef {
+    fn from(value: Id<SceneElement>) -> Self {
+        ElementRef::Id(value)
+    }
+}
+
+/// Errors that can occur while editing the elements of a [`SceneVariant`].
+#[derive(Debug, Serialize, PartialEq)]
+pub enum ElementError {
+    /// No element is at this index.
+    IndexOutOfRange(usize),
+    /// No element in the variant has this ID.
+    UnknownElement(Id<SceneElement>),
+    /// An element with this ID is already in the variant.
+    ElementAlreadyExists(Id<SceneElement>),
+    /// The element is not an action.
+    NotAnAction(Id<SceneElement>),
+    /// The element is not dialogue.
+    NotDialogue(Id<SceneElement>),
+    /// The action has no element after it to merge with.
+    NothingToMerge(Id<SceneElement>),
+    /// The split point lies past the end of the action's text.
+    SplitOutOfRange {
+        element: Id<SceneElement>,
+        at: usize,
+    },
+    /// The dialogue has no block at this index.
+    BlockOutOfRange {
+        element: Id<SceneElement>,
+        index: usize,
+    },
+    /// The new text is invalid, or a split would leave one side empty.
+    Input(InputError),
+    /// The update does not match the variant's current elements.
+    StaleUpdate(Box<ElementUpdate>),
+    /// The variant, or the dialogue to be changed, is locked.
+    Locked(LockedEntity),
+}
+
+impl From<InputError> for ElementError {
+    fn from(value: InputError) -> Self {
+        ElementError::Input(value)
+    }
+}
+
+/// A change to the elements of a [`SceneVariant`], emitted by each editing
+/// method.
+///
+/// Indices are positions in the variant's elements, or in a dialogue's
+/// blocks, at the time of the change. Elements and blocks are carried whole
+/// so every update can be reversed.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum ElementUpdate {
+    /// `element` was inserted at `index`.
+    Inserted { index: usize, element: SceneElement },
+    /// `element` was removed from `index`.
+    Removed { index: usize, element: SceneElement },
+    /// The element at `index` was replaced. An edited action keeps its ID.
+    Replaced {
+        index: usize,
+        old: SceneElement,
+        new: SceneElement,
+    },
+    /// `element` moved from index `from` to index `to`.
+    Moved {
+        element: Id<SceneElement>,
+        from: usize,
+        to: usize,
+    },
+    /// The action at `index` was split into `head`, which keeps its ID, and
+    /// `tail`, inserted after it.
+    ActionSplit {
+        index: usize,
+        action: SceneAction,
+        head: SceneAction,
+        tail: SceneAction,
+    },
+    /// The actions `head`, at `index`, and `tail`, after it, were merged into
+    /// `action`, which keeps the head's ID.
+    ActionsMerged {
+        index: usize,
+        head: SceneAction,
+        tail: SceneAction,
+        action: SceneAction,
+    },
+    /// `block` was inserted at `index` in the dialogue `element`.
+    DialogueBlockInserted {
+        element: Id<SceneElement>,
+        index: usize,
+        block: DialogueBlock,
+    },
+    /// `block` was removed from `index` in the dialogue `element`.
+    DialogueBlockRemoved {
+        element: Id<SceneElement>,
+        index: usize,
+        block: DialogueBlock,
+    },
+    /// The block at `index` in the dialogue `element` was replaced.
+    DialogueBlockReplaced {
+        element: Id<SceneElement>,
+        index: usize,
+        old: DialogueBlock,
+        new: DialogueBlock,
+    },
+    /// The variant's heading changed from `old` to `new`.
+    HeadingChanged {
+        old: Option<SceneHeading>,
+        new: Option<SceneHeading>,
+    },
+    /// The variant's summary changed from `old` to `new`.
+    SummaryChanged { old: Summary, new: Summary },
+}
+
+impl ElementUpdate {
+    /// Returns the update that reverses this one.
+    pub fn inverse(&self) -> ElementUpdate {
+        match self.clone() {
+            ElementUpdate::Inserted { index, element } => ElementUpdate::Removed { index, element },
+            ElementUpdate::Removed { index, element } => ElementUpdate::Inserted { index, element },
+            ElementUpdate::Replaced { index, old, new } => ElementUpdate::Replaced {
+                index,
+                old: new,
+                new: old,
+            },
+            ElementUpdate::Moved { element, from, to } => ElementUpdate::Moved {
+                element,
+                from: to,
+                to: from,
+            },
+            ElementUpdate::ActionSplit {
+                index,
+                action,
+                head,
+                tail,
+            } => ElementUpdate::ActionsMerged {
+                index,
+                head,
+                tail,
+                action,
+            },
+            ElementUpdate::ActionsMerged {
+                index,
+                head,
+                tail,
+                action,
+            } => ElementUpdate::ActionSplit {
+                index,
+                action,
+                head,
+                tail,
+            },
+            ElementUpdate::DialogueBlockInserted {
+                element,
+                index,
+                block,
+            } => ElementUpdate::DialogueBlockRemoved {
+                element,
+                index,
+                block,
+            },
+            ElementUpdate::DialogueBlockRemoved {
+                element,
+                index,
+                block,
+            } => ElementUpdate::DialogueBlockInserted {
+                element,
+                index,
+                block,
+            },
+            ElementUpdate::DialogueBlockReplaced {
+                element,
+                index,
+                old,
+                new,
+            } => ElementUpdate::DialogueBlockReplaced {
+                element,
+                index,
+                old: new,
+                new: old,
+            },
+            ElementUpdate::HeadingChanged { old, new } => {
+                ElementUpdate::HeadingChanged { old: new, new: old }
+            }
+            ElementUpdate::SummaryChanged { old, new } => {
+                ElementUpdate::SummaryChanged { old: new, new: old }
+            }
+        }
+    }
+}
+
+/// One version of a scene's heading, summary, and elements.
+///
+/// While the variant is locked, every method that changes it fails with
+/// [`ElementError::Locked`], and so does every change to a locked dialogue
+/// in it. Locking the variant locks its dialogues too.
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct SceneVariant {
     id: Id<Self>,
     heading: Option<SceneHeading>,
     elements: Vec<SceneElement>,
     summary: Summary,
-    next: Option<Id<SceneVariant>>,
     metadata: Metadata,
 }
 
@@ -25,32 +255,628 @@ impl SceneVariant {
             heading: None,
             elements: Vec::new(),
             summary: Summary::default(),
-            next: None,
             metadata: Metadata::new(),
         }
     }
 
     pub fn id(&self) -> Id<Self> {
-        self.id.clone()
+        self.id
+    }
+
+    pub fn heading(&self) -> Option<&SceneHeading> {
+        self.heading.as_ref()
+    }
+
+    /// Sets the variant's heading.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ElementError::Locked`] if the variant is locked.
+    pub fn set_heading(&mut self, heading: SceneHeading) -> Result<ElementUpdate, ElementError> {
+        self.check_unlocked()?;
+        let old = self.heading.replace(heading.clone());
+        self.touch();
+
+        Ok(ElementUpdate::HeadingChanged {
+            old,
+            new: Some(heading),
+        })
+    }
+
+    pub fn elements(&self) -> &[SceneElement] {
+        &self.elements
+    }
+
+    /// Appends an element to the variant.
+    ///
+    /// # Errors
+    ///
+    /// - [`ElementError::ElementAlreadyExists`] if an element with the same
+    ///   ID is already in the variant.
+    /// - [`ElementError::Locked`] if the variant is locked.
+    pub fn add_element(&mut self, element: SceneElement) -> Result<ElementUpdate, ElementError> {
+        self.insert_element(self.elements.len(), element)
     }
 
     pub fn summary(&self) -> &Summary {
         &self.summary
     }
 
-    pub fn set_next(&mut self, next: Id<SceneVariant>) {
-        self.next = Some(next)
+    /// Sets the variant's summary.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ElementError::Locked`] if the variant is locked.
+    pub fn set_summary(&mut self, summary: Summary) -> Result<ElementUpdate, ElementError> {
+        self.check_unlocked()?;
+        let old = std::mem::replace(&mut self.summary, summary.clone());
+        self.touch();
+
+        Ok(ElementUpdate::SummaryChanged { old, new: summary })
+    }
+
+    /// Returns the element with the given ID, if it is in the variant.
+    pub fn element(&self, element_id: Id<SceneElement>) -> Option<&SceneElement> {
+        self.elements.iter().find(|e| e.id() == element_id)
+    }
+
+    /// Returns the index of the element with the given ID, if it is in the variant.
+    pub fn position(&self, element_id: Id<SceneElement>) -> Option<usize> {
+        self.elements.iter().position(|e| e.id() == element_id)
+    }
+
+    /// Inserts an element at `index`, shifting later elements back.
+    ///
+    /// # Errors
+    ///
+    /// - [`ElementError::IndexOutOfRange`] if `index` is past the end.
+    /// - [`ElementError::ElementAlreadyExists`] if an element with the same
+    ///   ID is already in the variant.
+    pub fn insert_element(
+        &mut self,
+        index: usize,
+        element: SceneElement,
+    ) -> Result<ElementUpdate, ElementError> {
+        self.check_unlocked()?;
+        if index > self.elements.len() {
+            return Err(ElementError::IndexOutOfRange(index));
+        }
+        if self.position(element.id()).is_some() {
+            return Err(ElementError::ElementAlreadyExists(element.id()));
+        }
+
+        self.elements.insert(index, element.clone());
+        self.touch();
+
+        Ok(ElementUpdate::Inserted { index, element })
+    }
+
+    /// Replaces an element with another.
+    ///
+    /// # Errors
+    ///
+    /// - [`ElementError::IndexOutOfRange`] or [`ElementError::UnknownElement`]
+    ///   if the target is not in the variant.
+    /// - [`ElementError::ElementAlreadyExists`] if another element already
+    ///   has the new element's ID.
+    pub fn replace_element(
+        &mut self,
+        target: impl Into<ElementRef>,
+        element: SceneElement,
+    ) -> Result<ElementUpdate, ElementError> {
+        self.check_unlocked()?;
+        let index = self.resolve_unlocked(target.into())?;
+        if self
+            .position(element.id())
+            .is_some_and(|other| other != index)
+        {
+            return Err(ElementError::ElementAlreadyExists(element.id()));
+        }
+
+        Ok(self.replace_at(index, element))
+    }
+
+    /// Removes an element.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ElementError::IndexOutOfRange`] or
+    /// [`ElementError::UnknownElement`] if the target is not in the variant.
+    pub fn remove_element(
+        &mut self,
+        target: impl Into<ElementRef>,
+    ) -> Result<ElementUpdate, ElementError> {
+        self.check_unlocked()?;
+        let index = self.resolve_unlocked(target.into())?;
+        let element = self.elements.remove(index);
+        self.touch();
+
+        Ok(ElementUpdate::Removed { index, element })
+    }
+
+    /// Moves an element so that it ends up at index `to`.
+    ///
+    /// # Errors
+    ///
+    /// - [`ElementError::IndexOutOfRange`] or [`ElementError::UnknownElement`]
+    ///   if the target is not in the variant.
+    /// - [`ElementError::IndexOutOfRange`] if `to` is not a valid index.
+    /// - [`ElementError::Locked`] if the variant or the element is locked.
+    pub fn move_element(
+        &mut self,
+        target: impl Into<ElementRef>,
+        to: usize,
+    ) -> Result<ElementUpdate, ElementError> {
+        self.check_unlocked()?;
+        let from = self.resolve_unlocked(target.into())?;
+        if to >= self.elements.len() {
+            return Err(ElementError::IndexOutOfRange(to));
+        }
+
+        let element = self.elements.remove(from);
+        let element_id = element.id();
+        self.elements.insert(to, element);
+        self.touch();
+
+        Ok(ElementUpdate::Moved {
+            element: element_id,
+            from,
+            to,
+        })
+    }
+
+    /// Changes the text of an action, keeping its ID.
+    ///
+    /// # Errors
+    ///
+    /// - [`ElementError::IndexOutOfRange`] or [`ElementError::UnknownElement`]
+    ///   if the target is not in the variant.
+    /// - [`ElementError::NotAnAction`] if the target is dialogue.
+    /// - [`ElementError::Input`] if the text is invalid.
+    pub fn edit_action(
+        &mut self,
+        target: impl Into<ElementRef>,
+        text: &str,
+    ) -> Result<ElementUpdate, ElementError> {
+        self.check_unlocked()?;
+        let (index, action) = self.action_at(target.into())?;
+        let edited = action.with_text(text)?;
+
+        Ok(self.replace_at(index, SceneElement::Action(edited)))
+    }
+
+    /// Splits an action in two before the character at `at`.
+    ///
+    /// The first part keeps the action's ID and the second becomes a new
+    /// action right after it. Whitespace around the split is dropped.
+    ///
+    /// # Errors
+    ///
+    /// - [`ElementError::IndexOutOfRange`] or [`ElementError::UnknownElement`]
+    ///   if the target is not in the variant.
+    /// - [`ElementError::NotAnAction`] if the target is dialogue.
+    /// - [`ElementError::SplitOutOfRange`] if `at` is past the end of the text.
+    /// - [`ElementError::Input`] if either part would be empty.
+    pub fn split_action(
+        &mut self,
+        target: impl Into<ElementRef>,
+        at: usize,
+    ) -> Result<ElementUpdate, ElementError> {
+        self.check_unlocked()?;
+        let (index, action) = self.action_at(target.into())?;
+        let text = action.as_str();
+        let Some(split) = text
+            .char_indices()
+            .map(|(i, _)| i)
+            .chain([text.len()])
+            .nth(at)
+        else {
+            return Err(ElementError::SplitOutOfRange {
+                element: self.elements[index].id(),
+                at,
+            });
+        };
+
+        let head = action.with_text(&text[..split])?;
+        let tail = SceneAction::new(&text[split..])?;
+        self.elements[index] = SceneElement::Action(head.clone());
+        self.elements
+            .insert(index + 1, SceneElement::Action(tail.clone()));
+        self.touch();
+
+        Ok(ElementUpdate::ActionSplit {
+            index,
+            action,
+            head,
+            tail,
+        })
+    }
+
+    /// Merges an action with the action right after it, joining their text
+    /// with a space. The merged action keeps the first action's ID.
+    ///
+    /// # Errors
+    ///
+    /// - [`ElementError::IndexOutOfRange`] or [`ElementError::UnknownElement`]
+    ///   if the target is not in the variant.
+    /// - [`ElementError::NotAnAction`] if the target or the element after it
+    ///   is dialogue.
+    /// - [`ElementError::NothingToMerge`] if the target is the last element.
+    pub fn merge_actions(
+        &mut self,
+        target: impl Into<ElementRef>,
+    ) -> Result<ElementUpdate, ElementError> {
+        self.check_unlocked()?;
+        let (index, head) = self.action_at(target.into())?;
+        if index + 1 == self.elements.len() {
+            return Err(ElementError::NothingToMerge(self.elements[index].id()));
+        }
+        let (_, tail) = self.action_at(ElementRef::Index(index + 1))?;
+
+        let action = head.with_text(&format!("{} {}", head.as_str(), tail.as_str()))?;
+        self.elements[index] = SceneElement::Action(action.clone());
+        self.elements.remove(index + 1);
+        self.touch();
+
+        Ok(ElementUpdate::ActionsMerged {
+            index,
+            head,
+            tail,
+            action,
+        })
+    }
+
+    /// Inserts a block at `index` in a dialogue, shifting later blocks back.
+    ///
+    /// # Errors
+    ///
+    /// - [`ElementError::IndexOutOfRange`] or [`ElementError::UnknownElement`]
+    ///   if the target is not in the variant.
+    /// - [`ElementError::NotDialogue`] if the target is an action.
+    /// - [`ElementError::BlockOutOfRange`] if `index` is past the last block.
+    pub fn insert_dialogue_block(
+        &mut self,
+        target: impl Into<ElementRef>,
+        index: usize,
+        block: DialogueBlock,
+    ) -> Result<ElementUpdate, ElementError> {
+        self.check_unlocked()?;
+        let (element, dialogue) = self.dialogue_at(target.into())?;
+        if index > dialogue.content().len() {
+            return Err(ElementError::BlockOutOfRange { element, index });
+        }
+
+        dialogue.insert_dialogue_block(index, block.clone());
+        self.touch();
+
+        Ok(ElementUpdate::DialogueBlockInserted {
+            element,
+            index,
+            block,
+        })
     }
 
-    pub fn clear_next(&mut self) {
-        self.next = None
+    /// Replaces a block in a dialogue.
+    ///
+    /// # Errors
+    ///
+    /// - [`ElementError::IndexOutOfRange`] or [`ElementError::UnknownElement`]
+    ///   if the target is not in the variant.
+    /// - [`ElementError::NotDialogue`] if the target is an action.
+    /// - [`ElementError::BlockOutOfRange`] if the dialogue has no block at `index`.
+    pub fn replace_dialogue_block(
+        &mut self,
+        target: impl Into<ElementRef>,
+        index: usize,
+        block: DialogueBlock,
+    ) -> Result<ElementUpdate, ElementError> {
+        self.check_unlocked()?;
+        let (element, dialogue) = self.dialogue_at(target.into())?;
+        if index >= dialogue.content().len() {
+            return Err(ElementError::BlockOutOfRange { element, index });
+        }
+
+        let old = dialogue.replace_dialogue_block(index, block.clone());
+        self.touch();
+
+        Ok(ElementUpdate::DialogueBlockReplaced {
+            element,
+            index,
+            old,
+            new: block,
+        })
     }
 
-    pub fn next(&self) -> Option<&Id<SceneVariant>> {
-        self.next.as_ref()
+    /// Removes a block from a dialogue.
+    ///
+    /// # Errors
+    ///
+    /// - [`ElementError::IndexOutOfRange`] or [`ElementError::UnknownElement`]
+    ///   if the target is not in the variant.
+    /// - [`ElementError::NotDialogue`] if the target is an action.
+    /// - [`ElementError::BlockOutOfRange`] if the dialogue has no block at `index`.
+    pub fn remove_dialogue_block(
+        &mut self,
+        target: impl Into<ElementRef>,
+        index: usize,
+    ) -> Result<ElementUpdate, ElementError> {
+        self.check_unlocked()?;
+        let (element, dialogue) = self.dialogue_at(target.into())?;
+        if index >= dialogue.content().len() {
+            return Err(ElementError::BlockOutOfRange { element, index });
+        }
+
+        let block = dialogue.remove_dialogue_block(index);
+        self.touch();
+
+        Ok(ElementUpdate::DialogueBlockRemoved {
+            element,
+            index,
+            block,
+        })
+    }
+
+    /// Makes exactly the change described by `update`.
+    ///
+    /// This replays an update previously emitted by the variant, or the
+    /// [inverse](ElementUpdate::inverse) of one. Elements are matched by ID,
+    /// so a dialogue edited since the update was emitted still matches.
+    ///
+    /// # Errors
+    ///
+    /// - [`ElementError::StaleUpdate`] if the variant's elements no longer
+    ///   match the update.
+    /// - [`ElementError::Locked`] if the variant, or the dialogue the update
+    ///   changes, is locked.
+    ///
+    /// On failure the variant is left unchanged.
+    pub fn apply_update(&mut self, update: &ElementUpdate) -> Result<(), ElementError> {
+        self.check_unlocked()?;
+        let stale = || ElementError::StaleUpdate(Box::new(update.clone()));
+        let stale_unless_locked = |error: ElementError| match error {
+            ElementError::Locked(_) => error,
+            _ => stale(),
+        };
+        let id_at = |index: usize| self.elements.get(index).map(SceneElement::id);
+
+        match update {
+            ElementUpdate::Inserted { index, element } => {
+                self.insert_element(*index, element.clone())
+                    .map_err(stale_unless_locked)?;
+            }
+            ElementUpdate::Removed { index, element } => {
+                if id_at(*index) != Some(element.id()) {
+                    return Err(stale());
+                }
+                self.remove_element(*index)?;
+            }
+            ElementUpdate::Replaced { index, old, new } => {
+                if id_at(*index) != Some(old.id()) {
+                    return Err(stale());
+                }
+                self.replace_element(*index, new.clone())
+                    .map_err(stale_unless_locked)?;
+            }
+            ElementUpdate::Moved { element, from, to } => {
+                if id_at(*from) != Some(*element) {
+                    return Err(stale());
+                }
+                self.move_element(*from, *to).map_err(stale_unless_locked)?;
+            }
+            ElementUpdate::ActionSplit {
+                index,
+                action,
+                head,
+                tail,
+            } => {
+                if id_at(*index) != Some(action.id().uuid().into())
+                    || self.position(tail.id().uuid().into()).is_some()
+                {
+                    return Err(stale());
+                }
+                self.elements[*index] = SceneElement::Action(head.clone());
+                self.elements
+                    .insert(index + 1, SceneElement::Action(tail.clone()));
+                self.touch();
+            }
+            ElementUpdate::ActionsMerged {
+                index,
+                head,
+                tail,
+                action,
+            } => {
+                if id_at(*index) != Some(head.id().uuid().into())
+                    || id_at(index + 1) != Some(tail.id().uuid().into())
+                {
+                    return Err(stale());
+                }
+                self.elements[*index] = SceneElement::Action(action.clone());
+                self.elements.remove(index + 1);
+                self.touch();
+            }
+            ElementUpdate::DialogueBlockInserted {
+                element,
+                index,
+                block,
+            } => {
+                self.insert_dialogue_block(*element, *index, block.clone())
+                    .map_err(stale_unless_locked)?;
+            }
+            ElementUpdate::DialogueBlockRemoved {
+                element,
+                index,
+                block,
+            } => {
+                self.check_block(*element, *index, block)
+                    .map_err(stale_unless_locked)?;
+                self.remove_dialogue_block(*element, *index)?;
+            }
+            ElementUpdate::DialogueBlockReplaced {
+                element,
+                index,
+                old,
+                new,
+            } => {
+                self.check_block(*element, *index, old)
+                    .map_err(stale_unless_locked)?;
+                self.replace_dialogue_block(*element, *index, new.clone())?;
+            }
+            ElementUpdate::HeadingChanged { old, new } => {
+                if self.heading != *old {
+                    return Err(stale());
+                }
+                self.heading = new.clone();
+                self.touch();
+            }
+            ElementUpdate::SummaryChanged { old, new } => {
+                if self.summary != *old {
+                    return Err(stale());
+                }
+                self.summary = new.clone();
+                self.touch();
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the index of the element `target` addresses.
+    fn resolve(&self, target: ElementRef) -> Result<usize, ElementError> {
+        match target {
+            ElementRef::Index(index) if index < self.elements.len() => Ok(index),
+            ElementRef::Index(index) => Err(ElementError::IndexOutOfRange(index)),
+            ElementRef::Id(id) => self.position(id).ok_or(ElementError::UnknownElement(id)),
+        }
+    }
+
+    /// Returns the index of the element `target` addresses, checking that it
+    /// is not a locked dialogue.
+    fn resolve_unlocked(&self, target: ElementRef) -> Result<usize, ElementError> {
+        let index = self.resolve(target)?;
+        if let SceneElement::Dialogue(dialogue) = &self.elements[index] {
+            dialogue.check_unlocked()?;
+        }
+
+        Ok(index)
+    }
+
+    /// Returns [`ElementError::Locked`] if the variant is locked.
+    fn check_unlocked(&self) -> Result<(), ElementError> {
+        match self.is_locked() {
+            true => Err(ElementError::Locked(LockedEntity::Variant(self.id))),
+            false => Ok(()),
+        }
+    }
+
+    /// Returns the index of the action `target` addresses, and the action.
+    fn action_at(&self, target: ElementRef) -> Result<(usize, SceneAction), ElementError> {
+        let index = self.resolve(target)?;
+        match &self.elements[index] {
+            SceneElement::Action(action) => Ok((index, action.clone())),
+            element => Err(ElementError::NotAnAction(element.id())),
+        }
+    }
+
+    /// Returns the ID of the dialogue `target` addresses, and the dialogue.
+    ///
+    /// A locked dialogue is refused with [`ElementError::Locked`].
+    fn dialogue_at(
+        &mut self,
+        target: ElementRef,
+    ) -> Result<(Id<SceneElement>, &mut Dialogue), ElementError> {
+        let index = self.resolve(target)?;
+        match &mut self.elements[index] {
+            SceneElement::Dialogue(dialogue) => {
+                dialogue.check_unlocked()?;
+                Ok((dialogue.id().uuid().into(), dialogue))
+            }
+            element => Err(ElementError::NotDialogue(element.id())),
+        }
+    }
+
+    /// Checks that the dialogue `element` holds `block` at `index`.
+    fn check_block(
+        &mut self,
+        element: Id<SceneElement>,
+        index: usize,
+        block: &DialogueBlock,
+    ) -> Result<(), ElementError> {
+        let (element, dialogue) = self.dialogue_at(ElementRef::Id(element))?;
+        match dialogue.content().get(index) == Some(block) {
+            true => Ok(()),
+            false => Err(ElementError::BlockOutOfRange { element, index }),
+        }
+    }
+
+    fn replace_at(&mut self, index: usize, element: SceneElement) -> ElementUpdate {
+        let old = std::mem::replace(&mut self.elements[index], element.clone());
+        self.touch();
+
+        ElementUpdate::Replaced {
+            index,
+            old,
+            new: element,
+        }
+    }
+
+    /// Returns the variant with its content replaced, keeping its ID and
+    /// metadata.
+    pub(crate) fn with_content(
+        &self,
+        heading: Option<SceneHeading>,
+        summary: Summary,
+        elements: Vec<SceneElement>,
+    ) -> Self {
+        Self {
+            heading,
+            summary,
+            elements,
+            ..self.clone()
+        }
+    }
+
+    /// Keeps only the elements `keep` returns `true` for, past any lock.
+    pub(crate) fn retain_elements(&mut self, keep: impl FnMut(&SceneElement) -> bool) {
+        let count = self.elements.len();
+        self.elements.retain(keep);
+        if self.elements.len() < count {
+            self.touch();
+        }
+    }
+
+    /// Returns the dialogues among the variant's elements.
+    pub(crate) fn dialogues_mut(&mut self) -> impl Iterator<Item = &mut Dialogue> {
+        self.elements.iter_mut().filter_map(|e| match e {
+            SceneElement::Dialogue(dialogue) => Some(dialogue),
+            SceneElement::Action(_) => None,
+        })
+    }
+
+    /// Returns a deep copy of the variant with a fresh ID and metadata.
+    ///
+    /// Every element in the copy gets a fresh ID too, so the two variants
+    /// can be edited independently.
+    pub(crate) fn duplicate(&self) -> Self {
+        Self {
+            heading: self.heading.clone(),
+            elements: self.elements.iter().map(|e| e.duplicate()).collect(),
+            summary: self.summary.clone(),
+            ..Self::new()
+        }
     }
 }
 
+impl Default for SceneVariant {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A scene of the story, told by one of its variants at a time.
+///
+/// While the scene is locked, every method that changes its variants fails
+/// with [`SceneError::Locked`]. Locking the scene locks each of its variants
+/// and their dialogues too.
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct Scene {
     id: Id<Self>,
@@ -72,7 +898,7 @@ impl Scene {
     }
 
     pub fn id(&self) -> Id<Self> {
-        self.id.clone()
+        self.id
     }
 
     pub fn summary(&self) -> Summary {
@@ -88,7 +914,7 @@ impl Scene {
         &self.variants
     }
 
-    pub fn variants_mut(&mut self) -> &mut HashMap<Id<SceneVariant>, SceneVariant> {
+    pub(crate) fn variants_mut(&mut self) -> &mut HashMap<Id<SceneVariant>, SceneVariant> {
         &mut self.variants
     }
 
@@ -103,6 +929,136 @@ impl Scene {
     pub fn active_variant(&self) -> &Id<SceneVariant> {
         &self.active_variant
     }
+
+    /// Adds a deep copy of one of the scene's variants as a new, inactive
+    /// variant, and returns the copy's ID.
+    ///
+    /// # Errors
+    ///
+    /// - [`SceneError::Locked`] if the scene is locked.
+    /// - [`SceneError::UnknownVariant`] if the variant does not belong to the
+    ///   scene.
+    pub fn fork_variant(
+        &mut self,
+        variant_id: &Id<SceneVariant>,
+    ) -> Result<Id<SceneVariant>, SceneError> {
+        self.check_unlocked()?;
+        let fork = self
+            .variants
+            .get(variant_id)
+            .ok_or(SceneError::UnknownVariant(*variant_id))?
+            .duplicate();
+        let fork_id = fork.id();
+
+        self.variants.insert(fork_id, fork);
+        self.touch();
+
+        Ok(fork_id)
+    }
+
+    /// Makes one of the scene's variants the active one, returning the
+    /// previously active variant.
+    ///
+    /// Activating the variant that is already active changes nothing.
+    ///
+    /// # Errors
+    ///
+    /// - [`SceneError::Locked`] if the scene is locked.
+    /// - [`SceneError::UnknownVariant`] if the variant does not belong to the
+    ///   scene.
+    pub fn set_active_variant(
+        &mut self,
+        variant_id: Id<SceneVariant>,
+    ) -> Result<Id<SceneVariant>, SceneError> {
+        self.check_unlocked()?;
+        if !self.has_variant(&variant_id) {
+            return Err(SceneError::UnknownVariant(variant_id));
+        }
+
+        let previous = std::mem::replace(&mut self.active_variant, variant_id);
+        if previous != variant_id {
+            self.touch();
+        }
+
+        Ok(previous)
+    }
+
+    /// Removes one of the scene's variants and returns it.
+    ///
+    /// # Errors
+    ///
+    /// - [`SceneError::UnknownVariant`] if the variant does not belong to the
+    ///   scene.
+    /// - [`SceneError::LastVariant`] if it is the scene's only variant.
+    /// - [`SceneError::ActiveVariant`] if it is the active variant.
+    /// - [`SceneError::Locked`] if the scene or the variant is locked.
+    pub fn remove_variant(
+        &mut self,
+        variant_id: &Id<SceneVariant>,
+    ) -> Result<SceneVariant, SceneError> {
+        self.check_removable(variant_id)?;
+
+        let variant = self
+            .variants
+            .remove(variant_id)
+            .expect("Removable variants belong to the scene");
+        self.touch();
+
+        Ok(variant)
+    }
+
+    /// Checks that [`Scene::remove_variant`] would succeed, without removing
+    /// anything.
+    pub(crate) fn check_removable(&self, variant_id: &Id<SceneVariant>) -> Result<(), SceneError> {
+        self.check_unlocked()?;
+        let Some(variant) = self.variants.get(variant_id) else {
+            return Err(SceneError::UnknownVariant(*variant_id));
+        };
+        if variant.is_locked() {
+            return Err(SceneError::Locked(LockedEntity::Variant(*variant_id)));
+        }
+        if self.variants.len() == 1 {
+            return Err(SceneError::LastVariant(*variant_id));
+        }
+        if self.active_variant == *variant_id {
+            return Err(SceneError::ActiveVariant(*variant_id));
+        }
+
+        Ok(())
+    }
+
+    /// Adds an existing variant to the scene, as when a removal is undone.
+    ///
+    /// # Errors
+    ///
+    /// - [`SceneError::Locked`] if the scene is locked.
+    /// - [`SceneError::VariantAlreadyExists`] if a variant with the same ID
+    ///   already belongs to the scene.
+    pub(crate) fn insert_variant(&mut self, variant: SceneVariant) -> Result<(), SceneError> {
+        self.check_unlocked()?;
+        if self.has_variant(&variant.id()) {
+            return Err(SceneError::VariantAlreadyExists(variant.id()));
+        }
+
+        self.variants.insert(variant.id(), variant);
+        self.touch();
+
+        Ok(())
+    }
+
+    /// Returns [`SceneError::Locked`] if the scene is locked.
+    fn check_unlocked(&self) -> Result<(), SceneError> {
+        match self.is_locked() {
+            true => Err(SceneError::Locked(LockedEntity::Scene(self.id))),
+            false => Ok(()),
+        }
+    }
+}
+
+impl Default for Scene {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl HasMetadata for Scene {
@@ -112,7 +1068,298 @@ impl HasMetadata for Scene {
     fn metadata_mut(&mut self) -> &mut Metadata {
         &mut self.metadata
     }
+
+    /// Locks the scene along with each of its variants and their dialogues.
+    fn lock(&mut self, note: RevisionNote) {
+        self.variants
+            .values_mut()
+            .for_each(|v| v.lock(note.clone()));
+        self.metadata.set_locked(true, note);
+    }
+}
+
+impl Unlock for Scene {
+    /// Unlocks the scene along with each of its variants and their dialogues.
+    fn unlock(&mut self, note: RevisionNote) {
+        self.variants
+            .values_mut()
+            .for_each(|v| v.unlock(note.clone()));
+        self.metadata.set_locked(false, note);
+    }
+}
+
+impl HasMetadata for SceneVariant {
+    fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+    fn metadata_mut(&mut self) -> &mut Metadata {
+        &mut self.metadata
+    }
+
+    /// Locks the variant along with its dialogues.
+    fn lock(&mut self, note: RevisionNote) {
+        self.dialogues_mut().for_each(|d| d.lock(note.clone()));
+        self.metadata.set_locked(true, note);
+    }
+}
+
+impl Unlock for SceneVariant {
+    /// Unlocks the variant along with its dialogues.
+    fn unlock(&mut self, note: RevisionNote) {
+        self.dialogues_mut().for_each(|d| d.unlock(note.clone()));
+        self.metadata.set_locked(false, note);
+    }
 }
 
 #[cfg(test)]
-mod tests {}
+mod tests {
+    use crate::models::{
+        Dialogue, DialogueBlock, DialogueText, ElementError, ElementUpdate, HasMetadata, Id,
+        LockedEntity, Parenthetical, RevisionNote, Scene, SceneAction, SceneElement, SceneError,
+        SceneVariant, Summary,
+    };
+
+    /// A scene whose only variant holds an action and a line of dialogue.
+    fn generate_test_components() -> (Scene, Id<SceneVariant>) {
+        let mut scene = Scene::new();
+        let variant_id = *scene.active_variant();
+        let mut dialogue = Dialogue::new(scene.id(), Id::new());
+        dialogue
+            .add_dialogue_block(DialogueBlock::Text(DialogueText::new("Hello.").unwrap()))
+            .unwrap();
+
+        let variant = scene.variants_mut().get_mut(&variant_id).unwrap();
+        variant
+            .set_summary(Summary::new("A greeting.").unwrap())
+            .unwrap();
+        variant
+            .add_element(SceneElement::Action(SceneAction::new("Rain.").unwrap()))
+            .unwrap();
+        variant
+            .add_element(SceneElement::Dialogue(dialogue))
+            .unwrap();
+
+        (scene, variant_id)
+    }
+
+    fn dialogue_ids(variant: &SceneVariant) -> Vec<Id<Dialogue>> {
+        variant
+            .elements()
+            .iter()
+            .filter_map(|e| match e {
+                SceneElement::Dialogue(d) => Some(d.id()),
+                SceneElement::Action(_) => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_forking_copies_content_under_fresh_ids() {
+        // ARRANGE
+        let (mut scene, variant_id) = generate_test_components();
+        // ACT
+        let fork_id = scene.fork_variant(&variant_id).unwrap();
+        // ASSERT
+        let original = &scene.variants()[&variant_id];
+        let fork = &scene.variants()[&fork_id];
+        assert_ne!(fork_id, variant_id);
+        assert_eq!(scene.active_variant(), &variant_id);
+        assert_eq!(fork.summary(), original.summary());
+        assert_eq!(fork.elements().len(), original.elements().len());
+        assert_ne!(fork.elements()[0].id(), original.elements()[0].id());
+        assert_eq!(dialogue_ids(fork).len(), 1);
+        assert_ne!(dialogue_ids(fork), dialogue_ids(original));
+    }
+
+    #[test]
+    fn test_activating_a_foreign_variant_fails() {
+        // ARRANGE
+        let (mut scene, variant_id) = generate_test_components();
+        let foreign = Id::new();
+        // ACT
+        let response = scene.set_active_variant(foreign);
+        // ASSERT
+        assert_eq!(response, Err(SceneError::UnknownVariant(foreign)));
+        assert_eq!(scene.active_variant(), &variant_id);
+    }
+
+    #[test]
+    fn test_removing_keeps_the_last_and_active_variants() {
+        // ARRANGE
+        let (mut scene, variant_id) = generate_test_components();
+        // ACT
+        let last = scene.remove_variant(&variant_id);
+        let fork_id = scene.fork_variant(&variant_id).unwrap();
+        let active = scene.remove_variant(&variant_id);
+        let removed = scene.remove_variant(&fork_id).map(|v| v.id());
+        // ASSERT
+        assert_eq!(last, Err(SceneError::LastVariant(variant_id)));
+        assert_eq!(active, Err(SceneError::ActiveVariant(variant_id)));
+        assert_eq!(removed, Ok(fork_id));
+        assert_eq!(scene.variants().len(), 1);
+    }
+
+    fn action(text: &str) -> SceneElement {
+        SceneElement::Action(SceneAction::new(text).unwrap())
+    }
+
+    fn texts(variant: &SceneVariant) -> Vec<String> {
+        variant
+            .elements()
+            .iter()
+            .filter_map(|e| match e {
+                SceneElement::Action(a) => Some(a.as_str().to_string()),
+                SceneElement::Dialogue(_) => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_elements_can_be_addressed_by_index_or_id() {
+        // ARRANGE
+        let (mut scene, variant_id) = generate_test_components();
+        let variant = scene.variants_mut().get_mut(&variant_id).unwrap();
+        let rain = variant.elements()[0].id();
+        let version = variant.metadata().version;
+        // ACT
+        variant.insert_element(0, action("Thunder.")).unwrap();
+        variant.move_element(rain, 2).unwrap();
+        let removed = variant.remove_element(0).unwrap();
+        // ASSERT
+        assert_eq!(texts(variant), vec!["Rain."]);
+        assert_eq!(variant.position(rain), Some(1));
+        assert!(matches!(removed, ElementUpdate::Removed { index: 0, .. }));
+        assert_eq!(variant.metadata().version, version + 3);
+    }
+
+    #[test]
+    fn test_splitting_and_merging_actions_keeps_the_first_id() {
+        // ARRANGE
+        let (mut scene, variant_id) = generate_test_components();
+        let variant = scene.variants_mut().get_mut(&variant_id).unwrap();
+        variant.edit_action(0, "Rain falls. Wind howls.").unwrap();
+        let id = variant.elements()[0].id();
+        // ACT
+        variant.split_action(id, 11).unwrap();
+        let split = texts(variant);
+        variant.merge_actions(id).unwrap();
+        // ASSERT
+        assert_eq!(split, vec!["Rain falls.", "Wind howls."]);
+        assert_eq!(texts(variant), vec!["Rain falls. Wind howls."]);
+        assert_eq!(variant.elements()[0].id(), id);
+        assert_eq!(
+            variant.merge_actions(id),
+            Err(ElementError::NotAnAction(variant.elements()[1].id()))
+        );
+    }
+
+    #[test]
+    fn test_dialogue_blocks_are_edited_in_place() {
+        // ARRANGE
+        let (mut scene, variant_id) = generate_test_components();
+        let variant = scene.variants_mut().get_mut(&variant_id).unwrap();
+        let line = variant.elements()[1].id();
+        let aside = DialogueBlock::Parenthetical(Parenthetical::new("quietly").unwrap());
+        // ACT
+        variant
+            .insert_dialogue_block(line, 0, aside.clone())
+            .unwrap();
+        let response = variant.remove_dialogue_block(0, 0);
+        // ASSERT
+        let SceneElement::Dialogue(dialogue) = variant.element(line).unwrap() else {
+            panic!("Expected dialogue");
+        };
+        assert_eq!(dialogue.content()[0], aside);
+        assert_eq!(
+            response,
+            Err(ElementError::NotDialogue(variant.elements()[0].id()))
+        );
+    }
+
+    #[test]
+    fn test_inverse_updates_undo_edits() {
+        // ARRANGE
+        let (mut scene, variant_id) = generate_test_components();
+        let variant = scene.variants_mut().get_mut(&variant_id).unwrap();
+        let before = variant.elements().to_vec();
+        // ACT
+        let updates = [
+            variant.split_action(0, 2).unwrap(),
+            variant.move_element(2, 0).unwrap(),
+            variant.remove_element(1).unwrap(),
+            variant
+                .replace_dialogue_block(
+                    0,
+                    0,
+                    DialogueBlock::Text(DialogueText::new("Hi.").unwrap()),
+                )
+                .unwrap(),
+        ];
+        for update in updates.iter().rev() {
+            variant.apply_update(&update.inverse()).unwrap();
+        }
+        // ASSERT
+        let ids = |elements: &[SceneElement]| elements.iter().map(|e| e.id()).collect::<Vec<_>>();
+        assert_eq!(ids(variant.elements()), ids(&before));
+        assert_eq!(texts(variant), vec!["Rain."]);
+        assert_eq!(
+            variant.apply_update(&updates[2].inverse()),
+            Err(ElementError::StaleUpdate(Box::new(updates[2].inverse())))
+        );
+    }
+
+    #[test]
+    fn test_locking_a_scene_locks_its_variants_and_dialogues() {
+        // ARRANGE
+        let (mut scene, variant_id) = generate_test_components();
+        let note = RevisionNote::new("Shooting script.").unwrap();
+        // ACT
+        scene.lock(note.clone());
+        // ASSERT
+        assert_eq!(
+            scene.fork_variant(&variant_id),
+            Err(SceneError::Locked(LockedEntity::Scene(scene.id())))
+        );
+        assert_eq!(scene.metadata().revision_notes, vec![note]);
+        let variant = scene.variants_mut().get_mut(&variant_id).unwrap();
+        assert_eq!(
+            variant.edit_action(0, "Snow."),
+            Err(ElementError::Locked(LockedEntity::Variant(variant_id)))
+        );
+        assert!(variant.elements().iter().all(|e| match e {
+            SceneElement::Dialogue(d) => d.is_locked(),
+            SceneElement::Action(_) => true,
+        }));
+    }
+
+    #[test]
+    fn test_a_locked_dialogue_refuses_edits_in_an_unlocked_variant() {
+        // ARRANGE
+        let (mut scene, variant_id) = generate_test_components();
+        let variant = scene.variants_mut().get_mut(&variant_id).unwrap();
+        let mut dialogue = Dialogue::new(Id::new(), Id::new());
+        dialogue.lock(RevisionNote::new("Approved line.").unwrap());
+        let dialogue_id = dialogue.id();
+        variant
+            .add_element(SceneElement::Dialogue(dialogue))
+            .unwrap();
+        let locked = ElementError::Locked(LockedEntity::Dialogue(dialogue_id));
+        // ACT & ASSERT
+        assert_eq!(
+            variant.insert_dialogue_block(
+                2,
+                0,
+                DialogueBlock::Text(DialogueText::new("Hi.").unwrap())
+            ),
+            Err(locked)
+        );
+        assert!(variant.remove_element(2).is_err());
+        assert_eq!(
+            variant.move_element(2, 0),
+            Err(ElementError::Locked(LockedEntity::Dialogue(dialogue_id)))
+        );
+        assert!(variant.edit_action(0, "Snow.").is_ok());
+        assert!(variant.move_element(0, 1).is_ok());
+        assert_eq!(variant.position(dialogue_id.uuid().into()), Some(2));
+    }
+}
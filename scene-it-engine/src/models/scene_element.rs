@@ -1,8 +1,14 @@
 use crate::{
-    models::{Id, character::Character, metadata::Metadata, scene::Scene},
-    utils::{InputError, validate_input},
+    models::{
+        Id,
+        character::Character,
+        metadata::{HasMetadata, LockedEntity, Metadata, Unlock},
+        scene::{ElementError, Scene},
+    },
+    utils::{InputError, validate_input, validate_multiline_input},
 };
 use serde::{Deserialize, Serialize};
+use std::fmt;
 
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub enum SceneElement {
@@ -10,12 +16,57 @@ pub enum SceneElement {
     Dialogue(Dialogue),
 }
 
+impl SceneElement {
+    /// Returns the element's stable ID, which is the ID of the action or
+    /// dialogue it holds.
+    pub fn id(&self) -> Id<SceneElement> {
+        match self {
+            SceneElement::Action(action) => action.id().uuid().into(),
+            SceneElement::Dialogue(dialogue) => dialogue.id().uuid().into(),
+        }
+    }
+
+    /// Returns a copy of the element under a fresh ID.
+    pub(crate) fn duplicate(&self) -> Self {
+        match self {
+            SceneElement::Action(action) => SceneElement::Action(SceneAction {
+                id: Id::new(),
+                text: action.text.clone(),
+            }),
+            SceneElement::Dialogue(dialogue) => SceneElement::Dialogue(dialogue.duplicate()),
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
-pub struct SceneAction(String);
+pub struct SceneAction {
+    id: Id<Self>,
+    text: String,
+}
 
 impl SceneAction {
+    /// Creates an action. Line breaks in `input` are kept.
     pub fn new(input: &str) -> Result<Self, InputError> {
-        Ok(Self(validate_input(input, None)?))
+        Ok(Self {
+            id: Id::new(),
+            text: validate_multiline_input(input, None)?,
+        })
+    }
+
+    pub fn id(&self) -> Id<Self> {
+        self.id
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns the action with its text replaced, keeping its ID.
+    pub(crate) fn with_text(&self, input: &str) -> Result<Self, InputError> {
+        Ok(Self {
+            id: self.id,
+            text: validate_multiline_input(input, None)?,
+        })
     }
 }
 
@@ -24,7 +75,7 @@ pub struct Parenthetical(String);
 
 impl Parenthetical {
     pub fn new(input: &str) -> Result<Self, InputError> {
-        Ok(Self(validate_input(input, Some(25))?))
+        Ok(Self(validate_input(input, Some(40))?))
     }
 
     pub fn as_str(&self) -> &str {
@@ -71,15 +122,103 @@ impl Dialogue {
         }
     }
 
-    pub fn add_dialogue_block(&mut self, block: DialogueBlock) {
+    pub fn id(&self) -> Id<Self> {
+        self.id
+    }
+
+    pub fn scene(&self) -> Id<Scene> {
+        self.scene
+    }
+
+    pub fn speaker(&self) -> Id<Character> {
+        self.speaker
+    }
+
+    pub fn content(&self) -> &[DialogueBlock] {
+        &self.content
+    }
+
+    /// Appends a block to the dialogue.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ElementError::Locked`] if the dialogue is locked.
+    pub fn add_dialogue_block(&mut self, block: DialogueBlock) -> Result<(), ElementError> {
+        self.check_unlocked()?;
         self.content.push(block);
+        Ok(())
+    }
+
+    /// Returns [`ElementError::Locked`] if the dialogue is locked.
+    pub(crate) fn check_unlocked(&self) -> Result<(), ElementError> {
+        match self.is_locked() {
+            true => Err(ElementError::Locked(LockedEntity::Dialogue(self.id))),
+            false => Ok(()),
+        }
+    }
+
+    pub(crate) fn set_speaker(&mut self, speaker: Id<Character>) {
+        self.speaker = speaker;
+        self.touch();
+    }
+
+    pub(crate) fn insert_dialogue_block(&mut self, index: usize, block: DialogueBlock) {
+        self.content.insert(index, block);
+        self.touch();
+    }
+
+    pub(crate) fn replace_dialogue_block(
+        &mut self,
+        index: usize,
+        block: DialogueBlock,
+    ) -> DialogueBlock {
+        let old = std::mem::replace(&mut self.content[index], block);
+        self.touch();
+        old
+    }
+
+    pub(crate) fn remove_dialogue_block(&mut self, index: usize) -> DialogueBlock {
+        let block = self.content.remove(index);
+        self.touch();
+        block
+    }
+
+    /// Returns a copy of the dialogue with a fresh ID and metadata.
+    pub(crate) fn duplicate(&self) -> Self {
+        Self {
+            content: self.content.clone(),
+            ..Self::new(self.scene, self.speaker)
+        }
     }
 }
 
+impl HasMetadata for Dialogue {
+    fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+    fn metadata_mut(&mut self) -> &mut Metadata {
+        &mut self.metadata
+    }
+}
+
+impl Unlock for Dialogue {}
+
 #[derive(Debug, Hash, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub enum CameraLocation {
     Interior,
     Exterior,
+    InteriorExterior,
+}
+
+impl fmt::Display for CameraLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let prefix = match self {
+            CameraLocation::Interior => "INT.",
+            CameraLocation::Exterior => "EXT.",
+            CameraLocation::InteriorExterior => "INT./EXT.",
+        };
+        write!(f, "{prefix}")
+    }
 }
 
 #[derive(Debug, Hash, PartialEq, Eq, Clone, Serialize, Deserialize)]
@@ -89,6 +228,10 @@ impl SceneLocation {
     pub fn new(input: &str) -> Result<Self, InputError> {
         Ok(Self(validate_input(input, None)?))
     }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
 }
 
 #[derive(Debug, Hash, PartialEq, Eq, Clone, Serialize, Deserialize)]
@@ -103,6 +246,22 @@ pub enum SceneTimeOfDay {
     Continuous,
 }
 
+impl fmt::Display for SceneTimeOfDay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            SceneTimeOfDay::Morning => "MORNING",
+            SceneTimeOfDay::Dawn => "DAWN",
+            SceneTimeOfDay::Day => "DAY",
+            SceneTimeOfDay::Dusk => "DUSK",
+            SceneTimeOfDay::Evening => "EVENING",
+            SceneTimeOfDay::Night => "NIGHT",
+            SceneTimeOfDay::Later => "LATER",
+            SceneTimeOfDay::Continuous => "CONTINUOUS",
+        };
+        write!(f, "{label}")
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct SceneHeading {
     camera_location: CameraLocation,
@@ -122,4 +281,29 @@ impl SceneHeading {
             time_of_day,
         }
     }
+
+    pub fn camera_location(&self) -> &CameraLocation {
+        &self.camera_location
+    }
+
+    pub fn scene_location(&self) -> &SceneLocation {
+        &self.scene_location
+    }
+
+    pub fn time_of_day(&self) -> &SceneTimeOfDay {
+        &self.time_of_day
+    }
+}
+
+impl fmt::Display for SceneHeading {
+    /// Formats the heading the way it appears in a script, e.g. `INT. HOUSE - NIGHT`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} - {}",
+            self.camera_location,
+            self.scene_location.as_str(),
+            self.time_of_day
+        )
+    }
 }
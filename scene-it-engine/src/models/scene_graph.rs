@@ -4,7 +4,7 @@ use std::collections::{HashMap, HashSet, hash_map::Entry};
 use crate::models::{Id, scene::SceneVariant};
 
 /// A structural change to a [`SceneGraph`], emitted as the result of a mutating operation.
-#[derive(Clone, Debug, PartialEq, Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum SceneGraphUpdate {
     /// `variant` was moved from being a child of `src` to being a child of `dest`.
     Move {
@@ -32,22 +32,98 @@ pub enum SceneGraphUpdate {
     },
 }
 
+impl SceneGraphUpdate {
+    /// Returns the update that reverses this one.
+    ///
+    /// A single update is always reversed by a single update. Operations that
+    /// emit several updates, such as [`SceneGraph::remove_variant`], are
+    /// reversed by applying the inverse of each update in reverse order, so
+    /// the variant is restored before its root status and edges.
+    pub fn inverse(&self) -> SceneGraphUpdate {
+        match *self {
+            SceneGraphUpdate::Move { variant, src, dest } => SceneGraphUpdate::Move {
+                variant,
+                src: dest,
+                dest: src,
+            },
+            SceneGraphUpdate::SceneVariantAdded(variant) => {
+                SceneGraphUpdate::SceneVariantRemoved(variant)
+            }
+            SceneGraphUpdate::SceneVariantRemoved(variant) => {
+                SceneGraphUpdate::SceneVariantAdded(variant)
+            }
+            SceneGraphUpdate::SceneVariantSetAsRoot(variant) => {
+                SceneGraphUpdate::SceneVariantRemovedAsRoot(variant)
+            }
+            SceneGraphUpdate::SceneVariantRemovedAsRoot(variant) => {
+                SceneGraphUpdate::SceneVariantSetAsRoot(variant)
+            }
+            SceneGraphUpdate::EdgeAdded { src, dest } => {
+                SceneGraphUpdate::EdgeRemoved { src, dest }
+            }
+            SceneGraphUpdate::EdgeRemoved { src, dest } => {
+                SceneGraphUpdate::EdgeAdded { src, dest }
+            }
+        }
+    }
+}
+
 /// Errors that can occur while mutating or querying a [`SceneGraph`].
 #[derive(Debug, Serialize, PartialEq)]
 pub enum SceneGraphError {
     /// The referenced scene variant does not exist in the graph.
     UnknownVariant(Id<SceneVariant>),
-    /// `variant` could not be moved because it is not a child of `src`.
+    /// `variant` could not be moved because it is not a child of `src`, or is
+    /// already a child of `dest`.
     InvalidMove {
         variant: Id<SceneVariant>,
         src: Id<SceneVariant>,
         dest: Id<SceneVariant>,
     },
-    /// Moving `variant` under `dest` would create a cycle in the graph.
+    /// Making `variant` a child of `dest`, by a move or a new edge, would
+    /// create a cycle in the graph.
     CycleDetected {
         variant: Id<SceneVariant>,
         dest: Id<SceneVariant>,
     },
+    /// The update does not describe a change that can be made to the graph as
+    /// it stands, for example adding an edge that already exists.
+    StaleUpdate(SceneGraphUpdate),
+}
+
+/// The problems found by [`SceneGraph::validate`] or [`Narrative::validate`].
+///
+/// Graphs built through the API never have these problems, but a graph read
+/// from a file may. Every list is sorted so the report reads the same each time.
+///
+/// [`Narrative::validate`]: crate::models::Narrative::validate
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct GraphReport {
+    /// Each cycle found, as the variants along it in edge order. The last
+    /// variant links back to the first.
+    pub cycles: Vec<Vec<Id<SceneVariant>>>,
+    /// Roots that are not variants in the graph.
+    pub missing_roots: Vec<Id<SceneVariant>>,
+    /// Edges whose `dest` is not a variant in the graph, as `(src, dest)`.
+    pub dangling_edges: Vec<(Id<SceneVariant>, Id<SceneVariant>)>,
+    /// Variants in the graph that belong to no scene. Only a narrative can
+    /// tell, so [`SceneGraph::validate`] leaves this empty.
+    pub unowned_variants: Vec<Id<SceneVariant>>,
+    /// Scene variants that are not in the graph, so they can be neither
+    /// linked nor reached. Only a narrative can tell, so
+    /// [`SceneGraph::validate`] leaves this empty.
+    pub ungraphed_variants: Vec<Id<SceneVariant>>,
+}
+
+impl GraphReport {
+    /// Returns `true` if no problems were found.
+    pub fn is_valid(&self) -> bool {
+        self.cycles.is_empty()
+            && self.missing_roots.is_empty()
+            && self.dangling_edges.is_empty()
+            && self.unowned_variants.is_empty()
+            && self.ungraphed_variants.is_empty()
+    }
 }
 
 /// An ordering and relationship model for scenes that expresses what can come next.
@@ -87,8 +163,15 @@ pub enum SceneGraphError {
 /// the boundary where requests arrive from outside. Inside the graph, no-ops
 /// stay quiet — [`SceneGraph::remove_variant`] calls
 /// [`SceneGraph::remove_edge_unchecked`] in loops where absence is expected,
-/// and a self-loop legitimately visits the same pair twice.
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+/// and a self-loop read from an older file visits the same pair twice.
+///
+/// # Cycles
+///
+/// The graph is kept acyclic: [`SceneGraph::add_edge`] and
+/// [`SceneGraph::move_variant`] refuse any change that would close a loop.
+/// A graph deserialized from a file is not checked on the way in, so callers
+/// opening one should run [`SceneGraph::validate`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
 pub struct SceneGraph {
     /// Adjacency list mapping each scene variant to its direct successors.
     edges: HashMap<Id<SceneVariant>, HashSet<Id<SceneVariant>>>,
@@ -184,7 +267,9 @@ impl SceneGraph {
     /// # Errors
     /// Returns `SceneGraphError::UnknownVariant` if `variant`, `src`, or `dest`
     /// is not present in the graph.
-    /// Returns `SceneGraphError::InvalidMove` if `variant` is not a child of `src`.
+    /// Returns `SceneGraphError::InvalidMove` if `variant` is not a child of `src`,
+    /// or if `dest` is a different parent that already has `variant` as a child.
+    /// Such a move would merge two edges into one and could not be reversed.
     /// Returns `SceneGraphError::CycleDetected` if the move would create a cycle.
     /// On either failure the graph is left unchanged.
     pub fn move_variant(
@@ -200,10 +285,11 @@ impl SceneGraph {
             }
         }
 
-        if !self
-            .edges
-            .get_mut(&src)
-            .is_some_and(|edges| edges.remove(&variant))
+        if !self.is_movable(variant, src, dest)
+            || !self
+                .edges
+                .get_mut(&src)
+                .is_some_and(|edges| edges.remove(&variant))
         {
             return Err(SceneGraphError::InvalidMove { variant, src, dest });
         }
@@ -282,6 +368,10 @@ impl SceneGraph {
     ///
     /// Returns `SceneGraphError::UnknownVariant` if either `src` or `dest` is
     /// not in the graph. Neither is created implicitly.
+    ///
+    /// Returns `SceneGraphError::CycleDetected` if `src` is reachable from
+    /// `dest`, including when they are the same variant, since the edge would
+    /// close a loop. The graph is left unchanged.
     pub fn add_edge(
         &mut self,
         src: Id<SceneVariant>,
@@ -295,11 +385,22 @@ impl SceneGraph {
             return Err(SceneGraphError::UnknownVariant(dest));
         }
 
-        if self.edges.get_mut(&src).is_some_and(|e| e.insert(dest)) {
-            return Ok(Some(SceneGraphUpdate::EdgeAdded { src, dest }));
+        if self.edges.get(&src).is_some_and(|e| e.contains(&dest)) {
+            return Ok(None);
         }
 
-        Ok(None)
+        if self.is_descendant(dest, src) {
+            return Err(SceneGraphError::CycleDetected {
+                variant: dest,
+                dest: src,
+            });
+        }
+
+        if let Some(edges) = self.edges.get_mut(&src) {
+            edges.insert(dest);
+        }
+
+        Ok(Some(SceneGraphUpdate::EdgeAdded { src, dest }))
     }
 
     /// Removes a directed edge from one scene variant to another.
@@ -346,6 +447,84 @@ impl SceneGraph {
         None
     }
 
+    /// Makes exactly the change described by `update`.
+    ///
+    /// This is the low-level counterpart of the operations that emit updates,
+    /// used to replay or reverse them. Unlike those operations it does no
+    /// cascading: removing a variant requires its edges and root status to
+    /// have been removed first, just as [`SceneGraph::remove_variant`]
+    /// reports them.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SceneGraphError::StaleUpdate`] if the change has already
+    /// been made or cannot be made without further changes, or the error the
+    /// corresponding operation would return. On failure the graph is left
+    /// unchanged.
+    pub fn apply(&mut self, update: &SceneGraphUpdate) -> Result<(), SceneGraphError> {
+        let stale = || SceneGraphError::StaleUpdate(update.clone());
+
+        let changed = match *update {
+            SceneGraphUpdate::Move { variant, src, dest } => {
+                for s in [variant, src, dest] {
+                    if !self.edges.contains_key(&s) {
+                        return Err(SceneGraphError::UnknownVariant(s));
+                    }
+                }
+                let movable = self.is_movable(variant, src, dest)
+                    && self.edges.get(&src).is_some_and(|e| e.contains(&variant));
+
+                // Cycles were checked when the move was first made. Replaying or
+                // reversing it only restores a shape the graph has already had.
+                if movable {
+                    if let Some(edges) = self.edges.get_mut(&src) {
+                        edges.remove(&variant);
+                    }
+                    if let Some(edges) = self.edges.get_mut(&dest) {
+                        edges.insert(variant);
+                    }
+                }
+                movable
+            }
+            SceneGraphUpdate::SceneVariantAdded(variant) => self.add_variant(variant).is_some(),
+            SceneGraphUpdate::SceneVariantRemoved(variant) => {
+                let connected = self.roots.contains(&variant)
+                    || self
+                        .edges
+                        .get(&variant)
+                        .is_some_and(|dests| !dests.is_empty())
+                    || self.edges.values().any(|dests| dests.contains(&variant));
+                if connected {
+                    return Err(stale());
+                }
+                self.edges.remove(&variant).is_some()
+            }
+            SceneGraphUpdate::SceneVariantSetAsRoot(variant) => self.add_root(variant)?.is_some(),
+            SceneGraphUpdate::SceneVariantRemovedAsRoot(variant) => {
+                self.remove_root(variant)?.is_some()
+            }
+            SceneGraphUpdate::EdgeAdded { src, dest } => self.add_edge(src, dest)?.is_some(),
+            SceneGraphUpdate::EdgeRemoved { src, dest } => self.remove_edge(src, dest)?.is_some(),
+        };
+
+        if changed { Ok(()) } else { Err(stale()) }
+    }
+
+    /// Returns `true` if the scene variant is in the graph.
+    pub fn contains(&self, variant_id: Id<SceneVariant>) -> bool {
+        self.edges.contains_key(&variant_id)
+    }
+
+    /// Returns an iterator over every scene variant in the graph.
+    pub fn variants(&self) -> impl Iterator<Item = Id<SceneVariant>> {
+        self.edges.keys().copied()
+    }
+
+    /// Returns the scene variants registered as root entry points.
+    pub fn roots(&self) -> &HashSet<Id<SceneVariant>> {
+        &self.roots
+    }
+
     /// Returns an iterator over all scenes that are direct successors of `variant_id`.  
     /// These represent all possible "next" scenes in the procedural traversal of the graph.
     pub fn next_variants(
@@ -358,6 +537,17 @@ impl SceneGraph {
             .flat_map(|set| set.iter().cloned())
     }
 
+    /// Returns an iterator over all scene variants that are direct predecessors of `variant_id`.
+    pub fn previous_variants(
+        &self,
+        variant_id: Id<SceneVariant>,
+    ) -> impl Iterator<Item = Id<SceneVariant>> {
+        self.edges
+            .iter()
+            .filter(move |(_, dests)| dests.contains(&variant_id))
+            .map(|(src, _)| *src)
+    }
+
     /// Returns all scene variants in the graph that cannot be reached from any root.  
     /// These are "orphaned" scene variant with no path from a root node, useful for detecting disconnected content.
     pub fn unreachable_variants(&self) -> HashSet<Id<SceneVariant>> {
@@ -366,7 +556,7 @@ impl SceneGraph {
 
         while let Some(variant) = stack.pop() {
             if visited.insert(variant)
-                && let Some(edges) = self.edges.get(&variant)
+                && let Some(edges) = self.edges.get(variant)
             {
                 stack.extend(edges.iter())
             }
@@ -381,10 +571,7 @@ impl SceneGraph {
 
     /// Returns an iterator over all scene variants reachable from `root`, in
     /// depth-first traversal order (including `root` itself).
-    pub fn reachable_from<'a>(
-        &'a self,
-        root: Id<SceneVariant>,
-    ) -> impl Iterator<Item = Id<SceneVariant>> {
+    pub fn reachable_from(&self, root: Id<SceneVariant>) -> impl Iterator<Item = Id<SceneVariant>> {
         let mut visited = HashSet::new();
         let mut order = Vec::new();
         let mut stack = vec![root];
@@ -401,6 +588,253 @@ impl SceneGraph {
         order.into_iter()
     }
 
+    /// Checks the whole graph for cycles, roots that are not in the graph, and
+    /// edges that lead to unknown variants.
+    ///
+    /// This does not change the graph; it describes what is wrong so the
+    /// problems can be shown to the user and repaired.
+    pub fn validate(&self) -> GraphReport {
+        let mut missing_roots: Vec<_> = self
+            .roots
+            .iter()
+            .filter(|root| !self.edges.contains_key(root))
+            .copied()
+            .collect();
+        missing_roots.sort_by_key(|id| id.uuid());
+
+        let mut dangling_edges: Vec<_> = self
+            .edges
+            .iter()
+            .flat_map(|(src, dests)| dests.iter().map(move |dest| (*src, *dest)))
+            .filter(|(_, dest)| !self.edges.contains_key(dest))
+            .collect();
+        dangling_edges.sort_by_key(|(src, dest)| (src.uuid(), dest.uuid()));
+
+        GraphReport {
+            cycles: self.cycles(),
+            missing_roots,
+            dangling_edges,
+            unowned_variants: Vec::new(),
+            ungraphed_variants: Vec::new(),
+        }
+    }
+
+    /// Finds the cycles in the graph, one for each edge that leads back to a
+    /// variant on the current depth-first path.
+    fn cycles(&self) -> Vec<Vec<Id<SceneVariant>>> {
+        let mut starts: Vec<_> = self.variants().collect();
+        starts.sort_by_key(|id| id.uuid());
+
+        let mut cycles = Vec::new();
+        let mut finished = HashSet::new();
+        for start in starts {
+            if finished.contains(&start) {
+                continue;
+            }
+
+            let mut path = vec![start];
+            let mut stack = vec![self.pending_successors(start)];
+            while let Some(pending) = stack.last_mut() {
+                match pending.pop() {
+                    Some(next) => {
+                        if let Some(position) = path.iter().position(|v| *v == next) {
+                            cycles.push(path[position..].to_vec());
+                        } else if !finished.contains(&next) {
+                            path.push(next);
+                            stack.push(self.pending_successors(next));
+                        }
+                    }
+                    None => {
+                        stack.pop();
+                        if let Some(done) = path.pop() {
+                            finished.insert(done);
+                        }
+                    }
+                }
+            }
+        }
+
+        cycles
+    }
+
+    /// Returns a lazy iterator over every path from a root to a leaf, as the
+    /// variants along it in order.
+    ///
+    /// A leaf is a variant with no successors. Paths are produced one at a
+    /// time in a stable order, so a caller can stop early on a graph with
+    /// more paths than it wants to look at.
+    ///
+    /// The number of paths can grow exponentially with the number of
+    /// branches; use [`SceneGraph::count_paths`] to size them up first.
+    pub fn paths(&self) -> Paths<'_> {
+        let mut roots: Vec<_> = self
+            .roots
+            .iter()
+            .filter(|root| self.edges.contains_key(root))
+            .copied()
+            .collect();
+        roots.sort_by_key(|id| std::cmp::Reverse(id.uuid()));
+
+        Paths {
+            graph: self,
+            roots,
+            path: Vec::new(),
+            stack: Vec::new(),
+        }
+    }
+
+    /// Returns the number of distinct paths from a root to a leaf, without
+    /// listing them.
+    ///
+    /// The count saturates at [`u64::MAX`]. In a graph with cycles (see
+    /// [`SceneGraph::validate`]) edges that close a loop are ignored.
+    pub fn count_paths(&self) -> u64 {
+        let mut counts: HashMap<Id<SceneVariant>, u64> = HashMap::new();
+        for (variant, successors) in self.post_order() {
+            let count = if successors.is_empty() {
+                1
+            } else {
+                successors
+                    .iter()
+                    .map(|s| counts[s])
+                    .fold(0, u64::saturating_add)
+            };
+            counts.insert(variant, count);
+        }
+
+        self.roots
+            .iter()
+            .filter_map(|root| counts.get(root))
+            .fold(0, |total, count| total.saturating_add(*count))
+    }
+
+    /// Returns the root-to-leaf path with the greatest total `weight`, or
+    /// `None` if the graph has no roots.
+    ///
+    /// Ties go to the path that comes first in [`SceneGraph::paths`] order.
+    pub fn longest_path(
+        &self,
+        weight: impl Fn(Id<SceneVariant>) -> usize,
+    ) -> Option<Vec<Id<SceneVariant>>> {
+        self.extreme_path(weight, |candidate, best| candidate > best)
+    }
+
+    /// Returns the root-to-leaf path with the least total `weight`, or
+    /// `None` if the graph has no roots.
+    ///
+    /// Ties go to the path that comes first in [`SceneGraph::paths`] order.
+    pub fn shortest_path(
+        &self,
+        weight: impl Fn(Id<SceneVariant>) -> usize,
+    ) -> Option<Vec<Id<SceneVariant>>> {
+        self.extreme_path(weight, |candidate, best| candidate < best)
+    }
+
+    /// Finds the root-to-leaf path whose total weight beats every other under
+    /// `better`.
+    fn extreme_path(
+        &self,
+        weight: impl Fn(Id<SceneVariant>) -> usize,
+        better: impl Fn(usize, usize) -> bool,
+    ) -> Option<Vec<Id<SceneVariant>>> {
+        // For each variant, the best total from it to a leaf and the successor
+        // that achieves it.
+        let mut best: HashMap<Id<SceneVariant>, (usize, Option<Id<SceneVariant>>)> = HashMap::new();
+        for (variant, successors) in self.post_order() {
+            let next = successors
+                .iter()
+                .map(|s| (best[s].0, *s))
+                .reduce(|a, b| if better(b.0, a.0) { b } else { a });
+            let total = weight(variant).saturating_add(next.map_or(0, |(total, _)| total));
+            best.insert(variant, (total, next.map(|(_, s)| s)));
+        }
+
+        let mut roots: Vec<_> = self.roots.iter().filter(|r| best.contains_key(r)).collect();
+        roots.sort_by_key(|id| id.uuid());
+        let mut current = roots
+            .into_iter()
+            .map(|root| (best[root].0, *root))
+            .reduce(|a, b| if better(b.0, a.0) { b } else { a })
+            .map(|(_, root)| root);
+
+        let mut path = Vec::new();
+        while let Some(variant) = current {
+            path.push(variant);
+            current = best[&variant].1;
+        }
+
+        (!path.is_empty()).then_some(path)
+    }
+
+    /// Lists every variant reachable from a root exactly once, each after all
+    /// of its successors, alongside those successors in ascending order.
+    ///
+    /// Edges that lead back onto the depth-first path would close a loop, so
+    /// they are left out of the successor lists.
+    fn post_order(&self) -> Vec<(Id<SceneVariant>, Vec<Id<SceneVariant>>)> {
+        let mut roots: Vec<_> = self
+            .roots
+            .iter()
+            .filter(|root| self.edges.contains_key(root))
+            .copied()
+            .collect();
+        roots.sort_by_key(|id| id.uuid());
+
+        let mut order = Vec::new();
+        let mut finished = HashSet::new();
+        for root in roots {
+            if finished.contains(&root) {
+                continue;
+            }
+
+            let mut path = vec![root];
+            let mut stack = vec![(self.pending_successors(root), Vec::new())];
+            while let Some((pending, kept)) = stack.last_mut() {
+                let Some(next) = pending.pop() else {
+                    if let (Some((_, kept)), Some(variant)) = (stack.pop(), path.pop()) {
+                        finished.insert(variant);
+                        order.push((variant, kept));
+                    }
+                    continue;
+                };
+
+                if path.contains(&next) {
+                    continue;
+                }
+
+                kept.push(next);
+                if !finished.contains(&next) {
+                    path.push(next);
+                    stack.push((self.pending_successors(next), Vec::new()));
+                }
+            }
+        }
+
+        order
+    }
+
+    /// Returns the successors of `variant` that are in the graph, in
+    /// descending order so that popping visits them in ascending order.
+    fn pending_successors(&self, variant: Id<SceneVariant>) -> Vec<Id<SceneVariant>> {
+        let mut next: Vec<_> = self
+            .next_variants(variant)
+            .filter(|id| self.edges.contains_key(id))
+            .collect();
+        next.sort_by_key(|id| std::cmp::Reverse(id.uuid()));
+        next
+    }
+
+    /// Returns `false` if moving `variant` to `dest` would merge it into an
+    /// edge that already exists.
+    fn is_movable(
+        &self,
+        variant: Id<SceneVariant>,
+        src: Id<SceneVariant>,
+        dest: Id<SceneVariant>,
+    ) -> bool {
+        src == dest || !self.edges.get(&dest).is_some_and(|e| e.contains(&variant))
+    }
+
     /// Determines whether `target` is reachable from `start` in the scene graph.
     ///
     /// This method performs a depth-first traversal beginning at `start` and
@@ -443,6 +877,59 @@ impl SceneGraph {
     }
 }
 
+/// A lazy iterator over the root-to-leaf paths of a [`SceneGraph`], created
+/// by [`SceneGraph::paths`].
+///
+/// Roots are taken in ascending id order and successors likewise, so the
+/// order is stable for a given graph. In a graph with cycles, an edge back
+/// onto the current path is treated as absent.
+pub struct Paths<'a> {
+    graph: &'a SceneGraph,
+    /// Roots not yet started, in descending order.
+    roots: Vec<Id<SceneVariant>>,
+    path: Vec<Id<SceneVariant>>,
+    /// The successors still to visit for each variant on `path`.
+    stack: Vec<Vec<Id<SceneVariant>>>,
+}
+
+impl Paths<'_> {
+    /// Steps onto `variant`, returning the path if it ends there.
+    fn enter(&mut self, variant: Id<SceneVariant>) -> Option<Vec<Id<SceneVariant>>> {
+        self.path.push(variant);
+        let mut pending = self.graph.pending_successors(variant);
+        pending.retain(|next| !self.path.contains(next));
+
+        let leaf = pending.is_empty();
+        self.stack.push(pending);
+        leaf.then(|| self.path.clone())
+    }
+}
+
+impl Iterator for Paths<'_> {
+    type Item = Vec<Id<SceneVariant>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let next = match self.stack.last_mut() {
+                Some(pending) => pending.pop(),
+                None => Some(self.roots.pop()?),
+            };
+
+            match next {
+                Some(variant) => {
+                    if let Some(path) = self.enter(variant) {
+                        return Some(path);
+                    }
+                }
+                None => {
+                    self.stack.pop();
+                    self.path.pop();
+                }
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::models::{
@@ -778,4 +1265,170 @@ mod tests {
             Err(SceneGraphError::UnknownVariant(random_id))
         )
     }
+
+    #[test]
+    fn test_inverse_updates_restore_a_removed_variant() {
+        // ARRANGE
+        let (mut graph, variant_ids) = generate_test_components();
+        let before = graph.clone();
+        let updates = graph.remove_variant(variant_ids[0]);
+        // ACT
+        for update in updates.iter().rev() {
+            graph.apply(&update.inverse()).unwrap();
+        }
+        // ASSERT
+        assert_eq!(graph, before);
+    }
+
+    #[test]
+    fn test_applying_a_stale_update_throws_stale_update_error() {
+        // ARRANGE
+        let (mut graph, variant_ids) = generate_test_components();
+        let update = SceneGraphUpdate::EdgeAdded {
+            src: variant_ids[0],
+            dest: variant_ids[1],
+        };
+        // ACT
+        let response = graph.apply(&update);
+        // ASSERT
+        assert_eq!(response, Err(SceneGraphError::StaleUpdate(update)))
+    }
+
+    #[test]
+    fn test_removing_a_connected_variant_by_update_is_stale() {
+        // ARRANGE
+        let (mut graph, variant_ids) = generate_test_components();
+        let update = SceneGraphUpdate::SceneVariantRemoved(variant_ids[1]);
+        // ACT
+        let response = graph.apply(&update);
+        // ASSERT
+        assert_eq!(response, Err(SceneGraphError::StaleUpdate(update)));
+        assert!(graph.edges.contains_key(&variant_ids[1]))
+    }
+
+    #[test]
+    fn test_adding_an_edge_that_closes_a_loop_throws_cycle_detected_error() {
+        // ARRANGE
+        let (mut graph, variant_ids) = generate_test_components();
+        let before = graph.clone();
+        // ACT
+        let response = graph.add_edge(variant_ids[2], variant_ids[0]);
+        // ASSERT
+        assert_eq!(
+            response,
+            Err(SceneGraphError::CycleDetected {
+                variant: variant_ids[0],
+                dest: variant_ids[2]
+            })
+        );
+        assert_eq!(graph, before);
+    }
+
+    #[test]
+    fn test_adding_a_self_loop_throws_cycle_detected_error() {
+        // ARRANGE
+        let (mut graph, variant_ids) = generate_test_components();
+        // ACT
+        let response = graph.add_edge(variant_ids[1], variant_ids[1]);
+        // ASSERT
+        assert_eq!(
+            response,
+            Err(SceneGraphError::CycleDetected {
+                variant: variant_ids[1],
+                dest: variant_ids[1]
+            })
+        )
+    }
+
+    #[test]
+    fn test_validating_a_graph_built_through_the_api_finds_nothing() {
+        // ARRANGE
+        let (graph, _) = generate_test_components();
+        // ACT
+        let report = graph.validate();
+        // ASSERT
+        assert!(report.is_valid());
+    }
+
+    #[test]
+    fn test_validating_reports_cycles_missing_roots_and_dangling_edges() {
+        // ARRANGE
+        let (mut graph, variant_ids) = generate_test_components();
+        let (missing, unknown) = (Id::new(), Id::new());
+        graph.roots.insert(missing);
+        graph
+            .edges
+            .get_mut(&variant_ids[2])
+            .unwrap()
+            .insert(variant_ids[1]);
+        graph
+            .edges
+            .get_mut(&variant_ids[0])
+            .unwrap()
+            .insert(unknown);
+        // ACT
+        let report = graph.validate();
+        // ASSERT
+        assert_eq!(report.cycles.len(), 1);
+        let mut cycle = report.cycles[0].clone();
+        cycle.sort_by_key(|id| id.uuid());
+        let mut expected = vec![variant_ids[1], variant_ids[2]];
+        expected.sort_by_key(|id| id.uuid());
+        assert_eq!(cycle, expected);
+        assert_eq!(report.missing_roots, vec![missing]);
+        assert_eq!(report.dangling_edges, vec![(variant_ids[0], unknown)]);
+        assert!(!report.is_valid());
+    }
+
+    /// Adds a fourth variant after the second and third, making a diamond
+    /// with the existing chain and a new edge from the root to the third.
+    fn generate_diamond() -> (SceneGraph, Vec<Id<SceneVariant>>) {
+        let (mut graph, mut variant_ids) = generate_test_components();
+        let last = Id::new();
+        graph.add_variant(last);
+        graph.add_edge(variant_ids[0], variant_ids[2]).unwrap();
+        graph.add_edge(variant_ids[1], last).unwrap();
+        graph.add_edge(variant_ids[2], last).unwrap();
+        variant_ids.push(last);
+
+        (graph, variant_ids)
+    }
+
+    #[test]
+    fn test_paths_lists_every_root_to_leaf_path() {
+        // ARRANGE
+        let (graph, v) = generate_diamond();
+        // ACT
+        let paths: Vec<_> = graph.paths().collect();
+        // ASSERT
+        assert_eq!(paths.len(), 3);
+        assert!(paths.contains(&vec![v[0], v[1], v[2], v[3]]));
+        assert!(paths.contains(&vec![v[0], v[1], v[3]]));
+        assert!(paths.contains(&vec![v[0], v[2], v[3]]));
+        assert_eq!(graph.count_paths(), 3);
+    }
+
+    #[test]
+    fn test_paths_are_produced_lazily() {
+        // ARRANGE
+        let (graph, _) = generate_diamond();
+        // ACT
+        let first = graph.paths().take(1).count();
+        // ASSERT
+        assert_eq!(first, 1);
+    }
+
+    #[test]
+    fn test_longest_and_shortest_paths_follow_the_weights() {
+        // ARRANGE
+        let (graph, v) = generate_diamond();
+        // ACT
+        let longest = graph.longest_path(|_| 1);
+        let v2_heavy = graph.shortest_path(|id| if id == v[2] { 10 } else { 1 });
+        let v1_heavy = graph.shortest_path(|id| if id == v[1] { 10 } else { 1 });
+        // ASSERT
+        assert_eq!(longest, Some(vec![v[0], v[1], v[2], v[3]]));
+        assert_eq!(v2_heavy, Some(vec![v[0], v[1], v[3]]));
+        assert_eq!(v1_heavy, Some(vec![v[0], v[2], v[3]]));
+    }
 }
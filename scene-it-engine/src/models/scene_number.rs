@@ -0,0 +1,234 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::{
+    models::{
+        Id,
+        scene::{Scene, SceneVariant},
+    },
+    utils::{InputError, validate_input},
+};
+
+/// A shooting-script scene number, such as `12`, `12A` for a scene inserted
+/// after 12, or `A1` for one inserted before the first.
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct SceneNumber(String);
+
+impl SceneNumber {
+    /// Creates a scene number. Letters are uppercased, as scripts print them.
+    pub fn new(input: &str) -> Result<Self, InputError> {
+        Ok(Self(validate_input(&input.to_uppercase(), Some(10))?))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns the number given to the `n`th scene of a freshly numbered script.
+    pub(crate) fn nth(n: usize) -> Self {
+        Self(n.to_string())
+    }
+
+    /// Returns the next insert number after this one: `12` gives `12A`,
+    /// `12A` gives `12B`, and `12Z` gives `12ZA`.
+    pub(crate) fn next_after(&self) -> Self {
+        let mut label = self.0.clone();
+        match label.chars().last() {
+            Some(last @ 'A'..='Y') => {
+                label.pop();
+                label.push(char::from(last as u8 + 1));
+            }
+            _ => label.push('A'),
+        }
+
+        Self(label)
+    }
+
+    /// Returns the next insert number before this one: `1` gives `A1`,
+    /// `A1` gives `B1`, and `Z1` gives `AZ1`.
+    pub(crate) fn next_before(&self) -> Self {
+        let mut chars = self.0.chars();
+        let label = match chars.next() {
+            Some(first @ 'A'..='Y') => format!("{}{}", char::from(first as u8 + 1), chars.as_str()),
+            _ => format!("A{}", self.0),
+        };
+
+        Self(label)
+    }
+}
+
+impl fmt::Display for SceneNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// A scene number given out in a [`Narrative`](crate::models::Narrative), and
+/// the scene that holds it.
+///
+/// Numbers stay with their scenes once given. When a numbered scene is
+/// removed its number stays behind, and the script shows it as `OMITTED`.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct NumberedScene {
+    number: SceneNumber,
+    /// The scene holding the number, or `None` for a number that was
+    /// already omitted in an imported script.
+    scene: Option<Id<Scene>>,
+}
+
+impl NumberedScene {
+    pub(crate) fn new(number: SceneNumber, scene: Option<Id<Scene>>) -> Self {
+        Self { number, scene }
+    }
+
+    pub fn number(&self) -> &SceneNumber {
+        &self.number
+    }
+
+    /// Returns the scene holding the number, which may since have been removed.
+    pub fn scene(&self) -> Option<Id<Scene>> {
+        self.scene
+    }
+}
+
+/// One entry of a shooting script: a scene on the path, or the number of a
+/// scene that has been omitted.
+#[derive(Debug, PartialEq)]
+pub enum ScriptScene<'a> {
+    Scene {
+        /// The scene's number, or `None` if the script is not numbered or
+        /// the scene was added since it was last numbered.
+        number: Option<&'a SceneNumber>,
+        scene: &'a Scene,
+        variant: &'a SceneVariant,
+    },
+    Omitted(&'a SceneNumber),
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::{
+        BranchPolicy, Id, Narrative, NumberedScene, Scene, SceneNumber, SceneVariant, ScriptScene,
+    };
+
+    /// A narrative of three scenes in a row, numbered `12`, `12A`, and `13`.
+    fn generate_test_components() -> (Narrative, Vec<Id<SceneVariant>>) {
+        let mut narrative = Narrative::default();
+        let mut variants = Vec::new();
+        let mut numbers = Vec::new();
+        for label in ["12", "12A", "13"] {
+            let scene = Scene::new();
+            variants.push(*scene.active_variant());
+            numbers.push(NumberedScene::new(number(label), Some(scene.id())));
+            narrative.add_scene(scene).unwrap();
+        }
+        narrative.set_variant_as_root(variants[0]).unwrap();
+        narrative.link_variants(variants[0], variants[1]).unwrap();
+        narrative.link_variants(variants[1], variants[2]).unwrap();
+        narrative.set_scene_numbers(numbers);
+
+        (narrative, variants)
+    }
+
+    fn number(label: &str) -> SceneNumber {
+        SceneNumber::new(label).unwrap()
+    }
+
+    /// Moves the scene of `variant` to between `src` and `dest`.
+    fn insert_between(
+        narrative: &mut Narrative,
+        variant: Id<SceneVariant>,
+        src: Id<SceneVariant>,
+        dest: Id<SceneVariant>,
+    ) {
+        narrative.unlink_variants(src, dest).unwrap();
+        narrative.link_variants(src, variant).unwrap();
+        narrative.link_variants(variant, dest).unwrap();
+    }
+
+    fn script(narrative: &Narrative, root: Id<SceneVariant>) -> Vec<String> {
+        narrative
+            .linearize_numbered(root, &BranchPolicy::default())
+            .unwrap()
+            .into_iter()
+            .map(|entry| match entry {
+                ScriptScene::Scene { number, .. } => number.unwrap().to_string(),
+                ScriptScene::Omitted(number) => format!("{number} OMITTED"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_insert_numbers_roll_past_z() {
+        // ARRANGE
+        let (after, before) = (number("12Z"), number("Z1"));
+        // ACT
+        let (after, before) = (after.next_after(), before.next_before());
+        // ASSERT
+        assert_eq!(after, number("12ZA"));
+        assert_eq!(before, number("AZ1"));
+        assert_eq!(number("12").next_after(), number("12A"));
+        assert_eq!(number("A1").next_before(), number("B1"));
+    }
+
+    #[test]
+    fn test_an_insert_before_an_existing_insert_is_numbered_before_it() {
+        // ARRANGE
+        let (mut narrative, variants) = generate_test_components();
+        let inserted = Scene::new();
+        let (inserted_id, inserted_variant) = (inserted.id(), *inserted.active_variant());
+        narrative.add_scene(inserted).unwrap();
+        insert_between(&mut narrative, inserted_variant, variants[0], variants[1]);
+        // ACT
+        narrative
+            .number_scenes(variants[0], &BranchPolicy::default())
+            .unwrap();
+        // ASSERT
+        assert_eq!(narrative.scene_number(&inserted_id), Some(&number("A12A")));
+        assert_eq!(script(&narrative, variants[0]), ["12", "A12A", "12A", "13"]);
+    }
+
+    #[test]
+    fn test_an_insert_steps_past_a_number_omitted_before_it() {
+        // ARRANGE
+        let (mut narrative, variants) = generate_test_components();
+        let omitted = narrative.scene_for_variant(&variants[1]).unwrap().id();
+        narrative.remove_scene(omitted).unwrap();
+        let inserted = Scene::new();
+        let (inserted_id, inserted_variant) = (inserted.id(), *inserted.active_variant());
+        narrative.add_scene(inserted).unwrap();
+        narrative
+            .link_variants(variants[0], inserted_variant)
+            .unwrap();
+        narrative
+            .link_variants(inserted_variant, variants[2])
+            .unwrap();
+        // ACT
+        narrative
+            .number_scenes(variants[0], &BranchPolicy::default())
+            .unwrap();
+        // ASSERT
+        assert_eq!(narrative.scene_number(&inserted_id), Some(&number("12B")));
+        assert_eq!(
+            script(&narrative, variants[0]),
+            ["12", "12A OMITTED", "12B", "13"]
+        );
+    }
+
+    #[test]
+    fn test_numbers_stay_with_a_scene_that_moves() {
+        // ARRANGE
+        let (mut narrative, variants) = generate_test_components();
+        let moved = narrative.scene_for_variant(&variants[2]).unwrap().id();
+        narrative.unlink_variants(variants[1], variants[2]).unwrap();
+        insert_between(&mut narrative, variants[2], variants[0], variants[1]);
+        // ACT
+        let updates = narrative
+            .number_scenes(variants[0], &BranchPolicy::default())
+            .unwrap();
+        // ASSERT
+        assert!(updates.is_empty());
+        assert_eq!(narrative.scene_number(&moved), Some(&number("13")));
+        assert_eq!(script(&narrative, variants[0]), ["12", "13", "12A"]);
+    }
+}
@@ -1,10 +1,22 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use serde::{Deserialize, Serialize};
 
 use crate::models::{
-    Id, author::Author, character::Character, metadata::Metadata, narrative::Narrative,
-    summary::Summary, title::Title,
+    HasMetadata, Id,
+    author::Author,
+    character::{Character, CharacterError},
+    cut::{Cut, CutError, CutName},
+    metadata::{LockedEntity, Metadata, RevisionNote, Unlock},
+    narrative::BranchPolicy,
+    narrative::{Narrative, NarrativeError, NarrativeUpdate},
+    relationship::{Relationship, RelationshipError},
+    revision::{RevisionColor, RevisionSet},
+    scene::{Scene, SceneVariant},
+    scene_element::{Dialogue, SceneElement},
+    scene_graph::SceneGraphError,
+    summary::Summary,
+    title::Title,
 };
 
 /// Represents the different types of script formats available.
@@ -15,7 +27,7 @@ use crate::models::{
 /// - Screenplay
 /// - Half-hour Sitcom
 /// - Novel
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub enum StoryTemplate {
     /// A script formatted for television production.
     Teleplay,
@@ -27,16 +39,118 @@ pub enum StoryTemplate {
     Novel,
 }
 
+/// A change to a [`Storyboard`], recorded so that it can be replayed.
+///
+/// Each variant corresponds to one mutating method; changes to the story's
+/// structure are carried as the [`NarrativeUpdate`]s the narrative emits.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum StoryboardChange {
+    /// The title was set or replaced.
+    TitleUpdated(Title),
+    /// The title was removed.
+    TitleCleared,
+    /// The summary was set or replaced.
+    SummaryUpdated(Summary),
+    /// The story template was set or replaced.
+    TemplateUpdated(StoryTemplate),
+    /// The story template was removed.
+    TemplateCleared,
+    /// An author was added or replaced.
+    AuthorAdded(Author),
+    /// An author was removed.
+    AuthorRemoved(Id<Author>),
+    /// A character was added or replaced.
+    CharacterAdded(Character),
+    /// A character was removed, and their lines given to `lines_to` if set.
+    CharacterRemoved {
+        character: Id<Character>,
+        lines_to: Option<Id<Character>>,
+    },
+    /// A relationship between characters was added or replaced.
+    RelationshipAdded(Relationship),
+    /// A relationship between characters was removed.
+    RelationshipRemoved(Id<Relationship>),
+    /// A cut was created or changed; carries the cut as it now stands.
+    CutSaved(Cut),
+    /// A cut was deleted.
+    CutDeleted(Id<Cut>),
+    /// A revision set was opened on the given color.
+    RevisionOpened(RevisionColor, RevisionNote),
+    /// The storyboard, or something in its narrative, was locked.
+    Locked(LockedEntity, RevisionNote),
+    /// An admin author lifted a lock.
+    Unlocked {
+        by: Id<Author>,
+        entity: LockedEntity,
+        note: RevisionNote,
+    },
+    /// An admin author made another author an admin, or withdrew it.
+    AdminSet {
+        by: Id<Author>,
+        author: Id<Author>,
+        admin: bool,
+    },
+    /// The narrative changed.
+    Narrative(NarrativeUpdate),
+}
+
+impl From<NarrativeUpdate> for StoryboardChange {
+    fn from(value: NarrativeUpdate) -> Self {
+        StoryboardChange::Narrative(value)
+    }
+}
+
+/// Errors that can occur when applying a recorded [`StoryboardChange`].
+#[derive(Debug, Serialize, PartialEq)]
+pub enum ChangeError {
+    /// The change could not be applied to the narrative, or the storyboard
+    /// is locked.
+    Narrative(NarrativeError),
+    /// A character could not be removed.
+    Character(CharacterError),
+    /// A relationship could not be added.
+    Relationship(RelationshipError),
+}
+
+impl From<NarrativeError> for ChangeError {
+    fn from(value: NarrativeError) -> Self {
+        ChangeError::Narrative(value)
+    }
+}
+
+impl From<CharacterError> for ChangeError {
+    fn from(value: CharacterError) -> Self {
+        ChangeError::Character(value)
+    }
+}
+
+impl From<RelationshipError> for ChangeError {
+    fn from(value: RelationshipError) -> Self {
+        ChangeError::Relationship(value)
+    }
+}
+
 /// The `Storyboard` is the project workbench and packages all of the story details.
 ///
 /// From the storyboard, a user can:
 /// - Add, edit, or remove a `Title`
 /// - Create, edit, and delete a `Scene`
 /// - Create, edit, and delete a `Character`
+/// - Relate characters to each other, for the whole story or a span of scenes
 /// - Select and update the `StoryTemplate`
 /// - Add and remove an `Author`
+/// - Save named `Cut`s of the story
+/// - Open colored `RevisionSet`s once the script is in production
 /// - Generate a story outline
-#[derive(Serialize, Deserialize)]
+///
+/// While the storyboard is locked, its own details (title, summary,
+/// template, authors, characters, relationships, cuts, and revision sets)
+/// cannot be changed; each method that would change them fails with
+/// [`NarrativeError::Locked`]. Scenes are locked one by one, and the
+/// narrative enforces their locks. An admin author can make changes past
+/// every lock with [`Storyboard::override_locks`], and only an admin can
+/// lift a lock, with [`Storyboard::unlock_entity`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Storyboard {
     /// The working title of the story, if one has been set.
     title: Option<Title>,
@@ -46,15 +160,33 @@ pub struct Storyboard {
     characters: HashMap<Id<Character>, Character>,
     /// The scenes and their relationships that make up the story.
     narrative: Narrative,
+    /// How the characters relate to each other, keyed by ID.
+    #[serde(default)]
+    relationships: HashMap<Id<Relationship>, Relationship>,
+    /// The named cuts of the story, keyed by ID.
+    #[serde(default)]
+    cuts: HashMap<Id<Cut>, Cut>,
     /// The script format the story is being written for, if one has been selected.
     template: Option<StoryTemplate>,
     /// A summary of the story.
     summary: Summary,
+    /// The production revision sets, oldest first; the last is the current one.
+    #[serde(default)]
+    revisions: Vec<RevisionSet>,
     /// Bookkeeping metadata (e.g. creation and modification timestamps) for the storyboard.
     metadata: Metadata,
 }
 
 impl Storyboard {
+    /// Creates an empty storyboard, as [`Storyboard::default`] does, with
+    /// `admin` as its first author and first admin.
+    pub fn with_admin(mut admin: Author) -> Self {
+        admin.set_admin(true);
+        let mut storyboard = Self::default();
+        storyboard.authors.insert(admin.id(), admin);
+        storyboard
+    }
+
     /// Returns the storyboard's title, if one has been set.
     pub fn title(&self) -> &Option<Title> {
         &self.title
@@ -70,6 +202,31 @@ impl Storyboard {
         self.characters.values().collect()
     }
 
+    /// Returns the character with the given ID, if it is attached to the storyboard.
+    pub fn character(&self, character_id: &Id<Character>) -> Option<&Character> {
+        self.characters.get(character_id)
+    }
+
+    /// Returns the scenes and scene graph that make up the story.
+    pub fn narrative(&self) -> &Narrative {
+        &self.narrative
+    }
+
+    /// Returns the storyboard's narrative for structural edits.
+    pub fn narrative_mut(&mut self) -> &mut Narrative {
+        &mut self.narrative
+    }
+
+    /// Returns all cuts of the story.
+    pub fn cuts(&self) -> Vec<&Cut> {
+        self.cuts.values().collect()
+    }
+
+    /// Returns the cut with the given ID, if it exists.
+    pub fn cut(&self, cut_id: &Id<Cut>) -> Option<&Cut> {
+        self.cuts.get(cut_id)
+    }
+
     /// Returns the storyboard's selected story template, if one has been chosen.
     pub fn template(&self) -> &Option<StoryTemplate> {
         &self.template
@@ -80,76 +237,1328 @@ impl Storyboard {
         &self.summary
     }
 
-    /// Returns the storyboard's metadata.
-    pub fn metadata(&self) -> &Metadata {
-        &self.metadata
-    }
-
     /// Sets or replaces the storyboard title.
     ///
     /// This overwrites any existing title. Titles are optional and may be
     /// added, removed, or changed at any point during storyboard development.
-    pub fn update_title(&mut self, title: Title) {
+    ///
+    /// # Errors
+    ///
+    /// Returns [`NarrativeError::Locked`] if the storyboard is locked.
+    pub fn update_title(&mut self, title: Title) -> Result<(), NarrativeError> {
+        self.check_unlocked()?;
         self.title = Some(title);
+        Ok(())
     }
 
     /// Removes the storyboard title, returning it to an unnamed state.
     ///
     /// This does not affect scenes, characters, or metadata.
-    pub fn clear_title(&mut self) {
+    ///
+    /// # Errors
+    ///
+    /// Returns [`NarrativeError::Locked`] if the storyboard is locked.
+    pub fn clear_title(&mut self) -> Result<(), NarrativeError> {
+        self.check_unlocked()?;
         self.title = None;
+        Ok(())
+    }
+
+    /// Sets or replaces the storyboard summary.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`NarrativeError::Locked`] if the storyboard is locked.
+    pub fn update_summary(&mut self, summary: Summary) -> Result<(), NarrativeError> {
+        self.check_unlocked()?;
+        self.summary = summary;
+        Ok(())
     }
 
     /// Sets or replaces the active story template.
     ///
     /// The template determines formatting rules and structural expectations
     /// (e.g. screenplay vs. novel), but does not immediately modify scene data.
-    pub fn update_template(&mut self, template: StoryTemplate) {
+    ///
+    /// # Errors
+    ///
+    /// Returns [`NarrativeError::Locked`] if the storyboard is locked.
+    pub fn update_template(&mut self, template: StoryTemplate) -> Result<(), NarrativeError> {
+        self.check_unlocked()?;
         self.template = Some(template);
+        Ok(())
     }
 
     /// Clears the currently selected story template.
     ///
     /// After clearing, the storyboard has no enforced formatting or structure
     /// until a new template is selected.
-    pub fn clear_template(&mut self) {
+    ///
+    /// # Errors
+    ///
+    /// Returns [`NarrativeError::Locked`] if the storyboard is locked.
+    pub fn clear_template(&mut self) -> Result<(), NarrativeError> {
+        self.check_unlocked()?;
         self.template = None;
+        Ok(())
     }
 
     /// Adds an author to the storyboard.
     ///
     /// If an author with the same ID already exists, it will be replaced.
-    pub fn add_author(&mut self, author: Author) {
+    /// An admin cannot be added this way: the first admin comes with the
+    /// storyboard, from [`Storyboard::with_admin`], and after that only an
+    /// admin can make or unmake one, with [`Storyboard::set_admin`].
+    ///
+    /// # Errors
+    ///
+    /// - [`NarrativeError::Locked`] if the storyboard is locked.
+    /// - [`NarrativeError::AdminRequired`] if the author is an admin, or
+    ///   would replace one.
+    pub fn add_author(&mut self, author: Author) -> Result<(), NarrativeError> {
+        self.check_unlocked()?;
+        self.check_not_admin(author.id())?;
+        if author.is_admin() {
+            return Err(NarrativeError::AdminRequired(author.id()));
+        }
+
         self.authors.insert(author.id(), author);
+        Ok(())
     }
 
     /// Removes an author from the storyboard by ID.
     ///
     /// Removing an author does not affect scenes or other storyboard data.
-    pub fn remove_author(&mut self, author_id: &Id<Author>) {
+    ///
+    /// # Errors
+    ///
+    /// - [`NarrativeError::Locked`] if the storyboard is locked.
+    /// - [`NarrativeError::AdminRequired`] if the author is an admin; an
+    ///   admin must withdraw that with [`Storyboard::set_admin`] first.
+    pub fn remove_author(&mut self, author_id: &Id<Author>) -> Result<(), NarrativeError> {
+        self.check_unlocked()?;
+        self.check_not_admin(*author_id)?;
         self.authors.remove(author_id);
+        Ok(())
+    }
+
+    /// Makes `author` an admin, or withdraws it, as the admin `by`.
+    ///
+    /// # Errors
+    ///
+    /// - [`NarrativeError::Locked`] if the storyboard is locked.
+    /// - [`NarrativeError::NotAdmin`] if `by` is not an admin author of the
+    ///   storyboard.
+    /// - [`NarrativeError::UnknownAuthor`] if `author` is not one of the
+    ///   storyboard's authors.
+    pub fn set_admin(
+        &mut self,
+        by: Id<Author>,
+        author: Id<Author>,
+        admin: bool,
+    ) -> Result<(), NarrativeError> {
+        self.check_unlocked()?;
+        self.check_admin(by)?;
+        self.authors
+            .get_mut(&author)
+            .ok_or(NarrativeError::UnknownAuthor(author))?
+            .set_admin(admin);
+        Ok(())
     }
 
     /// Adds a character to the storyboard.
     ///
     /// If a character with the same ID already exists, it will be replaced.
-    pub fn add_character(&mut self, character: Character) {
+    ///
+    /// # Errors
+    ///
+    /// Returns [`NarrativeError::Locked`] if the storyboard is locked.
+    pub fn add_character(&mut self, character: Character) -> Result<(), NarrativeError> {
+        self.check_unlocked()?;
         self.characters.insert(character.id(), character);
+        Ok(())
+    }
+
+    /// Returns the character going by `name`, ignoring case.
+    ///
+    /// A character whose name it is comes before one who only has it as an
+    /// alias.
+    pub fn character_by_name(&self, name: &str) -> Option<&Character> {
+        let name = name.to_uppercase();
+        self.characters
+            .values()
+            .find(|c| c.name().to_uppercase() == name)
+            .or_else(|| self.characters.values().find(|c| c.answers_to(&name)))
+    }
+
+    /// Returns the character with the given ID for editing their profile.
+    ///
+    /// # Errors
+    ///
+    /// - [`CharacterError::UnknownCharacter`] if the character is not in the
+    ///   storyboard.
+    /// - [`CharacterError::Narrative`] with [`NarrativeError::Locked`] if the
+    ///   storyboard is locked.
+    pub fn character_mut(
+        &mut self,
+        character_id: Id<Character>,
+    ) -> Result<&mut Character, CharacterError> {
+        self.check_unlocked()?;
+        self.characters
+            .get_mut(&character_id)
+            .ok_or(CharacterError::UnknownCharacter(character_id))
+    }
+
+    /// Removes a character who has no lines, along with their relationships.
+    ///
+    /// # Errors
+    ///
+    /// - [`CharacterError::UnknownCharacter`] if the character is not in the
+    ///   storyboard.
+    /// - [`CharacterError::HasDialogue`] with every dialogue the character
+    ///   still speaks, in any variant. Nothing is removed.
+    /// - [`CharacterError::Narrative`] with [`NarrativeError::Locked`] if the
+    ///   storyboard is locked.
+    pub fn remove_character(
+        &mut self,
+        character_id: Id<Character>,
+    ) -> Result<Character, CharacterError> {
+        self.check_unlocked()?;
+        if !self.characters.contains_key(&character_id) {
+            return Err(CharacterError::UnknownCharacter(character_id));
+        }
+
+        let mut lines: Vec<Id<Dialogue>> = self
+            .narrative
+            .scenes()
+            .flat_map(|scene| scene.variants().values())
+            .flat_map(|variant| variant.elements())
+            .filter_map(|element| match element {
+                SceneElement::Dialogue(d) if d.speaker() == character_id => Some(d.id()),
+                _ => None,
+            })
+            .collect();
+        if !lines.is_empty() {
+            lines.sort_by_key(|id| id.uuid());
+            return Err(CharacterError::HasDialogue(lines));
+        }
+
+        self.relationships.retain(|_, r| !r.involves(character_id));
+        Ok(self
+            .characters
+            .remove(&character_id)
+            .expect("checked above"))
+    }
+
+    /// Removes a character, giving every line they speak to `lines_to`.
+    ///
+    /// # Errors
+    ///
+    /// - [`CharacterError::UnknownCharacter`] if either character is not in
+    ///   the storyboard.
+    /// - [`CharacterError::Narrative`] with [`NarrativeError::Locked`] if the
+    ///   storyboard, or a dialogue of the character's or the variant or scene
+    ///   holding it, is locked. Nothing is changed.
+    pub fn remove_character_reassigning(
+        &mut self,
+        character_id: Id<Character>,
+        lines_to: Id<Character>,
+    ) -> Result<Character, CharacterError> {
+        self.check_unlocked()?;
+        for id in [character_id, lines_to] {
+            if !self.characters.contains_key(&id) {
+                return Err(CharacterError::UnknownCharacter(id));
+            }
+        }
+        if character_id != lines_to {
+            self.narrative.reassign_speaker(character_id, lines_to)?;
+        }
+
+        self.remove_character(character_id)
+    }
+
+    /// Returns the first scene on the path starting at `root` that the
+    /// character appears in, and the variant the path visits; see
+    /// [`Character::appears_in`].
+    ///
+    /// # Errors
+    ///
+    /// - [`CharacterError::UnknownCharacter`] if the character is not in the
+    ///   storyboard.
+    /// - [`CharacterError::Narrative`] if the path cannot be linearized.
+    pub fn first_appearance(
+        &self,
+        character_id: Id<Character>,
+        root: Id<SceneVariant>,
+        policy: &BranchPolicy,
+    ) -> Result<Option<(&Scene, &SceneVariant)>, CharacterError> {
+        let character = self
+            .characters
+            .get(&character_id)
+            .ok_or(CharacterError::UnknownCharacter(character_id))?;
+
+        Ok(self
+            .narrative
+            .linearize_from(root, policy)?
+            .into_iter()
+            .find(|(_, variant)| character.appears_in(variant)))
+    }
+
+    /// Returns every relationship between characters, in no particular order.
+    pub fn relationships(&self) -> Vec<&Relationship> {
+        self.relationships.values().collect()
+    }
+
+    /// Returns the relationship with the given ID, if it exists.
+    pub fn relationship(&self, relationship_id: &Id<Relationship>) -> Option<&Relationship> {
+        self.relationships.get(relationship_id)
+    }
+
+    /// Returns every relationship `character_id` is either side of, at any
+    /// point in the story, in no particular order.
+    pub fn relationships_of(&self, character_id: Id<Character>) -> Vec<&Relationship> {
+        self.relationships
+            .values()
+            .filter(|r| r.involves(character_id))
+            .collect()
+    }
+
+    /// Adds a relationship between two characters.
+    ///
+    /// If a relationship with the same ID already exists, it will be replaced.
+    ///
+    /// # Errors
+    ///
+    /// - [`RelationshipError::UnknownCharacter`] if either character is not
+    ///   in the storyboard.
+    /// - [`RelationshipError::SelfRelationship`] if both sides are the same
+    ///   character.
+    /// - [`RelationshipError::UnknownScene`] if a scene bounding the
+    ///   relationship is not in the narrative.
+    /// - [`RelationshipError::Narrative`] with [`NarrativeError::Locked`] if
+    ///   the storyboard is locked.
+    pub fn add_relationship(
+        &mut self,
+        relationship: Relationship,
+    ) -> Result<(), RelationshipError> {
+        self.check_unlocked()?;
+        for id in [relationship.from(), relationship.to()] {
+            if !self.characters.contains_key(&id) {
+                return Err(RelationshipError::UnknownCharacter(id));
+            }
+        }
+        if relationship.from() == relationship.to() {
+            return Err(RelationshipError::SelfRelationship(relationship.from()));
+        }
+        for scene_id in [relationship.starts(), relationship.ends()]
+            .into_iter()
+            .flatten()
+        {
+            if self.narrative.scene(&scene_id).is_none() {
+                return Err(RelationshipError::UnknownScene(scene_id));
+            }
+        }
+
+        self.relationships.insert(relationship.id(), relationship);
+        Ok(())
+    }
+
+    /// Removes a relationship by ID, returning it.
+    ///
+    /// # Errors
+    ///
+    /// - [`RelationshipError::UnknownRelationship`] if the relationship does
+    ///   not exist.
+    /// - [`RelationshipError::Narrative`] with [`NarrativeError::Locked`] if
+    ///   the storyboard is locked.
+    pub fn remove_relationship(
+        &mut self,
+        relationship_id: Id<Relationship>,
+    ) -> Result<Relationship, RelationshipError> {
+        self.check_unlocked()?;
+        self.relationships
+            .remove(&relationship_id)
+            .ok_or(RelationshipError::UnknownRelationship(relationship_id))
+    }
+
+    /// Returns the relationships that hold at `scene_id` on the path
+    /// starting at `root`, ordered by ID.
+    ///
+    /// A scoped relationship holds from its starting scene until its ending
+    /// scene, as the path meets them; see [`Relationship`]. Filter with
+    /// [`Relationship::involves`] for one character's relationships.
+    ///
+    /// # Errors
+    ///
+    /// - [`RelationshipError::Narrative`] if the path cannot be linearized.
+    /// - [`RelationshipError::UnknownScene`] if the scene is not on the path.
+    pub fn relationships_as_of(
+        &self,
+        scene_id: Id<Scene>,
+        root: Id<SceneVariant>,
+        policy: &BranchPolicy,
+    ) -> Result<Vec<&Relationship>, RelationshipError> {
+        let path = self.narrative.linearize_from(root, policy)?;
+        let at = path
+            .iter()
+            .position(|(scene, _)| scene.id() == scene_id)
+            .ok_or(RelationshipError::UnknownScene(scene_id))?;
+
+        let mut relationships: Vec<_> = self
+            .relationships
+            .values()
+            .filter(|r| r.holds_at(&path, at))
+            .collect();
+        relationships.sort_by_key(|r| r.id().uuid());
+
+        Ok(relationships)
+    }
+
+    /// Returns the relationships that removing the scene with
+    /// [`Narrative::remove_scene`] would break, sorted by ID.
+    ///
+    /// A relationship breaks if it starts or ends at the scene: a start
+    /// that is gone is never reached, and an end that is gone never comes.
+    /// Check before removing the scene, and rescope or remove them; afterwards
+    /// [`Storyboard::broken_relationships`] reports them.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RelationshipError::UnknownScene`] if the scene does not exist.
+    pub fn relationships_broken_by_removing(
+        &self,
+        scene_id: Id<Scene>,
+    ) -> Result<Vec<Id<Relationship>>, RelationshipError> {
+        if self.narrative.scene(&scene_id).is_none() {
+            return Err(RelationshipError::UnknownScene(scene_id));
+        }
+
+        Ok(self.relationships_bounded_by(|scene| scene == scene_id))
+    }
+
+    /// Returns the relationships that start or end at a scene no longer in
+    /// the narrative, sorted by ID.
+    pub fn broken_relationships(&self) -> Vec<Id<Relationship>> {
+        self.relationships_bounded_by(|scene| self.narrative.scene(&scene).is_none())
+    }
+
+    /// Returns the relationships with a bound `matches`, sorted by ID.
+    fn relationships_bounded_by(
+        &self,
+        matches: impl Fn(Id<Scene>) -> bool,
+    ) -> Vec<Id<Relationship>> {
+        let mut bounded: Vec<_> = self
+            .relationships
+            .values()
+            .filter(|r| [r.starts(), r.ends()].into_iter().flatten().any(&matches))
+            .map(Relationship::id)
+            .collect();
+        bounded.sort_by_key(|id| id.uuid());
+
+        bounded
+    }
+
+    /// Locks the storyboard, or a scene, variant, or dialogue in its
+    /// narrative, recording `note` as the reason.
+    ///
+    /// Locking a scene or variant locks what it holds as well.
+    ///
+    /// # Errors
+    ///
+    /// - [`NarrativeError::UnknownScene`], [`NarrativeError::MissingVariant`],
+    ///   or [`NarrativeError::UnknownDialogue`] if `entity` is not in the
+    ///   narrative.
+    pub fn lock_entity(
+        &mut self,
+        entity: LockedEntity,
+        note: RevisionNote,
+    ) -> Result<(), NarrativeError> {
+        match entity {
+            LockedEntity::Storyboard => self.lock(note),
+            LockedEntity::Scene(scene_id) => self.narrative.lock_scene(scene_id, note)?,
+            LockedEntity::Variant(variant_id) => self
+                .narrative
+                .variant_mut(&variant_id)
+                .ok_or(NarrativeError::MissingVariant(variant_id))?
+                .lock(note),
+            LockedEntity::Dialogue(dialogue_id) => self
+                .narrative
+                .dialogue_mut(dialogue_id)
+                .ok_or(NarrativeError::UnknownDialogue(dialogue_id))?
+                .lock(note),
+        }
+        Ok(())
+    }
+
+    /// Lifts the lock on the storyboard, or on a scene, variant, or dialogue
+    /// in its narrative, as the admin `by`, recording `note` as the reason.
+    ///
+    /// Unlocking a scene or variant unlocks what it holds as well.
+    ///
+    /// # Errors
+    ///
+    /// - [`NarrativeError::NotAdmin`] if `by` is not an admin author of the
+    ///   storyboard. Nothing is changed.
+    /// - [`NarrativeError::UnknownScene`], [`NarrativeError::MissingVariant`],
+    ///   or [`NarrativeError::UnknownDialogue`] if `entity` is not in the
+    ///   narrative.
+    pub fn unlock_entity(
+        &mut self,
+        by: Id<Author>,
+        entity: LockedEntity,
+        note: RevisionNote,
+    ) -> Result<(), NarrativeError> {
+        self.check_admin(by)?;
+        match entity {
+            LockedEntity::Storyboard => self.metadata.set_locked(false, note),
+            LockedEntity::Scene(scene_id) => self.narrative.unlock_scene(scene_id, note)?,
+            LockedEntity::Variant(variant_id) => self
+                .narrative
+                .variant_mut(&variant_id)
+                .ok_or(NarrativeError::MissingVariant(variant_id))?
+                .unlock(note),
+            LockedEntity::Dialogue(dialogue_id) => self
+                .narrative
+                .dialogue_mut(dialogue_id)
+                .ok_or(NarrativeError::UnknownDialogue(dialogue_id))?
+                .unlock(note),
+        }
+        Ok(())
+    }
+
+    /// Makes changes as `author`, past every lock in the storyboard.
+    ///
+    /// The locks are lifted while `edit` runs and put back afterwards on
+    /// whatever is still there, even if `edit` panics; anything `edit` adds
+    /// stays unlocked. `note` is recorded on the storyboard.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`NarrativeError::NotAdmin`] if `author` is not an admin
+    /// author of the storyboard. Nothing is changed.
+    pub fn override_locks<T>(
+        &mut self,
+        author: Id<Author>,
+        note: RevisionNote,
+        edit: impl FnOnce(&mut Storyboard) -> T,
+    ) -> Result<T, NarrativeError> {
+        self.check_admin(author)?;
+
+        let result = {
+            let guard = SuspendedLocks::new(self);
+            edit(guard.storyboard)
+        };
+        self.metadata.add_revision_note(note);
+
+        Ok(result)
+    }
+
+    /// Returns the production revision sets, oldest first.
+    pub fn revisions(&self) -> &[RevisionSet] {
+        &self.revisions
+    }
+
+    /// Returns the revision set changes are being tracked in, if one has been opened.
+    pub fn current_revision(&self) -> Option<&RevisionSet> {
+        self.revisions.last()
+    }
+
+    /// Opens a revision set on `color`, which becomes the current one.
+    ///
+    /// From then on, every element that is added, moved, or whose text
+    /// changes counts as revised in the new set; see [`RevisionSet`]. `note` is recorded on
+    /// the storyboard, and the set keeps the version it brings the storyboard to.
+    ///
+    /// Colors are issued in order: the locked draft itself is white, so the
+    /// first set goes out on Blue, and each set after it on the
+    /// [next color](RevisionColor::next) after the current one.
+    ///
+    /// # Errors
+    ///
+    /// - [`NarrativeError::Locked`] if the storyboard is locked.
+    /// - [`NarrativeError::RevisionOutOfOrder`] if `color` is not the next
+    ///   color to be issued.
+    /// - [`NarrativeError::RevisionColorsExhausted`] if the current set is
+    ///   already on the last color.
+    pub fn open_revision(
+        &mut self,
+        color: RevisionColor,
+        note: RevisionNote,
+    ) -> Result<(), NarrativeError> {
+        self.check_unlocked()?;
+        let expected = match self.current_revision() {
+            Some(set) => set
+                .color()
+                .next()
+                .ok_or(NarrativeError::RevisionColorsExhausted { found: color })?,
+            None => RevisionColor::Blue,
+        };
+        if color != expected {
+            return Err(NarrativeError::RevisionOutOfOrder {
+                expected,
+                found: color,
+            });
+        }
+
+        self.metadata.add_revision_note(note.clone());
+        self.revisions.push(RevisionSet::open(
+            color,
+            note,
+            self.metadata.version,
+            &self.narrative,
+        ));
+
+        Ok(())
+    }
+
+    /// Creates a cut that starts at `root` and has made no choices yet.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CutError::Narrative`] with [`SceneGraphError::UnknownVariant`]
+    /// if `root` is not in the narrative, or with [`NarrativeError::Locked`]
+    /// if the storyboard is locked.
+    pub fn create_cut(
+        &mut self,
+        name: CutName,
+        root: Id<SceneVariant>,
+    ) -> Result<Id<Cut>, CutError> {
+        self.check_unlocked()?;
+        if !self.narrative.graph().contains(root) {
+            return Err(NarrativeError::Graph(SceneGraphError::UnknownVariant(root)).into());
+        }
+
+        let cut = Cut::new(name, root);
+        let cut_id = cut.id();
+        self.cuts.insert(cut_id, cut);
+        Ok(cut_id)
+    }
+
+    /// Renames a cut.
+    ///
+    /// # Errors
+    ///
+    /// - [`CutError::UnknownCut`] if the cut does not exist.
+    /// - [`CutError::Narrative`] with [`NarrativeError::Locked`] if the
+    ///   storyboard is locked.
+    pub fn rename_cut(&mut self, cut_id: Id<Cut>, name: CutName) -> Result<(), CutError> {
+        self.cut_mut(cut_id)?.rename(name);
+        Ok(())
+    }
+
+    /// Copies a cut under a new name, returning the copy's ID.
+    ///
+    /// The copy has the same root and choices, and its own metadata.
+    ///
+    /// # Errors
+    ///
+    /// - [`CutError::UnknownCut`] if the cut does not exist.
+    /// - [`CutError::Narrative`] with [`NarrativeError::Locked`] if the
+    ///   storyboard is locked.
+    pub fn duplicate_cut(&mut self, cut_id: Id<Cut>, name: CutName) -> Result<Id<Cut>, CutError> {
+        self.check_unlocked()?;
+        let copy = self
+            .cuts
+            .get(&cut_id)
+            .ok_or(CutError::UnknownCut(cut_id))?
+            .duplicate(name);
+        let copy_id = copy.id();
+        self.cuts.insert(copy_id, copy);
+        Ok(copy_id)
+    }
+
+    /// Deletes a cut, returning it.
+    ///
+    /// # Errors
+    ///
+    /// - [`CutError::UnknownCut`] if the cut does not exist.
+    /// - [`CutError::Narrative`] with [`NarrativeError::Locked`] if the
+    ///   storyboard is locked.
+    pub fn delete_cut(&mut self, cut_id: Id<Cut>) -> Result<Cut, CutError> {
+        self.check_unlocked()?;
+        self.cuts
+            .remove(&cut_id)
+            .ok_or(CutError::UnknownCut(cut_id))
+    }
+
+    /// Records that the cut takes `choice` at the branch after `at`,
+    /// replacing any earlier choice there.
+    ///
+    /// # Errors
+    ///
+    /// - [`CutError::UnknownCut`] if the cut does not exist.
+    /// - [`CutError::Narrative`] with [`SceneGraphError::UnknownVariant`] if
+    ///   `at` is not in the narrative, with [`NarrativeError::InvalidChoice`]
+    ///   if `choice` does not follow `at`, or with [`NarrativeError::Locked`]
+    ///   if the storyboard is locked.
+    pub fn choose_in_cut(
+        &mut self,
+        cut_id: Id<Cut>,
+        at: Id<SceneVariant>,
+        choice: Id<SceneVariant>,
+    ) -> Result<(), CutError> {
+        self.check_unlocked()?;
+        let graph = self.narrative.graph();
+        if !graph.contains(at) {
+            return Err(NarrativeError::Graph(SceneGraphError::UnknownVariant(at)).into());
+        }
+        if !graph.next_variants(at).any(|next| next == choice) {
+            return Err(NarrativeError::InvalidChoice {
+                variant: at,
+                choice,
+            }
+            .into());
+        }
+
+        self.cut_mut(cut_id)?.set_choice(at, choice);
+        Ok(())
+    }
+
+    /// Forgets the cut's choice at the branch after `at`, if it made one, so
+    /// the branch falls back to the active variant.
+    ///
+    /// # Errors
+    ///
+    /// - [`CutError::UnknownCut`] if the cut does not exist.
+    /// - [`CutError::Narrative`] with [`NarrativeError::Locked`] if the
+    ///   storyboard is locked.
+    pub fn clear_cut_choice(
+        &mut self,
+        cut_id: Id<Cut>,
+        at: Id<SceneVariant>,
+    ) -> Result<(), CutError> {
+        self.cut_mut(cut_id)?.clear_choice(at);
+        Ok(())
+    }
+
+    /// Returns the story as the cut tells it, as each scene paired with the
+    /// variant of it that the cut visits.
+    ///
+    /// # Errors
+    ///
+    /// - [`CutError::UnknownCut`] if the cut does not exist.
+    /// - [`CutError::Narrative`] with the error from
+    ///   [`Narrative::linearize_from`] if the cut's path cannot be followed.
+    pub fn linearize_cut(&self, cut_id: Id<Cut>) -> Result<Vec<(&Scene, &SceneVariant)>, CutError> {
+        let cut = self.cuts.get(&cut_id).ok_or(CutError::UnknownCut(cut_id))?;
+        Ok(self.narrative.linearize_from(cut.root(), &cut.policy())?)
+    }
+
+    /// Returns the cuts that removing the scene with
+    /// [`Narrative::remove_scene`] would break, sorted by ID.
+    ///
+    /// A cut breaks if one of the scene's variants is its root, appears in
+    /// one of its choices, or lies on its path. Check before removing the
+    /// scene; afterwards [`Storyboard::broken_cuts`] can only report the cuts
+    /// left naming variants that are gone.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CutError::Narrative`] with [`NarrativeError::UnknownScene`]
+    /// if the scene does not exist.
+    pub fn cuts_broken_by_removing(&self, scene_id: Id<Scene>) -> Result<Vec<Id<Cut>>, CutError> {
+        let scene = self
+            .narrative
+            .scene(&scene_id)
+            .ok_or(NarrativeError::UnknownScene(scene_id))?;
+
+        let mut broken: Vec<_> = self
+            .cuts
+            .values()
+            .filter(|cut| {
+                scene.variant_ids().any(|v| cut.references(*v))
+                    || self
+                        .narrative
+                        .linearize_from(cut.root(), &cut.policy())
+                        .is_ok_and(|path| path.iter().any(|(s, _)| s.id() == scene_id))
+            })
+            .map(|cut| cut.id())
+            .collect();
+        broken.sort_by_key(|id| id.uuid());
+
+        Ok(broken)
+    }
+
+    /// Returns the cuts that can no longer be followed, sorted by ID.
+    ///
+    /// A cut is broken if it names a variant that is not in the narrative, or
+    /// if [`Storyboard::linearize_cut`] fails for it.
+    pub fn broken_cuts(&self) -> Vec<Id<Cut>> {
+        let graph = self.narrative.graph();
+        let mut broken: Vec<_> = self
+            .cuts
+            .values()
+            .filter(|cut| {
+                !graph.contains(cut.root())
+                    || cut
+                        .choices()
+                        .iter()
+                        .any(|(at, choice)| !graph.contains(*at) || !graph.contains(*choice))
+                    || self
+                        .narrative
+                        .linearize_from(cut.root(), &cut.policy())
+                        .is_err()
+            })
+            .map(|cut| cut.id())
+            .collect();
+        broken.sort_by_key(|id| id.uuid());
+
+        broken
+    }
+
+    pub(crate) fn authors_mut(&mut self) -> &mut HashMap<Id<Author>, Author> {
+        &mut self.authors
+    }
+
+    pub(crate) fn characters_mut(&mut self) -> &mut HashMap<Id<Character>, Character> {
+        &mut self.characters
+    }
+
+    pub(crate) fn relationships_mut(&mut self) -> &mut HashMap<Id<Relationship>, Relationship> {
+        &mut self.relationships
+    }
+
+    pub(crate) fn cuts_mut(&mut self) -> &mut HashMap<Id<Cut>, Cut> {
+        &mut self.cuts
+    }
+
+    /// Clears every lock in the storyboard, returning what was locked.
+    pub(crate) fn suspend_locks(&mut self) -> HashSet<LockedEntity> {
+        let mut locked = self.narrative.suspend_locks();
+        if std::mem::take(&mut self.metadata.locked) {
+            locked.insert(LockedEntity::Storyboard);
+        }
+
+        locked
+    }
+
+    /// Puts back the locks [`Storyboard::suspend_locks`] cleared, on whatever
+    /// is still there.
+    pub(crate) fn restore_locks(&mut self, locked: &HashSet<LockedEntity>) {
+        self.narrative.restore_locks(locked);
+        self.metadata.locked |= locked.contains(&LockedEntity::Storyboard);
+    }
+
+    /// Returns [`NarrativeError::Locked`] if the storyboard is locked.
+    fn check_unlocked(&self) -> Result<(), NarrativeError> {
+        match self.metadata.locked {
+            true => Err(NarrativeError::Locked(LockedEntity::Storyboard)),
+            false => Ok(()),
+        }
+    }
+
+    /// Returns [`NarrativeError::NotAdmin`] unless `author` is an admin author
+    /// of the storyboard.
+    fn check_admin(&self, author: Id<Author>) -> Result<(), NarrativeError> {
+        match self.authors.get(&author).is_some_and(Author::is_admin) {
+            true => Ok(()),
+            false => Err(NarrativeError::NotAdmin(author)),
+        }
+    }
+
+    /// Returns [`NarrativeError::AdminRequired`] if `author` is already an
+    /// admin author of the storyboard.
+    fn check_not_admin(&self, author: Id<Author>) -> Result<(), NarrativeError> {
+        match self.authors.get(&author).is_some_and(Author::is_admin) {
+            true => Err(NarrativeError::AdminRequired(author)),
+            false => Ok(()),
+        }
+    }
+
+    /// Returns the cut for changing, if the storyboard is unlocked.
+    fn cut_mut(&mut self, cut_id: Id<Cut>) -> Result<&mut Cut, CutError> {
+        self.check_unlocked()?;
+        self.cuts
+            .get_mut(&cut_id)
+            .ok_or(CutError::UnknownCut(cut_id))
+    }
+
+    /// Makes the change described by `change`, as the corresponding method would.
+    ///
+    /// Narrative changes are applied with [`Narrative::apply_update`].
+    ///
+    /// # Errors
+    ///
+    /// - [`ChangeError::Narrative`] with the error from
+    ///   [`Narrative::apply_update`] if a narrative change cannot be applied.
+    /// - [`ChangeError::Narrative`] with [`NarrativeError::Locked`] if the
+    ///   storyboard is locked and the change is to its own details.
+    /// - [`ChangeError::Character`] if a removed character cannot be removed,
+    ///   as for [`Storyboard::remove_character`].
+    /// - [`ChangeError::Relationship`] if an added relationship cannot be
+    ///   added, as for [`Storyboard::add_relationship`].
+    pub fn apply_change(&mut self, change: &StoryboardChange) -> Result<(), ChangeError> {
+        match change {
+            StoryboardChange::TitleUpdated(title) => self.update_title(title.clone())?,
+            StoryboardChange::TitleCleared => self.clear_title()?,
+            StoryboardChange::SummaryUpdated(summary) => self.update_summary(summary.clone())?,
+            StoryboardChange::TemplateUpdated(template) => {
+                self.update_template(template.clone())?
+            }
+            StoryboardChange::TemplateCleared => self.clear_template()?,
+            StoryboardChange::AuthorAdded(author) => self.add_author(author.clone())?,
+            StoryboardChange::AuthorRemoved(author_id) => self.remove_author(author_id)?,
+            StoryboardChange::CharacterAdded(character) => self.add_character(character.clone())?,
+            StoryboardChange::CharacterRemoved {
+                character,
+                lines_to,
+            } => {
+                match lines_to {
+                    Some(lines_to) => self.remove_character_reassigning(*character, *lines_to)?,
+                    None => self.remove_character(*character)?,
+                };
+            }
+            StoryboardChange::RelationshipAdded(relationship) => {
+                self.add_relationship(relationship.clone())?;
+            }
+            StoryboardChange::RelationshipRemoved(relationship_id) => {
+                self.check_unlocked()?;
+                self.relationships.remove(relationship_id);
+            }
+            StoryboardChange::CutSaved(cut) => {
+                self.check_unlocked()?;
+                self.cuts.insert(cut.id(), cut.clone());
+            }
+            StoryboardChange::CutDeleted(cut_id) => {
+                self.check_unlocked()?;
+                self.cuts.remove(cut_id);
+            }
+            StoryboardChange::RevisionOpened(color, note) => {
+                self.open_revision(*color, note.clone())?;
+            }
+            StoryboardChange::Locked(entity, note) => self.lock_entity(*entity, note.clone())?,
+            StoryboardChange::Unlocked { by, entity, note } => {
+                self.unlock_entity(*by, *entity, note.clone())?;
+            }
+            StoryboardChange::AdminSet { by, author, admin } => {
+                self.set_admin(*by, *author, *admin)?;
+            }
+            StoryboardChange::Narrative(update) => self.narrative.apply_update(update)?,
+        }
+
+        Ok(())
+    }
+}
+
+/// Keeps the locks [`Storyboard::suspend_locks`] cleared, and puts them back
+/// when dropped.
+struct SuspendedLocks<'a> {
+    storyboard: &'a mut Storyboard,
+    locked: HashSet<LockedEntity>,
+}
+
+impl<'a> SuspendedLocks<'a> {
+    fn new(storyboard: &'a mut Storyboard) -> Self {
+        let locked = storyboard.suspend_locks();
+        Self { storyboard, locked }
+    }
+}
+
+impl Drop for SuspendedLocks<'_> {
+    fn drop(&mut self) {
+        self.storyboard.restore_locks(&self.locked);
+    }
+}
+
+impl HasMetadata for Storyboard {
+    fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+    fn metadata_mut(&mut self) -> &mut Metadata {
+        &mut self.metadata
     }
 }
 
 impl Default for Storyboard {
-    /// Creates an empty `Storyboard` with no title, authors, characters, or
-    /// template, and a fresh narrative, summary, and metadata.
+    /// Creates an empty `Storyboard` with no title, authors, characters, cuts,
+    /// or template, and a fresh narrative, summary, and metadata.
     fn default() -> Self {
         Self {
             title: None,
             authors: HashMap::new(),
             characters: HashMap::new(),
             narrative: Narrative::default(),
+            relationships: HashMap::new(),
+            cuts: HashMap::new(),
             template: None,
             summary: Summary::default(),
+            revisions: Vec::new(),
             metadata: Metadata::new(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::models::{
+        Author, AuthorName, BranchPolicy, Character, CharacterError, CharacterName, Dialogue,
+        HasMetadata, Id, LockedEntity, NarrativeError, Relationship, RelationshipError,
+        RelationshipKind, RevisionColor, RevisionNote, Scene, SceneAction, SceneElement,
+        Storyboard, StoryboardChange, Title,
+    };
+
+    fn note(text: &str) -> RevisionNote {
+        RevisionNote::new(text).unwrap()
+    }
+
+    #[test]
+    fn test_a_locked_storyboard_refuses_changes_to_its_details() {
+        // ARRANGE
+        let mut storyboard = Storyboard::default();
+        storyboard.lock(note("Delivered to the studio."));
+        // ACT
+        let response = storyboard.update_title(Title::new("Rain").unwrap());
+        // ASSERT
+        assert_eq!(
+            response,
+            Err(NarrativeError::Locked(LockedEntity::Storyboard))
+        );
+        assert!(storyboard.title().is_none());
+        assert_eq!(storyboard.metadata().revision_notes.len(), 1);
+    }
+
+    #[test]
+    fn test_only_an_admin_author_can_override_locks() {
+        // ARRANGE
+        let admin = Author::new(AuthorName::new("Showrunner").unwrap());
+        let admin_id = admin.id();
+        let mut storyboard = Storyboard::with_admin(admin);
+        let scene = Scene::new();
+        let (scene_id, variant_id) = (scene.id(), *scene.active_variant());
+        let narrative = storyboard.narrative_mut();
+        narrative.add_scene(scene).unwrap();
+        narrative.lock_scene(scene_id, note("Locked.")).unwrap();
+        storyboard.lock(note("Locked."));
+        let stranger = Id::new();
+        // ACT
+        let refused = storyboard.override_locks(stranger, note("Let me in."), |_| ());
+        let overridden = storyboard
+            .override_locks(admin_id, note("Late fix."), |storyboard| {
+                storyboard.update_title(Title::new("Rain").unwrap())?;
+                storyboard.narrative_mut().set_variant_as_root(variant_id)
+            })
+            .unwrap();
+        // ASSERT
+        assert_eq!(refused, Err(NarrativeError::NotAdmin(stranger)));
+        assert!(overridden.is_ok());
+        assert!(storyboard.title().is_some());
+        assert!(storyboard.is_locked());
+        assert!(storyboard.narrative().scene(&scene_id).unwrap().is_locked());
+        assert!(
+            storyboard
+                .narrative_mut()
+                .remove_variant_as_root(variant_id)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_locks_are_restored_when_an_override_panics() {
+        // ARRANGE
+        let admin = Author::new(AuthorName::new("Showrunner").unwrap());
+        let admin_id = admin.id();
+        let mut storyboard = Storyboard::with_admin(admin);
+        let scene = Scene::new();
+        let scene_id = scene.id();
+        storyboard.narrative_mut().add_scene(scene).unwrap();
+        storyboard
+            .narrative_mut()
+            .lock_scene(scene_id, note("Locked."))
+            .unwrap();
+        storyboard.lock(note("Locked."));
+        // ACT
+        let response = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            storyboard.override_locks(admin_id, note("Late fix."), |_| panic!("edit failed"))
+        }));
+        // ASSERT
+        assert!(response.is_err());
+        assert!(storyboard.is_locked());
+        assert!(storyboard.narrative().scene(&scene_id).unwrap().is_locked());
+    }
+
+    #[test]
+    fn test_only_an_admin_author_can_unlock() {
+        // ARRANGE
+        let admin = Author::new(AuthorName::new("Showrunner").unwrap());
+        let admin_id = admin.id();
+        let mut storyboard = Storyboard::with_admin(admin);
+        let scene = Scene::new();
+        let scene_id = scene.id();
+        storyboard.narrative_mut().add_scene(scene).unwrap();
+        let locked = LockedEntity::Scene(scene_id);
+        storyboard
+            .apply_change(&StoryboardChange::Locked(locked, note("Locked.")))
+            .unwrap();
+        let stranger = Id::new();
+        // ACT
+        let refused = storyboard.unlock_entity(stranger, locked, note("Let me in."));
+        let unlocked = storyboard.apply_change(&StoryboardChange::Unlocked {
+            by: admin_id,
+            entity: locked,
+            note: note("Reshoot."),
+        });
+        // ASSERT
+        assert_eq!(refused, Err(NarrativeError::NotAdmin(stranger)));
+        assert!(unlocked.is_ok());
+        assert!(!storyboard.narrative().scene(&scene_id).unwrap().is_locked());
+    }
+
+    #[test]
+    fn test_only_an_admin_author_can_make_another_admin() {
+        // ARRANGE
+        let admin = Author::new(AuthorName::new("Showrunner").unwrap());
+        let admin_id = admin.id();
+        let mut storyboard = Storyboard::with_admin(admin);
+        let writer = Author::new(AuthorName::new("Staff Writer").unwrap());
+        let writer_id = writer.id();
+        storyboard.add_author(writer.clone()).unwrap();
+        let mut self_made = writer;
+        self_made.set_admin(true);
+        // ACT
+        let added = storyboard.add_author(self_made);
+        let refused = storyboard.set_admin(writer_id, writer_id, true);
+        let granted = storyboard.set_admin(admin_id, writer_id, true);
+        // ASSERT
+        assert_eq!(added, Err(NarrativeError::AdminRequired(writer_id)));
+        assert_eq!(refused, Err(NarrativeError::NotAdmin(writer_id)));
+        assert!(granted.is_ok());
+        assert_eq!(
+            storyboard.remove_author(&admin_id),
+            Err(NarrativeError::AdminRequired(admin_id))
+        );
+    }
+
+    #[test]
+    fn test_without_an_admin_no_author_can_become_one() {
+        // ARRANGE
+        let mut storyboard = Storyboard::default();
+        let writer = Author::new(AuthorName::new("Staff Writer").unwrap());
+        let writer_id = writer.id();
+        storyboard.add_author(writer).unwrap();
+        let mut self_made = Author::new(AuthorName::new("Interloper").unwrap());
+        self_made.set_admin(true);
+        let self_made_id = self_made.id();
+        // ACT
+        let added = storyboard.add_author(self_made);
+        let refused = storyboard.set_admin(writer_id, writer_id, true);
+        // ASSERT
+        assert_eq!(added, Err(NarrativeError::AdminRequired(self_made_id)));
+        assert_eq!(refused, Err(NarrativeError::NotAdmin(writer_id)));
+        assert!(storyboard.authors().iter().all(|a| !a.is_admin()));
+    }
+
+    #[test]
+    fn test_opening_a_revision_tracks_elements_changed_since() {
+        // ARRANGE
+        let mut storyboard = Storyboard::default();
+        let scene = Scene::new();
+        let variant_id = *scene.active_variant();
+        storyboard.narrative_mut().add_scene(scene).unwrap();
+        let version = storyboard.metadata().version;
+        // ACT
+        storyboard
+            .open_revision(RevisionColor::Blue, note("Blue pages."))
+            .unwrap();
+        let variant = storyboard.narrative_mut().variant_mut(&variant_id).unwrap();
+        let action = SceneElement::Action(SceneAction::new("Thunder.").unwrap());
+        variant.add_element(action.clone()).unwrap();
+        // ASSERT
+        let revision = storyboard.current_revision().unwrap();
+        let variant = storyboard
+            .narrative()
+            .scene_for_variant(&variant_id)
+            .unwrap()
+            .variants()
+            .get(&variant_id)
+            .unwrap();
+        assert_eq!(revision.color(), RevisionColor::Blue);
+        assert_eq!(revision.version(), version + 1);
+        assert_eq!(storyboard.metadata().version, version + 1);
+        assert!(revision.is_element_revised(variant, &action));
+        assert!(revision.is_variant_revised(variant));
+    }
+
+    #[test]
+    fn test_revision_colors_must_be_opened_in_order() {
+        // ARRANGE
+        let mut storyboard = Storyboard::default();
+        // ACT
+        let early = storyboard.open_revision(RevisionColor::Pink, note("Pink pages."));
+        storyboard
+            .open_revision(RevisionColor::Blue, note("Blue pages."))
+            .unwrap();
+        let again = storyboard.open_revision(RevisionColor::Blue, note("Blue again."));
+        let next = storyboard.open_revision(RevisionColor::Pink, note("Pink pages."));
+        // ASSERT
+        assert_eq!(
+            early,
+            Err(NarrativeError::RevisionOutOfOrder {
+                expected: RevisionColor::Blue,
+                found: RevisionColor::Pink
+            })
+        );
+        assert_eq!(
+            again,
+            Err(NarrativeError::RevisionOutOfOrder {
+                expected: RevisionColor::Pink,
+                found: RevisionColor::Blue
+            })
+        );
+        assert!(next.is_ok());
+        assert_eq!(storyboard.revisions().len(), 2);
+    }
+
+    #[test]
+    fn test_no_revision_set_opens_after_the_last_color() {
+        // ARRANGE
+        let mut storyboard = Storyboard::default();
+        let colors = std::iter::successors(Some(RevisionColor::Blue), |c| c.next());
+        for color in colors {
+            storyboard.open_revision(color, note("Pages.")).unwrap();
+        }
+        // ACT
+        let response = storyboard.open_revision(RevisionColor::White, note("Pages."));
+        // ASSERT
+        assert_eq!(
+            response,
+            Err(NarrativeError::RevisionColorsExhausted {
+                found: RevisionColor::White
+            })
+        );
+        assert_eq!(
+            storyboard.current_revision().map(|set| set.color()),
+            Some(RevisionColor::DoubleCherry)
+        );
+    }
+
+    #[test]
+    fn test_removing_a_speaking_character_fails_unless_their_lines_are_reassigned() {
+        // ARRANGE
+        let mut storyboard = Storyboard::default();
+        let mut kit = Character::new(CharacterName::new("Katherine").unwrap());
+        kit.add_alias(CharacterName::new("Kit").unwrap());
+        let ray = Character::new(CharacterName::new("Ray").unwrap());
+        let (kit_id, ray_id) = (kit.id(), ray.id());
+        storyboard.add_character(kit).unwrap();
+        storyboard.add_character(ray).unwrap();
+        let scene = Scene::new();
+        let (scene_id, variant_id) = (scene.id(), *scene.active_variant());
+        let narrative = storyboard.narrative_mut();
+        narrative.add_scene(scene).unwrap();
+        narrative.set_variant_as_root(variant_id).unwrap();
+        let dialogue = Dialogue::new(scene_id, kit_id);
+        let dialogue_id = dialogue.id();
+        narrative
+            .variant_mut(&variant_id)
+            .unwrap()
+            .add_element(SceneElement::Dialogue(dialogue))
+            .unwrap();
+        // ACT
+        let found = storyboard.character_by_name("KIT").map(Character::id);
+        let appearance = storyboard
+            .first_appearance(kit_id, variant_id, &BranchPolicy::default())
+            .unwrap()
+            .map(|(scene, variant)| (scene.id(), variant.id()));
+        let refused = storyboard.remove_character(kit_id);
+        let removed = storyboard.remove_character_reassigning(kit_id, ray_id);
+        // ASSERT
+        assert_eq!(found, Some(kit_id));
+        assert_eq!(appearance, Some((scene_id, variant_id)));
+        assert_eq!(refused, Err(CharacterError::HasDialogue(vec![dialogue_id])));
+        assert_eq!(removed.unwrap().id(), kit_id);
+        assert!(storyboard.character(&kit_id).is_none());
+        let variant = storyboard.narrative_mut().variant_mut(&variant_id).unwrap();
+        let SceneElement::Dialogue(dialogue) = &variant.elements()[0] else {
+            panic!("expected dialogue");
+        };
+        assert_eq!(dialogue.speaker(), ray_id);
+    }
+
+    #[test]
+    fn test_relationships_change_over_the_story() {
+        // ARRANGE
+        let mut storyboard = Storyboard::default();
+        let kit = Character::new(CharacterName::new("Kit").unwrap());
+        let ray = Character::new(CharacterName::new("Ray").unwrap());
+        let (kit_id, ray_id) = (kit.id(), ray.id());
+        storyboard.add_character(kit).unwrap();
+        storyboard.add_character(ray).unwrap();
+        let scenes: Vec<_> = (0..3).map(|_| Scene::new()).collect();
+        let variants: Vec<_> = scenes.iter().map(|s| *s.active_variant()).collect();
+        let scene_ids: Vec<_> = scenes.iter().map(Scene::id).collect();
+        let narrative = storyboard.narrative_mut();
+        for scene in scenes {
+            narrative.add_scene(scene).unwrap();
+        }
+        narrative.set_variant_as_root(variants[0]).unwrap();
+        narrative.link_variants(variants[0], variants[1]).unwrap();
+        narrative.link_variants(variants[1], variants[2]).unwrap();
+        let mut rivals = Relationship::new(kit_id, ray_id, RelationshipKind::Rival);
+        rivals.set_span(None, Some(scene_ids[2]));
+        let mut allies = Relationship::new(kit_id, ray_id, RelationshipKind::Ally);
+        allies.set_span(Some(scene_ids[2]), None);
+        let (rivals_id, allies_id) = (rivals.id(), allies.id());
+        // ACT
+        let refused = storyboard.add_relationship(Relationship::new(
+            kit_id,
+            kit_id,
+            RelationshipKind::Friend,
+        ));
+        storyboard.add_relationship(rivals).unwrap();
+        storyboard.add_relationship(allies).unwrap();
+        let as_of = |scene| {
+            storyboard
+                .relationships_as_of(scene, variants[0], &BranchPolicy::default())
+                .unwrap()
+                .into_iter()
+                .filter(|r| r.involves(kit_id))
+                .map(Relationship::id)
+                .collect::<Vec<_>>()
+        };
+        let (early, late) = (as_of(scene_ids[1]), as_of(scene_ids[2]));
+        storyboard.remove_character(ray_id).unwrap();
+        // ASSERT
+        assert_eq!(refused, Err(RelationshipError::SelfRelationship(kit_id)));
+        assert_eq!(early, [rivals_id]);
+        assert_eq!(late, [allies_id]);
+        assert!(storyboard.relationships_of(kit_id).is_empty());
+    }
+
+    #[test]
+    fn test_removing_a_bounding_scene_breaks_a_relationship() {
+        // ARRANGE
+        let mut storyboard = Storyboard::default();
+        let kit = Character::new(CharacterName::new("Kit").unwrap());
+        let ray = Character::new(CharacterName::new("Ray").unwrap());
+        let (kit_id, ray_id) = (kit.id(), ray.id());
+        storyboard.add_character(kit).unwrap();
+        storyboard.add_character(ray).unwrap();
+        let scenes: Vec<_> = (0..2).map(|_| Scene::new()).collect();
+        let scene_ids: Vec<_> = scenes.iter().map(Scene::id).collect();
+        for scene in scenes {
+            storyboard.narrative_mut().add_scene(scene).unwrap();
+        }
+        let mut rivals = Relationship::new(kit_id, ray_id, RelationshipKind::Rival);
+        rivals.set_span(None, Some(scene_ids[0]));
+        let mut allies = Relationship::new(kit_id, ray_id, RelationshipKind::Ally);
+        allies.set_span(Some(scene_ids[1]), None);
+        let rivals_id = rivals.id();
+        storyboard.add_relationship(rivals).unwrap();
+        storyboard.add_relationship(allies).unwrap();
+        // ACT
+        let preview = storyboard
+            .relationships_broken_by_removing(scene_ids[0])
+            .unwrap();
+        storyboard
+            .narrative_mut()
+            .remove_scene(scene_ids[0])
+            .unwrap();
+        // ASSERT
+        assert_eq!(preview, [rivals_id]);
+        assert_eq!(storyboard.broken_relationships(), [rivals_id]);
+        assert_eq!(
+            storyboard.relationships_broken_by_removing(scene_ids[0]),
+            Err(RelationshipError::UnknownScene(scene_ids[0]))
+        );
+    }
+}
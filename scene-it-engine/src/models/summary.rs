@@ -6,15 +6,9 @@ use crate::utils::{InputError, validate_input};
 /// Represent the summary of the user's story.
 /// By default, the summary is empty,
 /// unless provided during storyboard setup.
-#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+#[derive(Debug, Default, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct Summary(String);
 
-impl Default for Summary {
-    fn default() -> Self {
-        Self(String::new()) // TODO: Add timestamp to default title
-    }
-}
-
 impl Summary {
     pub fn new(input: &str) -> Result<Self, InputError> {
         Ok(Self(validate_input(input, None)?))
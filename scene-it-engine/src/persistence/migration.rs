@@ -0,0 +1,229 @@
+use std::collections::HashMap;
+
+use serde_json::{Map, Value, json};
+use uuid::Uuid;
+
+use crate::persistence::project::{CURRENT_FORMAT_VERSION, ProjectError};
+
+/// Upgrades a storyboard payload by one format version.
+type Migration = fn(Value) -> Result<Value, ProjectError>;
+
+/// The migration chain: the entry at index `n` upgrades version `n + 1` to `n + 2`.
+const MIGRATIONS: [Migration; CURRENT_FORMAT_VERSION as usize - 1] = [v1_to_v2, v2_to_v3, v3_to_v4];
+
+/// Upgrades a storyboard payload written at `version` to the current format.
+///
+/// `version` must lie between `1` and [`CURRENT_FORMAT_VERSION`].
+pub(crate) fn migrate(mut payload: Value, version: u32) -> Result<Value, ProjectError> {
+    for migration in &MIGRATIONS[version as usize - 1..] {
+        payload = migration(payload)?;
+    }
+
+    Ok(payload)
+}
+
+/// Returns a [`ProjectError::MalformedField`] for a version 1 field.
+fn malformed_v1(field: &str) -> ProjectError {
+    ProjectError::MalformedField {
+        version: 1,
+        field: field.to_string(),
+    }
+}
+
+/// Returns a [`ProjectError::MalformedField`] for a version 2 field.
+fn malformed_v2(field: &str) -> ProjectError {
+    ProjectError::MalformedField {
+        version: 2,
+        field: field.to_string(),
+    }
+}
+
+/// Returns a [`ProjectError::MalformedField`] for a version 3 field.
+fn malformed_v3(field: &str) -> ProjectError {
+    ProjectError::MalformedField {
+        version: 3,
+        field: field.to_string(),
+    }
+}
+
+/// Version 1 kept scenes in a top-level `scene_bank` and linked them in a
+/// `scene_graph` keyed by scene ids, with the summary held on each scene.
+///
+/// Version 2 moves both under `narrative`, links the graph by variant ids,
+/// and gives every variant its own summary. Each scene id in the old graph
+/// becomes the id of that scene's active variant, and every other variant
+/// joins the graph without edges, so it can be linked later.
+fn v1_to_v2(payload: Value) -> Result<Value, ProjectError> {
+    let Value::Object(mut storyboard) = payload else {
+        return Err(malformed_v1("storyboard"));
+    };
+    let Some(Value::Object(mut scenes)) = storyboard.remove("scene_bank") else {
+        return Err(malformed_v1("scene_bank"));
+    };
+    let Some(Value::Object(mut graph)) = storyboard.remove("scene_graph") else {
+        return Err(malformed_v1("scene_graph"));
+    };
+
+    let mut active_variants = HashMap::new();
+    let mut all_variants = Vec::new();
+    for (scene_id, scene) in &mut scenes {
+        let scene = scene
+            .as_object_mut()
+            .ok_or_else(|| malformed_v1("scene_bank"))?;
+        let active = scene
+            .get("active_variant")
+            .and_then(Value::as_str)
+            .ok_or_else(|| malformed_v1("active_variant"))?
+            .to_string();
+        let summary = scene.remove("summary").unwrap_or_else(|| json!(""));
+
+        let variants = scene
+            .get_mut("variants")
+            .and_then(Value::as_object_mut)
+            .ok_or_else(|| malformed_v1("variants"))?;
+        all_variants.extend(variants.keys().cloned());
+        for variant in variants.values_mut() {
+            variant
+                .as_object_mut()
+                .ok_or_else(|| malformed_v1("variants"))?
+                .entry("summary")
+                .or_insert_with(|| summary.clone());
+        }
+
+        active_variants.insert(scene_id.clone(), active);
+    }
+
+    let to_variant = |scene_id: &Value| {
+        scene_id
+            .as_str()
+            .and_then(|id| active_variants.get(id))
+            .cloned()
+            .ok_or_else(|| malformed_v1("scene_graph"))
+    };
+
+    let mut edges = Map::new();
+    if let Some(Value::Object(old_edges)) = graph.remove("edges") {
+        for (from, targets) in old_edges {
+            let from = to_variant(&json!(from))?;
+            let targets = targets
+                .as_array()
+                .ok_or_else(|| malformed_v1("scene_graph"))?
+                .iter()
+                .map(to_variant)
+                .collect::<Result<Vec<_>, _>>()?;
+            edges.insert(from, json!(targets));
+        }
+    }
+    for variant in all_variants {
+        edges.entry(variant).or_insert_with(|| json!([]));
+    }
+
+    let roots = graph
+        .remove("roots")
+        .unwrap_or_else(|| json!([]))
+        .as_array()
+        .ok_or_else(|| malformed_v1("scene_graph"))?
+        .iter()
+        .map(to_variant)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    storyboard.insert(
+        "narrative".to_string(),
+        json!({
+            "graph": { "edges": edges, "roots": roots },
+            "scenes": scenes,
+        }),
+    );
+
+    Ok(Value::Object(storyboard))
+}
+
+/// Version 2 stored each action as its bare text.
+///
+/// Version 3 gives every action a stable id alongside its text, so actions
+/// can be addressed like dialogue. Actions that already have an id are left
+/// alone, since a bare version 3 storyboard is read as version 2.
+fn v2_to_v3(mut payload: Value) -> Result<Value, ProjectError> {
+    let scenes = payload
+        .pointer_mut("/narrative/scenes")
+        .and_then(Value::as_object_mut)
+        .ok_or_else(|| malformed_v2("narrative"))?;
+
+    for scene in scenes.values_mut() {
+        let variants = scene
+            .get_mut("variants")
+            .and_then(Value::as_object_mut)
+            .ok_or_else(|| malformed_v2("variants"))?;
+        for variant in variants.values_mut() {
+            let elements = variant
+                .get_mut("elements")
+                .and_then(Value::as_array_mut)
+                .ok_or_else(|| malformed_v2("elements"))?;
+            for element in elements {
+                if let Some(action) = element.get_mut("Action")
+                    && let Value::String(text) = action
+                {
+                    *action = json!({ "id": Uuid::new_v4().to_string(), "text": text });
+                }
+            }
+        }
+    }
+
+    Ok(payload)
+}
+
+/// Version 3 kept the scene number read from an imported script in each
+/// scene's `scene_number` metadata property.
+///
+/// Version 4 keeps the narrative's `scene_numbers` in script order. The old
+/// numbers are moved there, ordered by number since version 3 recorded no
+/// order: `A1` before `1`, then `1A`, then `2`. Narratives that already have
+/// `scene_numbers` are left alone, since a bare version 4 storyboard is read
+/// as version 2.
+fn v3_to_v4(mut payload: Value) -> Result<Value, ProjectError> {
+    let narrative = payload
+        .get_mut("narrative")
+        .and_then(Value::as_object_mut)
+        .ok_or_else(|| malformed_v3("narrative"))?;
+    if narrative.contains_key("scene_numbers") {
+        return Ok(payload);
+    }
+
+    let scenes = narrative
+        .get_mut("scenes")
+        .and_then(Value::as_object_mut)
+        .ok_or_else(|| malformed_v3("scenes"))?;
+
+    let mut numbers = Vec::new();
+    for (scene_id, scene) in scenes.iter_mut() {
+        let number = scene
+            .pointer_mut("/metadata/properties")
+            .and_then(Value::as_object_mut)
+            .and_then(|properties| properties.remove("scene_number"));
+        if let Some(Value::String(number)) = number
+            && !number.trim().is_empty()
+        {
+            numbers.push((number.trim().to_uppercase(), scene_id.clone()));
+        }
+    }
+    numbers.sort_by_cached_key(|(number, _)| {
+        let prefix: String = number.chars().take_while(|c| !c.is_ascii_digit()).collect();
+        let rest = &number[prefix.len()..];
+        let digits: String = rest.chars().take_while(char::is_ascii_digit).collect();
+        let suffix = rest[digits.len()..].to_string();
+        (
+            digits.parse::<u64>().ok(),
+            prefix.is_empty(),
+            prefix,
+            suffix,
+        )
+    });
+
+    let numbers: Vec<_> = numbers
+        .into_iter()
+        .map(|(number, scene)| json!({ "number": number, "scene": scene }))
+        .collect();
+    narrative.insert("scene_numbers".to_string(), json!(numbers));
+
+    Ok(payload)
+}
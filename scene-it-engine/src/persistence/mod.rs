@@ -0,0 +1,16 @@
+//! Reading and writing project files.
+//!
+//! A project file is a JSON envelope holding the format version it was
+//! written with and the [`Storyboard`](crate::models::Storyboard) itself.
+//! Files written by older engines, including those that predate the
+//! envelope, are upgraded through a chain of migrations when they are read.
+//!
+//! [`ProjectStore`] saves project files atomically, with rotating backups
+//! and an autosave journal for recovering after a crash.
+
+mod migration;
+mod project;
+mod store;
+
+pub use project::{CURRENT_FORMAT_VERSION, ProjectError, from_json, to_json};
+pub use store::{DEFAULT_BACKUPS, Opened, ProjectStore, Source, StoreError};
@@ -0,0 +1,292 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{models::Storyboard, persistence::migration};
+
+/// The project format version written by this engine.
+///
+/// | Version | Layout                                                              |
+/// |---------|---------------------------------------------------------------------|
+/// | 1       | Bare storyboard with `scene_bank` and a `scene_graph` of scene ids  |
+/// | 2       | Storyboard with a `narrative` whose graph links variant ids         |
+/// | 3       | As version 2, with an id on every action                            |
+/// | 4       | As version 3, with scene numbers kept by the narrative              |
+pub const CURRENT_FORMAT_VERSION: u32 = 4;
+
+/// Errors that can occur while reading or writing a project file.
+#[derive(Debug, Serialize, PartialEq)]
+pub enum ProjectError {
+    /// The file is not valid JSON, or does not match the storyboard layout.
+    Json(String),
+    /// The file is neither an envelope nor a storyboard layout this engine knows.
+    UnrecognizedLayout,
+    /// The envelope names a format version that never existed.
+    UnsupportedVersion(u32),
+    /// The file was written by a newer engine than this one.
+    NewerVersion { found: u32, supported: u32 },
+    /// A field required to upgrade from the given version is missing or malformed.
+    MalformedField { version: u32, field: String },
+}
+
+impl From<serde_json::Error> for ProjectError {
+    fn from(error: serde_json::Error) -> Self {
+        ProjectError::Json(error.to_string())
+    }
+}
+
+/// The on-disk wrapper around a storyboard.
+#[derive(Serialize, Deserialize)]
+struct Envelope<T> {
+    format_version: u32,
+    storyboard: T,
+}
+
+/// Reads a project file, upgrading it to the current format if needed.
+///
+/// Files without an envelope are recognized by their layout: a top-level
+/// `scene_bank` marks version 1, and a top-level `narrative` marks a bare
+/// storyboard of version 2 or later, which is upgraded from version 2.
+///
+/// # Errors
+///
+/// - [`ProjectError::NewerVersion`] if the file was written by a newer engine.
+/// - [`ProjectError::UnsupportedVersion`] if the envelope's version is `0`.
+/// - [`ProjectError::UnrecognizedLayout`] if the layout cannot be identified.
+/// - [`ProjectError::MalformedField`] if a migration cannot find the data it upgrades.
+/// - [`ProjectError::Json`] if the file is not JSON, or the upgraded payload is
+///   not a valid storyboard.
+pub fn from_json(json: &str) -> Result<Storyboard, ProjectError> {
+    from_value(serde_json::from_str(json)?)
+}
+
+/// Reads a project file that has already been parsed as JSON.
+///
+/// See [`from_json`].
+pub(crate) fn from_value(value: Value) -> Result<Storyboard, ProjectError> {
+    let (version, payload) = unwrap_envelope(value)?;
+
+    if version == 0 {
+        return Err(ProjectError::UnsupportedVersion(version));
+    }
+    if version > CURRENT_FORMAT_VERSION {
+        return Err(ProjectError::NewerVersion {
+            found: version,
+            supported: CURRENT_FORMAT_VERSION,
+        });
+    }
+
+    let payload = migration::migrate(payload, version)?;
+    Ok(serde_json::from_value(payload)?)
+}
+
+/// Writes a storyboard as a project file in the current format.
+///
+/// # Errors
+///
+/// Returns [`ProjectError::Json`] if the storyboard cannot be serialized.
+pub fn to_json(storyboard: &Storyboard) -> Result<String, ProjectError> {
+    Ok(serde_json::to_string_pretty(&to_value(storyboard)?)?)
+}
+
+/// Builds the JSON value of a project file in the current format.
+pub(crate) fn to_value(storyboard: &Storyboard) -> Result<Value, ProjectError> {
+    let envelope = Envelope {
+        format_version: CURRENT_FORMAT_VERSION,
+        storyboard,
+    };
+
+    Ok(serde_json::to_value(&envelope)?)
+}
+
+/// Splits a parsed file into its format version and storyboard payload.
+fn unwrap_envelope(value: Value) -> Result<(u32, Value), ProjectError> {
+    let Value::Object(object) = value else {
+        return Err(ProjectError::UnrecognizedLayout);
+    };
+
+    if object.contains_key("format_version") {
+        let envelope: Envelope<Value> = serde_json::from_value(Value::Object(object))?;
+        return Ok((envelope.format_version, envelope.storyboard));
+    }
+
+    let version = if object.contains_key("scene_bank") {
+        1
+    } else if object.contains_key("narrative") {
+        2
+    } else {
+        return Err(ProjectError::UnrecognizedLayout);
+    };
+
+    Ok((version, Value::Object(object)))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use serde_json::{Value, json};
+
+    use crate::{
+        models::{BranchPolicy, HasMetadata, SceneElement},
+        persistence::{CURRENT_FORMAT_VERSION, ProjectError, from_json, to_json},
+    };
+
+    const LEGACY: &str = include_str!("../../../data/screenplay1.json");
+
+    #[test]
+    fn test_legacy_layout_is_upgraded() {
+        // ARRANGE & ACT
+        let storyboard = from_json(LEGACY).unwrap();
+        let narrative = storyboard.narrative();
+        let root = *narrative.graph().roots().iter().next().unwrap();
+        // ASSERT
+        assert_eq!(storyboard.title().as_ref().unwrap().as_str(), "Encryption");
+        assert_eq!(narrative.scenes().count(), 10);
+        assert!(narrative.scene_for_variant(&root).is_some());
+        assert_eq!(
+            narrative
+                .linearize_from(root, &BranchPolicy::default())
+                .unwrap()
+                .len(),
+            10
+        );
+        assert!(narrative.scenes().all(|scene| {
+            scene
+                .variants()
+                .values()
+                .all(|variant| !variant.summary().is_empty())
+        }));
+        assert!(narrative.scenes().all(|scene| {
+            scene
+                .variant_ids()
+                .all(|variant| narrative.graph().contains(*variant))
+        }));
+    }
+
+    #[test]
+    fn test_saved_project_round_trips() {
+        // ARRANGE
+        let storyboard = from_json(LEGACY).unwrap();
+        // ACT
+        let saved = to_json(&storyboard).unwrap();
+        let reloaded = from_json(&saved).unwrap();
+        // ASSERT
+        let saved_value: Value = serde_json::from_str(&saved).unwrap();
+        assert_eq!(saved_value["format_version"], json!(CURRENT_FORMAT_VERSION));
+        let resaved: Value = serde_json::from_str(&to_json(&reloaded).unwrap()).unwrap();
+        assert_eq!(resaved, saved_value);
+    }
+
+    #[test]
+    fn test_bare_current_storyboard_loads() {
+        // ARRANGE
+        let saved: Value =
+            serde_json::from_str(&to_json(&from_json(LEGACY).unwrap()).unwrap()).unwrap();
+        let bare = saved["storyboard"].to_string();
+        // ACT
+        let storyboard = from_json(&bare);
+        // ASSERT
+        assert!(storyboard.is_ok());
+    }
+
+    #[test]
+    fn test_version_2_actions_are_given_ids() {
+        // ARRANGE
+        let mut file: Value =
+            serde_json::from_str(&to_json(&from_json(LEGACY).unwrap()).unwrap()).unwrap();
+        file["format_version"] = json!(2);
+        for scene in file["storyboard"]["narrative"]["scenes"]
+            .as_object_mut()
+            .unwrap()
+            .values_mut()
+        {
+            for variant in scene["variants"].as_object_mut().unwrap().values_mut() {
+                for element in variant["elements"].as_array_mut().unwrap() {
+                    if let Some(action) = element.get_mut("Action") {
+                        *action = action["text"].clone();
+                    }
+                }
+            }
+        }
+        // ACT
+        let storyboard = from_json(&file.to_string()).unwrap();
+        // ASSERT
+        let ids: Vec<_> = storyboard
+            .narrative()
+            .scenes()
+            .flat_map(|scene| scene.variants().values())
+            .flat_map(|variant| variant.elements())
+            .filter(|element| matches!(element, SceneElement::Action(_)))
+            .map(|element| element.id())
+            .collect();
+        assert!(!ids.is_empty());
+        assert_eq!(ids.iter().collect::<HashSet<_>>().len(), ids.len());
+    }
+
+    #[test]
+    fn test_newer_file_is_rejected() {
+        // ARRANGE
+        let file = json!({ "format_version": CURRENT_FORMAT_VERSION + 1, "storyboard": {} });
+        // ACT
+        let result = from_json(&file.to_string());
+        // ASSERT
+        assert_eq!(
+            result.err(),
+            Some(ProjectError::NewerVersion {
+                found: CURRENT_FORMAT_VERSION + 1,
+                supported: CURRENT_FORMAT_VERSION,
+            })
+        );
+    }
+
+    #[test]
+    fn test_unknown_layout_is_rejected() {
+        // ARRANGE & ACT
+        let result = from_json(r#"{ "chapters": [] }"#);
+        // ASSERT
+        assert_eq!(result.err(), Some(ProjectError::UnrecognizedLayout));
+    }
+
+    #[test]
+    fn test_version_3_scene_numbers_move_to_the_narrative() {
+        // ARRANGE
+        let mut file: Value =
+            serde_json::from_str(&to_json(&from_json(LEGACY).unwrap()).unwrap()).unwrap();
+        file["format_version"] = json!(3);
+        let narrative = file["storyboard"]["narrative"].as_object_mut().unwrap();
+        narrative.remove("scene_numbers");
+        let numbered: Vec<_> = narrative["scenes"]
+            .as_object_mut()
+            .unwrap()
+            .iter_mut()
+            .zip(["2", "1a", "1"])
+            .map(|((id, scene), number)| {
+                scene["metadata"]["properties"]["scene_number"] = json!(number);
+                id.clone()
+            })
+            .collect();
+        // ACT
+        let storyboard = from_json(&file.to_string()).unwrap();
+        // ASSERT
+        let numbers: Vec<_> = storyboard
+            .narrative()
+            .scene_numbers()
+            .iter()
+            .map(|n| (n.number().as_str(), n.scene().unwrap().to_string()))
+            .collect();
+        assert_eq!(
+            numbers,
+            [
+                ("1", numbered[2].clone()),
+                ("1A", numbered[1].clone()),
+                ("2", numbered[0].clone()),
+            ]
+        );
+        assert!(
+            storyboard
+                .narrative()
+                .scenes()
+                .all(|scene| !scene.metadata().properties.contains_key("scene_number"))
+        );
+    }
+}
@@ -0,0 +1,534 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use std::{
+    fs::{self, File},
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    models::Storyboard,
+    persistence::project::{self, ProjectError},
+    utils::fnv1a,
+};
+
+/// How many backups a [`ProjectStore`] keeps unless told otherwise.
+pub const DEFAULT_BACKUPS: usize = 3;
+
+/// Errors that can occur while saving or opening a project.
+#[derive(Debug, Serialize, PartialEq)]
+pub enum StoreError {
+    /// The file system refused an operation. Holds the underlying error message.
+    Io(String),
+    /// A project file could not be read or written.
+    Project(ProjectError),
+    /// Neither the project file, its autosave journal, nor any backup exists.
+    NotFound,
+}
+
+impl From<io::Error> for StoreError {
+    fn from(error: io::Error) -> Self {
+        StoreError::Io(error.to_string())
+    }
+}
+
+impl From<ProjectError> for StoreError {
+    fn from(error: ProjectError) -> Self {
+        StoreError::Project(error)
+    }
+}
+
+impl From<serde_json::Error> for StoreError {
+    fn from(error: serde_json::Error) -> Self {
+        StoreError::Project(error.into())
+    }
+}
+
+/// Where an opened storyboard was read from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub enum Source {
+    /// The last clean save.
+    Save,
+    /// The autosave journal, which was made over the last clean save.
+    Journal,
+    /// The numbered backup, because the project file could not be read.
+    Backup(usize),
+}
+
+/// A storyboard read by [`ProjectStore::open`].
+pub struct Opened {
+    pub storyboard: Storyboard,
+    pub source: Source,
+}
+
+/// One autosave, written as a single line of the journal.
+#[derive(Serialize, Deserialize)]
+struct JournalEntry {
+    /// The [`fnv1a`] hash of the project file when the autosave was made, or
+    /// `None` if there was no project file.
+    #[serde(default)]
+    base: Option<u64>,
+    project: Value,
+}
+
+/// Saves and opens a project file, keeping it safe across crashes.
+///
+/// Alongside the project file at `path` the store keeps:
+///
+/// - `<path>.tmp`, the in-progress copy of a save. A save only replaces the
+///   project file once the copy is complete and flushed to disk, so a crash
+///   mid-save leaves the previous save untouched.
+/// - `<path>.bak1` to `<path>.bakN`, the previous saves, newest first.
+/// - `<path>.autosave`, a journal holding the newest autosave, which records
+///   a hash of the project file it was made over. A clean save clears it.
+///   When the project is opened, the journal wins over the project file if
+///   it was made over that very file.
+#[derive(Clone, Debug)]
+pub struct ProjectStore {
+    path: PathBuf,
+    backups: usize,
+}
+
+impl ProjectStore {
+    /// Creates a store for the project file at `path`, keeping
+    /// [`DEFAULT_BACKUPS`] backups.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            backups: DEFAULT_BACKUPS,
+        }
+    }
+
+    /// Sets how many previous saves to keep. Zero disables backups.
+    pub fn with_backups(mut self, backups: usize) -> Self {
+        self.backups = backups;
+        self
+    }
+
+    /// Returns the path of the project file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Saves the storyboard atomically and clears the autosave journal.
+    ///
+    /// The new contents are written to a temporary file and flushed to disk.
+    /// Only once that succeeds is the previous save, if any, rotated into the
+    /// backups and the temporary file renamed over the project file.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StoreError::Io`] if any file operation fails, or
+    /// [`StoreError::Project`] if the storyboard cannot be serialized.
+    pub fn save(&self, storyboard: &Storyboard) -> Result<(), StoreError> {
+        let contents = project::to_json(storyboard)?;
+
+        let temp = write_temp(&self.path, contents.as_bytes())?;
+        self.rotate_backups()?;
+        replace(&temp, &self.path)?;
+
+        match fs::remove_file(self.sibling("autosave")) {
+            Err(error) if error.kind() != io::ErrorKind::NotFound => Err(error.into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Replaces the autosave journal with the storyboard.
+    ///
+    /// The desktop app calls this periodically between clean saves. The
+    /// journal is replaced atomically, so it holds only the newest autosave
+    /// and a crash mid-write leaves the previous one in place.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StoreError::Io`] if the journal cannot be written, or
+    /// [`StoreError::Project`] if the storyboard cannot be serialized.
+    pub fn autosave(&self, storyboard: &Storyboard) -> Result<(), StoreError> {
+        let entry = JournalEntry {
+            base: fs::read(&self.path).ok().map(|contents| fnv1a(&contents)),
+            project: project::to_value(storyboard)?,
+        };
+        let mut line = serde_json::to_string(&entry)?;
+        line.push('\n');
+
+        write_atomic(&self.sibling("autosave"), line.as_bytes())?;
+
+        Ok(())
+    }
+
+    /// Opens the project, recovering from the journal or a backup if needed.
+    ///
+    /// The newest complete journal entry is used when it was made over the
+    /// project file as it is now, or when the project file is missing or
+    /// unreadable. Otherwise the project file is used. If neither can be
+    /// read, the backups are tried newest first.
+    ///
+    /// # Errors
+    ///
+    /// - [`StoreError::Project`] with [`ProjectError::NewerVersion`] if the
+    ///   project file was written by a newer engine. Neither the journal nor
+    ///   the backups are tried, as they would hide the newer work.
+    /// - [`StoreError::NotFound`] if there is nothing to open.
+    /// - The error from reading the project file if nothing else could be
+    ///   read either.
+    pub fn open(&self) -> Result<Opened, StoreError> {
+        let (saved, base) = match self.read_save() {
+            Err(error @ StoreError::Project(ProjectError::NewerVersion { .. })) => {
+                return Err(error);
+            }
+            Ok((storyboard, base)) => (Ok(storyboard), Some(base)),
+            Err(error) => (Err(error), None),
+        };
+
+        if let Some(entry) = self.read_journal()
+            && (saved.is_err() || entry.base == base)
+            && let Ok(storyboard) = project::from_value(entry.project)
+        {
+            return Ok(Opened {
+                storyboard,
+                source: Source::Journal,
+            });
+        }
+
+        let error = match saved {
+            Ok(storyboard) => {
+                return Ok(Opened {
+                    storyboard,
+                    source: Source::Save,
+                });
+            }
+            Err(error) => error,
+        };
+
+        for index in 1..=self.backups {
+            if let Ok(contents) = fs::read_to_string(self.sibling(&format!("bak{index}")))
+                && let Ok(storyboard) = project::from_json(&contents)
+            {
+                return Ok(Opened {
+                    storyboard,
+                    source: Source::Backup(index),
+                });
+            }
+        }
+
+        Err(error)
+    }
+
+    /// Reads the project file, returning it with its [`fnv1a`] hash.
+    fn read_save(&self) -> Result<(Storyboard, u64), StoreError> {
+        let contents = fs::read_to_string(&self.path).map_err(|error| match error.kind() {
+            io::ErrorKind::NotFound => StoreError::NotFound,
+            _ => error.into(),
+        })?;
+
+        Ok((project::from_json(&contents)?, fnv1a(contents.as_bytes())))
+    }
+
+    /// Returns the newest journal entry that was written completely.
+    fn read_journal(&self) -> Option<JournalEntry> {
+        let journal = fs::read_to_string(self.sibling("autosave")).ok()?;
+
+        journal
+            .lines()
+            .rev()
+            .find_map(|line| serde_json::from_str(line).ok())
+    }
+
+    /// Shifts each backup down one place and copies the project file into the first.
+    fn rotate_backups(&self) -> Result<(), StoreError> {
+        if self.backups == 0 || !self.path.exists() {
+            return Ok(());
+        }
+
+        for index in (1..self.backups).rev() {
+            let from = self.sibling(&format!("bak{index}"));
+            if from.exists() {
+                fs::rename(from, self.sibling(&format!("bak{}", index + 1)))?;
+            }
+        }
+        fs::copy(&self.path, self.sibling("bak1"))?;
+
+        Ok(())
+    }
+
+    /// Returns the path of a file kept next to the project file, named
+    /// `<project file name>.<extension>`.
+    fn sibling(&self, extension: &str) -> PathBuf {
+        sibling(&self.path, extension)
+    }
+}
+
+fn sibling(path: &Path, extension: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".");
+    name.push(extension);
+    path.with_file_name(name)
+}
+
+/// Replaces the file at `path` with `contents` so that a crash leaves either
+/// the old or the new contents in place, never a mix.
+fn write_atomic(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let temp = write_temp(path, contents)?;
+    replace(&temp, path)
+}
+
+/// Writes `contents` to the temporary file kept next to `path` and flushes it
+/// to disk, returning the temporary file's path.
+fn write_temp(path: &Path, contents: &[u8]) -> io::Result<PathBuf> {
+    let temp = sibling(path, "tmp");
+
+    let mut file = File::create(&temp)?;
+    file.write_all(contents)?;
+    file.sync_all()?;
+
+    Ok(temp)
+}
+
+/// Renames the flushed temporary file `temp` over the file at `path`.
+fn replace(temp: &Path, path: &Path) -> io::Result<()> {
+    fs::rename(temp, path)?;
+
+    // Persist the rename itself. Directories cannot be opened for syncing on
+    // every platform, so this is best effort.
+    if let Some(directory) = path.parent()
+        && let Ok(directory) = File::open(if directory.as_os_str().is_empty() {
+            Path::new(".")
+        } else {
+            directory
+        })
+    {
+        let _ = directory.sync_all();
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs::{self, OpenOptions},
+        io::Write,
+        path::PathBuf,
+    };
+
+    use uuid::Uuid;
+
+    use crate::{
+        models::{Storyboard, Title},
+        persistence::{
+            CURRENT_FORMAT_VERSION, ProjectError, ProjectStore, Source, StoreError, to_json,
+        },
+    };
+
+    /// A fresh, empty directory for one test.
+    fn scratch() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("scene-it-{}", Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn titled(title: &str) -> Storyboard {
+        let mut storyboard = Storyboard::default();
+        storyboard.update_title(Title::new(title).unwrap()).unwrap();
+        storyboard
+    }
+
+    fn title_of(storyboard: &Storyboard) -> &str {
+        storyboard.title().as_ref().unwrap().as_str()
+    }
+
+    #[test]
+    fn test_save_then_open_returns_the_save() {
+        // ARRANGE
+        let dir = scratch();
+        let store = ProjectStore::new(dir.join("story.json"));
+        // ACT
+        store.save(&titled("Draft")).unwrap();
+        let opened = store.open().unwrap();
+        // ASSERT
+        assert_eq!(opened.source, Source::Save);
+        assert_eq!(title_of(&opened.storyboard), "Draft");
+        assert!(!dir.join("story.json.tmp").exists());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_interrupted_save_keeps_the_previous_save() {
+        // ARRANGE
+        let dir = scratch();
+        let store = ProjectStore::new(dir.join("story.json"));
+        store.save(&titled("Draft")).unwrap();
+        let next = to_json(&titled("Revised")).unwrap();
+        // ACT: the process dies halfway through writing the temporary file.
+        fs::write(dir.join("story.json.tmp"), &next[..next.len() / 2]).unwrap();
+        let opened = store.open().unwrap();
+        // ASSERT
+        assert_eq!(opened.source, Source::Save);
+        assert_eq!(title_of(&opened.storyboard), "Draft");
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_backups_rotate_and_are_used_when_the_save_is_damaged() {
+        // ARRANGE
+        let dir = scratch();
+        let store = ProjectStore::new(dir.join("story.json")).with_backups(2);
+        for title in ["One", "Two", "Three", "Four"] {
+            store.save(&titled(title)).unwrap();
+        }
+        // ACT
+        fs::write(dir.join("story.json"), "{ \"format_version\": 2, \"stor").unwrap();
+        let opened = store.open().unwrap();
+        // ASSERT
+        assert_eq!(opened.source, Source::Backup(1));
+        assert_eq!(title_of(&opened.storyboard), "Three");
+        assert!(dir.join("story.json.bak2").exists());
+        assert!(!dir.join("story.json.bak3").exists());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_failed_save_leaves_the_backups_alone() {
+        // ARRANGE
+        let dir = scratch();
+        let store = ProjectStore::new(dir.join("story.json"));
+        store.save(&titled("One")).unwrap();
+        store.save(&titled("Two")).unwrap();
+        // ACT: the temporary file cannot be created.
+        fs::create_dir(dir.join("story.json.tmp")).unwrap();
+        let result = store.save(&titled("Three"));
+        let opened = store.open().unwrap();
+        // ASSERT
+        assert!(matches!(result, Err(StoreError::Io(_))));
+        assert_eq!(title_of(&opened.storyboard), "Two");
+        assert!(dir.join("story.json.bak1").exists());
+        assert!(!dir.join("story.json.bak2").exists());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_save_from_a_newer_engine_is_not_hidden_by_backups() {
+        // ARRANGE
+        let dir = scratch();
+        let store = ProjectStore::new(dir.join("story.json"));
+        store.save(&titled("One")).unwrap();
+        store.save(&titled("Two")).unwrap();
+        store.autosave(&titled("Autosaved")).unwrap();
+        let mut newer: serde_json::Value =
+            serde_json::from_str(&to_json(&titled("Three")).unwrap()).unwrap();
+        newer["format_version"] = (CURRENT_FORMAT_VERSION + 1).into();
+        // ACT
+        fs::write(dir.join("story.json"), newer.to_string()).unwrap();
+        let result = store.open();
+        // ASSERT
+        assert_eq!(
+            result.err(),
+            Some(StoreError::Project(ProjectError::NewerVersion {
+                found: CURRENT_FORMAT_VERSION + 1,
+                supported: CURRENT_FORMAT_VERSION,
+            }))
+        );
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_newer_autosave_is_recovered() {
+        // ARRANGE
+        let dir = scratch();
+        let store = ProjectStore::new(dir.join("story.json"));
+        store.save(&titled("Saved")).unwrap();
+        // ACT
+        store.autosave(&titled("Autosaved")).unwrap();
+        let opened = store.open().unwrap();
+        // ASSERT
+        assert_eq!(opened.source, Source::Journal);
+        assert_eq!(title_of(&opened.storyboard), "Autosaved");
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_autosave_made_over_an_earlier_save_is_ignored() {
+        // ARRANGE
+        let dir = scratch();
+        let store = ProjectStore::new(dir.join("story.json"));
+        store.save(&titled("Saved")).unwrap();
+        store.autosave(&titled("Autosaved")).unwrap();
+        let journal = fs::read(dir.join("story.json.autosave")).unwrap();
+        // ACT: the journal outlives the next save.
+        store.save(&titled("Resaved")).unwrap();
+        fs::write(dir.join("story.json.autosave"), journal).unwrap();
+        let opened = store.open().unwrap();
+        // ASSERT
+        assert_eq!(opened.source, Source::Save);
+        assert_eq!(title_of(&opened.storyboard), "Resaved");
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_autosaves_replace_the_journal() {
+        // ARRANGE
+        let dir = scratch();
+        let store = ProjectStore::new(dir.join("story.json"));
+        // ACT
+        for title in ["First", "Second", "Third"] {
+            store.autosave(&titled(title)).unwrap();
+        }
+        let journal = fs::read_to_string(dir.join("story.json.autosave")).unwrap();
+        let opened = store.open().unwrap();
+        // ASSERT
+        assert_eq!(journal.lines().count(), 1);
+        assert_eq!(title_of(&opened.storyboard), "Third");
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_interrupted_autosave_falls_back_to_the_last_complete_entry() {
+        // ARRANGE
+        let dir = scratch();
+        let store = ProjectStore::new(dir.join("story.json"));
+        store.autosave(&titled("First")).unwrap();
+        store.autosave(&titled("Second")).unwrap();
+        // ACT: the process dies partway through appending a third entry.
+        let mut journal = OpenOptions::new()
+            .append(true)
+            .open(dir.join("story.json.autosave"))
+            .unwrap();
+        journal
+            .write_all(b"{\"saved_at\":\"2999-01-01T00:00:00Z\",\"proj")
+            .unwrap();
+        let opened = store.open().unwrap();
+        // ASSERT
+        assert_eq!(opened.source, Source::Journal);
+        assert_eq!(title_of(&opened.storyboard), "Second");
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_clean_save_clears_the_journal() {
+        // ARRANGE
+        let dir = scratch();
+        let store = ProjectStore::new(dir.join("story.json"));
+        store.autosave(&titled("Autosaved")).unwrap();
+        // ACT
+        store.save(&titled("Saved")).unwrap();
+        let opened = store.open().unwrap();
+        // ASSERT
+        assert!(!dir.join("story.json.autosave").exists());
+        assert_eq!(opened.source, Source::Save);
+        assert_eq!(title_of(&opened.storyboard), "Saved");
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_opening_nothing_is_not_found() {
+        // ARRANGE
+        let dir = scratch();
+        let store = ProjectStore::new(dir.join("story.json"));
+        // ACT
+        let result = store.open();
+        // ASSERT
+        assert_eq!(result.err(), Some(StoreError::NotFound));
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
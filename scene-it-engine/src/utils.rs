@@ -1,4 +1,6 @@
-#[derive(Debug)]
+use serde::Serialize;
+
+#[derive(Debug, PartialEq, Serialize)]
 pub enum InputError {
     EmptyInput,
     TooManyChars,
@@ -22,3 +24,37 @@ pub fn validate_input(input: &str, size_limit: Option<usize>) -> Result<String,
 
     Ok(trimmed)
 }
+
+/// Validates text that may run over several lines, as [`validate_input`]
+/// validates each line, keeping the line breaks and dropping blank lines.
+pub fn validate_multiline_input(
+    input: &str,
+    size_limit: Option<usize>,
+) -> Result<String, InputError> {
+    let lines = input
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| validate_input(line, None))
+        .collect::<Result<Vec<_>, _>>()?;
+    let text = lines.join("\n");
+
+    if text.is_empty() {
+        return Err(InputError::EmptyInput);
+    }
+
+    if size_limit.is_some_and(|s| text.chars().count() > s) {
+        return Err(InputError::TooManyChars);
+    }
+
+    Ok(text)
+}
+
+/// Returns the 64-bit FNV-1a hash of `bytes`.
+///
+/// Unlike [`std::hash::Hash`], its output is the same across Rust releases,
+/// so it can be kept in saved files.
+pub(crate) fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}